@@ -0,0 +1,59 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use splitbits::{combinebits, replacebits, splitbits, splitbits_named};
+
+const ITERATIONS: u32 = 10_000_000;
+
+fn main() {
+    bench("splitbits u8", || {
+        let fields = splitbits!(black_box(0b1101_0110u8), "aaab bccc");
+        (fields.a, fields.b, fields.c)
+    });
+    bench("splitbits u32", || {
+        let fields = splitbits!(black_box(0xDEAD_BEEFu32), "aaaa aaaa bbbb bbbb bbbb cccc dddd dddd");
+        (fields.a, fields.b, fields.c, fields.d)
+    });
+    bench("splitbits u64 split field", || {
+        let fields = splitbits!(black_box(0x0123_4567_89AB_CDEFu64),
+            "aaaa aaaa bbbb bbbb aaaa aaaa cccc cccc dddd dddd dddd dddd eeee eeee eeee eeee");
+        (fields.a, fields.b, fields.c, fields.d, fields.e)
+    });
+    bench("splitbits bools", || {
+        let fields = splitbits!(black_box(0b1010_0110u8), "abcd efgh");
+        (fields.a, fields.b, fields.c, fields.d, fields.e, fields.f, fields.g, fields.h)
+    });
+    bench("splitbits_named u16", || {
+        let (a, b): (u8, u8) = splitbits_named!(black_box(0xA5C3u16), "aaaa aaaa bbbb bbbb");
+        (a, b)
+    });
+    bench("combinebits u32", || {
+        let a: u8 = black_box(0xDE);
+        let b: u16 = black_box(0xADB);
+        let c: u8 = black_box(0xE);
+        let d: u8 = black_box(0xEF);
+        combinebits!("aaaa aaaa bbbb bbbb bbbb cccc dddd dddd")
+    });
+    bench("replacebits u16", || {
+        let a: u8 = black_box(0x5);
+        let c: u8 = black_box(0x3);
+        replacebits!(black_box(0xFFFFu16), "aaaa .... cccc ....")
+    });
+}
+
+// Time many calls of f, printing the average duration of a single call.
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    // Warm up before timing.
+    for _ in 0..ITERATIONS / 10 {
+        black_box(f());
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+
+    let elapsed: Duration = start.elapsed();
+    let nanos = elapsed.as_secs_f64() * 1e9 / f64::from(ITERATIONS);
+    println!("{name:<28} {nanos:>8.3} ns/iter");
+}
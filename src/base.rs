@@ -2,6 +2,7 @@
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Base {
     Binary = 2,
+    Octal = 8,
     Hexadecimal = 16,
 }
 
@@ -10,7 +11,18 @@ impl Base {
     pub const fn bits_per_digit(self) -> usize {
         match self {
             Self::Binary => 1,
+            Self::Octal => 3,
             Self::Hexadecimal => 4,
         }
     }
+
+    // Convert a lower-case str into its corresponding Base.
+    pub fn parse(text: &str) -> Result<Base, String> {
+        Ok(match text {
+            "binary" => Base::Binary,
+            "octal" => Base::Octal,
+            "hexadecimal" => Base::Hexadecimal,
+            base => return Err(format!("'{base}' is an invalid base option. Options: 'binary', 'octal', 'hexadecimal'.")),
+        })
+    }
 }
@@ -44,13 +44,17 @@ impl Character {
         }
     }
 
-    // Convert the character to a unicode char.
-    pub const fn to_char(self) -> char {
+}
+
+impl fmt::Display for Character {
+    // Write the character as it would appear in a template, with multi-char names in angle brackets.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Placeholder => '.',
-            Self::Zero => '0',
-            Self::One  => '1',
-            Self::Name(name) => name.to_char(),
+            Self::Placeholder => write!(f, "."),
+            Self::Zero => write!(f, "0"),
+            Self::One  => write!(f, "1"),
+            Self::Name(name) if name.is_single_char() => write!(f, "{name}"),
+            Self::Name(name) => write!(f, "<{name}>"),
         }
     }
 }
@@ -65,35 +69,123 @@ impl Characters {
     /* Given a numeric Base, convert a str to a Characters type.
      * Strips out any spaces as those are for human-reability.
      * Converts non-binary literals into binary literals.
+     * Multi-char field names are written between angle brackets, and take up one digit, just like
+     * single-char field names: "<mode><mode>aa bbbb".
+     * Pipes separate bytes, and must fall on byte boundaries: "aaaa aaaa|bbbb cccc".
+     * Parenthesized groups are repeated by the count that follows them: "(ab){4}".
      */
     pub fn from_str(text: &str, base: Base) -> Self {
-        let characters: Vec<Character> = text.chars()
+        let mut characters = Vec::new();
+        let mut has_separators = false;
+        let expanded = Self::expand_groups(text);
+        let mut chars = expanded.chars();
+        while let Some(c) = chars.next() {
             // Spaces are only for human-readability.
-            .filter(|&c| c != ' ')
+            if c == ' ' {
+                continue;
+            }
+
+            // Pipes are for human-readability too, but they also check that each byte is complete.
+            if c == '|' {
+                assert!(!characters.is_empty() && characters.len() % 8 == 0,
+                    "Byte separator '|' must fall on a byte boundary in template '{text}', \
+                    but was found after {} bits.", characters.len());
+                has_separators = true;
+                continue;
+            }
+
             // Each template char needs to be repeated if we aren't working in base 2.
-            .flat_map(|c| {
-                let characters: Box<dyn Iterator<Item = Character>>;
-                if base == Base::Hexadecimal {
-                    if let Some(array) = Self::hex_digit_to_array(c) {
-                        characters = Box::new(array.into_iter());
-                        return characters;
-                    }
+            if base == Base::Hexadecimal {
+                if let Some(array) = Self::hex_digit_to_array(c) {
+                    characters.extend(array);
+                    continue;
+                }
+            }
+
+            if base == Base::Octal {
+                if let Some(array) = Self::octal_digit_to_array(c) {
+                    characters.extend(array);
+                    continue;
                 }
+            }
 
-                if let Ok(character) = Character::from_char(c) {
-                    characters = Box::new(std::iter::repeat(character).take(base.bits_per_digit()));
-                } else {
-                    panic!("Invalid template char '{c}' in template '{text}'.");
+            let character = if c == '<' {
+                let mut raw_name = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '>' {
+                        closed = true;
+                        break;
+                    }
+
+                    raw_name.push(c);
                 }
 
-                characters
-            })
-            .collect();
+                assert!(closed, "Unclosed '<' in template '{text}'. Multi-char field names must end with '>'.");
+                let name = Name::bracketed(&raw_name)
+                    .unwrap_or_else(|err| panic!("Invalid field name in template '{text}'. {err}"));
+                Character::Name(name)
+            } else if let Ok(character) = Character::from_char(c) {
+                character
+            } else {
+                panic!("Invalid template char '{c}' in template '{text}'.");
+            };
 
+            characters.extend(std::iter::repeat_n(character, base.bits_per_digit()));
+        }
+
+        assert!(!characters.is_empty(), "Template must not be empty, but was '{text}'.");
+        assert!(!has_separators || characters.len() % 8 == 0,
+            "The last byte of template '{text}' is incomplete. A template with byte separators ('|') \
+            must have a whole number of bytes, but has {} bits.", characters.len());
         assert!(characters.len() <= 128, "Template size was greater than 128 bits. Template: '{text}'");
         Self(characters)
     }
 
+    // Expand every repeated group in a template, innermost first: "1(ab){2}." becomes "1abab.".
+    pub fn expand_groups(text: &str) -> String {
+        // The outermost level of the template, followed by each group that is currently open.
+        let mut levels = vec![String::new()];
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '(' => levels.push(String::new()),
+                ')' => {
+                    assert!(levels.len() > 1, "Unbalanced ')' in template '{text}'.");
+                    let group = levels.pop().expect("A group level should have been pushed");
+                    assert!(chars.next() == Some('{'),
+                        "Group '({group})' in template '{text}' must be followed by a repeat count, such as '{{4}}'.");
+                    let mut raw_count = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+
+                        raw_count.push(c);
+                    }
+
+                    assert!(closed, "Unclosed '{{' in template '{text}'. Repeat counts must end with '}}'.");
+                    let count: usize = raw_count.trim().parse()
+                        .unwrap_or_else(|_| panic!("Invalid repeat count '{raw_count}' in template '{text}'."));
+                    assert!(count > 0, "Repeat count of group '({group})' in template '{text}' must not be zero.");
+                    // Every digit (or bracketed name) is at least one bit, so this can't reject a valid group.
+                    let digit_count = crate::template::strip_non_digits(&group).chars()
+                        .filter(|&c| c != ' ' && c != '|')
+                        .count() + group.matches('<').count();
+                    assert!(digit_count.saturating_mul(count) <= 128,
+                        "Group '({group})' in template '{text}' is repeated {count} times, which is wider than 128 bits.");
+                    levels.last_mut().expect("The outermost level is never popped").push_str(&group.repeat(count));
+                }
+                c => levels.last_mut().expect("The outermost level is never popped").push(c),
+            }
+        }
+
+        assert!(levels.len() == 1, "Unbalanced '(' in template '{text}'.");
+        levels.pop().expect("The outermost level is never popped")
+    }
+
     /* Get the literal that the template corresponds to.
      * Effectively, return the '1's among the Characters, converting everything else to '0's.
      * Return None if there are no literal digits in the template.
@@ -117,10 +209,12 @@ impl Characters {
         u128::from_str_radix(&literal_string, 2).expect("All digits should be '0' or '1'")
     }
 
-    // Return true if there are any periods among the Characters.
-    pub fn has_placeholders(&self) -> bool {
-        self.0.iter()
-            .any(|&character| character == Character::Placeholder)
+    // Return '1's where there is a placeholder Character, '0's everywhere else.
+    pub fn placeholder_mask(&self) -> u128 {
+        let placeholder_string: String = self.0.iter()
+            .map(|&c| if c == Character::Placeholder { '1' } else { '0' })
+            .collect();
+        u128::from_str_radix(&placeholder_string, 2).expect("All digits should be '0' or '1'")
     }
 
     // Extract all the unique names that are present in the Characters.
@@ -163,12 +257,34 @@ impl Characters {
 
         Some([conv(n & 0b1000), conv(n & 0b0100), conv(n & 0b0010), conv(n & 0b0001)])
     }
+
+    // Convert an octal digit char into its 3 corresponding binary Characters.
+    const fn octal_digit_to_array(digit: char) -> Option<[Character; 3]> {
+        const fn conv(value: u32) -> Character {
+            if value == 0 { Character::Zero } else { Character::One }
+        }
+
+        let n = match digit {
+            '0'..='7' => digit as u32 - '0' as u32,
+            _ => return None,
+        };
+
+        Some([conv(n & 0b100), conv(n & 0b010), conv(n & 0b001)])
+    }
+
+    // Add placeholders to the left of the Characters until they are the specified width.
+    // Returns how many were added.
+    pub fn pad_to_width(&mut self, width: u8) -> u8 {
+        let padding = width - self.width();
+        self.0.splice(0..0, std::iter::repeat_n(Character::Placeholder, usize::from(padding)));
+        padding
+    }
 }
 
 impl fmt::Display for Characters {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for &character in &self.0 {
-            write!(f, "{}", character.to_char())?;
+            write!(f, "{character}")?;
         }
 
         Ok(())
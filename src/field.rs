@@ -1,13 +1,13 @@
 use std::collections::BTreeMap;
 
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::Expr;
+use proc_macro2::{TokenStream, Span};
+use quote::{quote, format_ident};
+use syn::{Expr, ExprArray, LitInt};
 
 use crate::name::Name;
-use crate::location::Location;
+use crate::location::{Location, BitNumbering};
 use crate::segment::Segment;
-use crate::r#type::{Type, Precision};
+use crate::r#type::{Type, Precision, Float};
 
 /* A bit field to be extracted from input in accordance with a Template.
  * A Field can be split over multiple segments in different locations in the Template.
@@ -17,6 +17,28 @@ pub struct Field {
     name: Name,
     segments: Vec<Segment>,
     bit_width: Type,
+    // Whether the bits of the Field should be flipped after extraction (e.g. for active-low bits).
+    inverted: bool,
+    // Whether the Field is a two's complement signed integer, which is sign-extended after extraction.
+    twos_complement: bool,
+    // How the extracted bits are converted into the Field's type.
+    conversion: Conversion,
+    // Whether failed runtime checks return an Err from the enclosing try_ macro, rather than panicking.
+    fallible: bool,
+    // The float type to produce if the Field should be a ratio of its maximum value (0.0 to 1.0).
+    ratio: Option<Float>,
+    // The range that the Field's value must fall within, checked after extraction.
+    range: Option<Expr>,
+    // Whether the Field should be converted to an array of big-endian bytes after extraction.
+    bytes: bool,
+    // The table (and its element type) that the extracted value indexes into, if any.
+    lookup: Option<(ExprArray, TokenStream)>,
+    // The 1-bit Field that holds the sign, if this Field is the magnitude of a sign-and-magnitude value.
+    sign: Option<Box<Field>>,
+    // The offset to add to the extracted value to get a char's code point, if the Field is a char.
+    char_offset: Option<u32>,
+    // The function (and its return type) that the extracted value is passed through, if any.
+    map_into: Option<(Expr, TokenStream)>,
 }
 
 impl Field {
@@ -52,23 +74,360 @@ impl Field {
             bit_width = std::cmp::max(bit_width, min_size);
         }
 
-        Self { name, segments, bit_width }
+        Self {
+            name,
+            segments,
+            bit_width,
+            inverted: false,
+            twos_complement: false,
+            conversion: Conversion::Checked,
+            fallible: false,
+            ratio: None,
+            range: None,
+            bytes: false,
+            lookup: None,
+            sign: None,
+            char_offset: None,
+            map_into: None,
+        }
     }
 
     // Convert the Field into its macro expansion format, either "bool" or "uX".
     pub fn to_token_stream(&self) -> TokenStream {
+        let mut value = self.extracted_value();
+        if let Some((table, t)) = &self.lookup {
+            let len = table.elems.len();
+            value = quote! {
+                {
+                    const TABLE: [#t; #len] = #table;
+                    TABLE[(#value) as usize]
+                }
+            };
+        }
+
+        if let Some(sign) = &self.sign {
+            let t = self.signed_type();
+            let sign = sign.to_token_stream();
+            value = quote! {
+                {
+                    let magnitude = (#value) as #t;
+                    if #sign { -magnitude } else { magnitude }
+                }
+            };
+        }
+
+        if let Some(offset) = self.char_offset {
+            value = self.char_value(&value, offset);
+        }
+
+        if self.twos_complement {
+            value = self.sign_extended_value(&value);
+        }
+
+        if let Some((function, _)) = &self.map_into {
+            value = quote! { (#function)(::core::convert::Into::into(#value)) };
+        }
+
+        if let Some(range) = &self.range {
+            let name = self.name.to_string();
+            let range_string = quote! { #range }.to_string();
+            let failure = crate::runtime_failure(self.fallible, &quote! {
+                "Field '{}' must be within its range ({}), but was {}.", #name, #range_string, value
+            });
+            value = quote! {
+                {
+                    let value = #value;
+                    if !(#range).contains(&value) {
+                        #failure;
+                    }
+
+                    value
+                }
+            };
+        }
+
+        if self.bytes {
+            value = quote! { (#value).to_be_bytes() };
+        }
+
+        value
+    }
+
+    // The extracted value of the Field, before any range check.
+    fn extracted_value(&self) -> TokenStream {
+        if let Some(value) = self.literal_value() {
+            return value;
+        }
+
         let t = self.bit_width.to_token_stream();
         let mut segments = self.segments.iter().map(Segment::to_token_stream);
-        if self.bit_width == Type::Bool {
+        if let Some(float) = self.ratio {
+            let input_type = self.segments[0].input_type().to_token_stream();
+            let max = u128::MAX >> (128 - self.width());
+            let mut value = quote! { #(#segments)|* };
+            if self.inverted {
+                value = quote! { (#value) ^ (#max as #input_type) };
+            }
+
+            // The segments are already masked, so the value is never more than the max.
+            let float = float.to_token_stream();
+            quote! { (#value) as #float / #max as #float }
+        } else if self.bit_width == Type::Bool {
             let segment = segments.next()
                 .expect("At least one Segment should be present in a Field.");
-            quote! { (#segment) != 0 }
+            if self.inverted {
+                quote! { (#segment) == 0 }
+            } else {
+                quote! { (#segment) != 0 }
+            }
         } else {
-            // TODO: Is there a good expect() message we could use here?
-            quote! { #t::try_from(#(#segments)|*).unwrap() }
+            let mut value = quote! { #(#segments)|* };
+            if self.inverted {
+                // Only flip the bits within the Field's width, not the full width of the input type.
+                let input_type = self.segments[0].input_type().to_token_stream();
+                let mask = u128::MAX >> (128 - self.width());
+                value = quote! { (#value) ^ (#mask as #input_type) };
+            }
+
+            match self.conversion {
+                // Widening into a standard type can't fail, so there's no need for a panic path.
+                Conversion::Checked if self.bit_width.is_standard()
+                        && self.segments[0].input_type().bit_count() <= self.bit_width.bit_count() =>
+                    quote! { #t::from(#value) },
+                // arbitrary-int types only convert from their own underlying type, through new().
+                // The segments are already masked to the Field's width, so new() can't panic.
+                Conversion::Checked | Conversion::Fallible
+                        if cfg!(feature = "arbitrary-int") && !self.bit_width.is_standard() => {
+                    let underlying = format_ident!("u{}", self.bit_width.bit_count().next_power_of_two().max(8));
+                    quote! { #t::new((#value) as #underlying) }
+                }
+                // TODO: Is there a good expect() message we could use here?
+                Conversion::Checked => quote! { #t::try_from(#value).unwrap() },
+                Conversion::Fallible => {
+                    let message = format!("Field '{}' doesn't fit in its type: {{}}", self.name);
+                    let failure = crate::runtime_failure(true, &quote! { #message, err });
+                    quote! {
+                        match #t::try_from(#value) {
+                            ::core::result::Result::Ok(value) => value,
+                            ::core::result::Result::Err(err) => #failure,
+                        }
+                    }
+                }
+                // The segments are already masked to the Field's width, so the cast can't lose bits.
+                Conversion::Masked => quote! { (#value) as #t },
+            }
+        }
+    }
+
+    /* The Field's value as a literal, if it can be computed during macro expansion because the
+     * input is an integer literal. Only bools and standard integer types are supported.
+     */
+    fn literal_value(&self) -> Option<TokenStream> {
+        if self.ratio.is_some() || !self.bit_width.is_standard() {
+            return None;
+        }
+
+        let mut value = 0;
+        for segment in &self.segments {
+            value |= segment.literal_value()?;
+        }
+
+        if self.inverted {
+            value ^= u128::MAX >> (128 - self.width());
+        }
+
+        Some(if self.bit_width == Type::Bool {
+            let value = value != 0;
+            quote! { #value }
+        } else {
+            let literal = LitInt::new(&format!("{value}{}", self.bit_width), Span::call_site());
+            quote! { #literal }
+        })
+    }
+
+    // Flip each bit of the Field after it is extracted.
+    pub fn invert(mut self) -> Self {
+        self.inverted = true;
+        self
+    }
+
+    // Produce the Field as a two's complement signed integer of the same width as its unsigned type.
+    pub fn twos_complement(mut self) -> Self {
+        assert!(self.ratio.is_none() && self.lookup.is_none() && self.sign.is_none() && !self.bytes
+            && self.char_offset.is_none(),
+            "Setting 'signed' must not be combined with 'ratio', 'lookup', 'sign_magnitude', 'bytes', or 'char' \
+            for field '{}'.", self.name);
+        assert!(self.bit_width.is_standard(),
+            "Setting 'signed' is only supported for standard types, but field '{}' is a {}.", self.name, self.bit_width);
+        self.twos_complement = true;
+        self
+    }
+
+    /* Sign-extend an extracted value from the Field's width to the width of its signed type: shift
+     * the Field's sign bit up to the top of the type, then arithmetic shift it back down. A 1-bit
+     * Field (a bool) is either 0 or -1.
+     */
+    fn sign_extended_value(&self, value: &TokenStream) -> TokenStream {
+        let t = self.twos_complement_type();
+        if self.bit_width == Type::Bool {
+            return quote! { -#t::from(#value) };
+        }
+
+        let shift = u32::from(self.bit_width.bit_count() - self.width());
+        if shift == 0 {
+            quote! { ((#value) as #t) }
+        } else {
+            quote! { ((((#value) as #t) << #shift) >> #shift) }
+        }
+    }
+
+    // The signed type for a two's complement Field: the same width as its unsigned type (i8 for bool).
+    fn twos_complement_type(&self) -> TokenStream {
+        let bit_count = if self.bit_width == Type::Bool { 8 } else { self.bit_width.bit_count() };
+        let t = format_ident!("i{bit_count}");
+        quote! { #t }
+    }
+
+    // Convert the extracted bits into the Field's type with a cast, rather than a checked conversion.
+    pub fn masked(mut self) -> Self {
+        assert!(self.bit_width.is_standard(),
+            "Setting 'conversion=masked' is only supported for standard types, but field '{}' is a {}.",
+            self.name, self.bit_width);
+        self.conversion = Conversion::Masked;
+        self
+    }
+
+    // Return an Err from the enclosing try_splitbits! if a runtime check fails, rather than panicking.
+    pub fn fallible(mut self) -> Self {
+        self.fallible = true;
+        if self.conversion == Conversion::Checked {
+            self.conversion = Conversion::Fallible;
+        }
+
+        self
+    }
+
+    /* Reverse which segments hold the low bits of the Field, so that the first (leftmost) segment in
+     * the Template holds the least significant bits, rather than the most significant bits.
+     */
+    pub fn lsb_first(mut self) -> Self {
+        let mut segment_offset = 0;
+        for segment in self.segments.iter_mut().rev() {
+            segment.set_field_offset(segment_offset);
+            segment_offset += segment.width();
+        }
+
+        self
+    }
+
+    // Convert the Field into a ratio of its maximum value, from 0.0 to 1.0.
+    pub fn ratio(mut self, float: Float) -> Self {
+        self.ratio = Some(float);
+        self
+    }
+
+    // Require the Field's value to fall within the specified range, panicking otherwise.
+    pub fn range(mut self, range: Expr) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    // Convert the Field to an array of big-endian bytes after it is extracted.
+    pub fn bytes(mut self) -> Self {
+        assert!(self.ratio.is_none() && self.bit_width != Type::Bool && self.bit_width.is_standard(),
+            "Setting 'bytes' is only supported for fields of type u8, u16, u32, u64, or u128, \
+            but field '{}' is a {}.", self.name, self.bit_width);
+        self.bytes = true;
+        self
+    }
+
+    /* Map the extracted value through a table, for fields whose raw values are indexes rather than
+     * the real values. The table must have an entry for every possible raw value.
+     */
+    pub fn lookup(mut self, table: ExprArray, element_type: TokenStream) -> Self {
+        assert!(self.ratio.is_none() && !self.bytes,
+            "Setting 'lookup' must not be combined with 'ratio' or 'bytes' for field '{}'.", self.name);
+        let expected = 1u128 << self.width();
+        assert!(table.elems.len() as u128 == expected,
+            "The lookup table for field '{}' must have exactly {expected} entries (one for each value of its \
+            {} bits), but had {}.", self.name, self.width(), table.elems.len());
+        self.lookup = Some((table, element_type));
+        self
+    }
+
+    /* Combine the Field (the magnitude) with a 1-bit sign Field into a signed integer that is wide
+     * enough for both of them, for formats that use sign-and-magnitude rather than two's complement.
+     */
+    pub fn sign_magnitude(mut self, sign: Field) -> Self {
+        assert!(sign.width() == 1,
+            "The sign field '{}' of setting 'sign_magnitude' must be a single bit, but has {} bits.",
+            sign.name, sign.width());
+        assert!(self.bit_width != Type::Bool && self.bit_width.is_standard(),
+            "The magnitude field '{}' of setting 'sign_magnitude' must have more than one bit.", self.name);
+        assert!(self.ratio.is_none() && self.lookup.is_none() && !self.bytes,
+            "Setting 'sign_magnitude' must not be combined with 'ratio', 'lookup', or 'bytes' for field '{}'.",
+            self.name);
+        self.sign = Some(Box::new(sign));
+        self
+    }
+
+    // Produce the Field as a char, whose code point is the extracted value plus an offset.
+    pub fn char(mut self, offset: u32) -> Self {
+        assert!(self.ratio.is_none() && self.lookup.is_none() && self.sign.is_none() && !self.bytes,
+            "Setting 'char' must not be combined with 'ratio', 'lookup', 'sign_magnitude', or 'bytes' for field '{}'.",
+            self.name);
+        assert!(self.width() <= 21, "Field '{}' of setting 'char' must be at most 21 bits wide, but has {} bits.",
+            self.name, self.width());
+        self.char_offset = Some(offset);
+        self
+    }
+
+    /* Pass the extracted value through a function (such as a constructor of a unit type) that
+     * returns the type t. The value is converted into the function's parameter type first.
+     */
+    pub fn map_into(mut self, function: Expr, t: TokenStream) -> Self {
+        assert!(self.ratio.is_none() && self.lookup.is_none() && self.sign.is_none() && !self.bytes
+            && self.char_offset.is_none(),
+            "Setting 'map_into' must not be combined with 'ratio', 'lookup', 'sign_magnitude', 'bytes', \
+            or 'char' for field '{}'.", self.name);
+        self.map_into = Some((function, t));
+        self
+    }
+
+    /* Convert an extracted value to a char. If every possible code point fits in a byte, then the
+     * conversion can't fail. Otherwise, a code point that isn't a valid char is a runtime failure.
+     */
+    fn char_value(&self, value: &TokenStream, offset: u32) -> TokenStream {
+        let max_code = u64::from(offset) + (u64::MAX >> (64 - self.width()));
+        if max_code <= 0xFF {
+            let offset = u8::try_from(offset).expect("The offset should be at most the max code point");
+            let offset = (offset != 0).then(|| quote! { + #offset });
+            return quote! { char::from(((#value) as u8) #offset) };
+        }
+
+        let offset = (offset != 0).then(|| quote! { + #offset });
+        let message = format!("Field '{}' must be a valid char, but had code point {{:#X}}.", self.name);
+        let failure = crate::runtime_failure(self.fallible, &quote! { #message, code });
+        quote! {
+            {
+                let code = ((#value) as u32) #offset;
+                match char::from_u32(code) {
+                    ::core::option::Option::Some(c) => c,
+                    ::core::option::Option::None => #failure,
+                }
+            }
         }
     }
 
+    // The signed type for a sign-and-magnitude Field: wide enough for the magnitude plus a sign bit.
+    fn signed_type(&self) -> TokenStream {
+        let with_sign = Type::for_field(self.width() + 1, Precision::Standard)
+            .expect("Field should be shorter than 256 characters");
+        let bit_count = std::cmp::max(with_sign, self.bit_width).bit_count();
+        let t = format_ident!("i{bit_count}");
+        quote! { #t }
+    }
+
     // Merge two collections of fields into one, removing duplicates.
     pub fn merge(upper: &[Self], lower: &[Self]) -> Vec<Self> {
         let lower_map: BTreeMap<_, _> = lower.iter()
@@ -119,9 +478,30 @@ impl Field {
             name: self.name,
             segments: new_segments,
             bit_width,
+            inverted: self.inverted,
+            twos_complement: self.twos_complement,
+            conversion: self.conversion,
+            fallible: self.fallible,
+            ratio: self.ratio,
+            range: self.range.clone(),
+            bytes: self.bytes,
+            lookup: self.lookup.clone(),
+            sign: self.sign.clone(),
+            char_offset: self.char_offset,
+            map_into: self.map_into.clone(),
         }
     }
 
+    // Extract the Field from a different input expression, keeping all of its other properties.
+    pub fn with_input(&self, input: &Expr) -> Self {
+        let mut field = self.clone();
+        for segment in &mut field.segments {
+            segment.set_input(input.clone());
+        }
+
+        field
+    }
+
     // Widen all segments.
     pub fn widen(mut self, new_bit_width: Type) -> Self {
         self.bit_width = new_bit_width;
@@ -142,6 +522,104 @@ impl Field {
         self.bit_width
     }
 
+    // The type of the Field as it will appear in the macro expansion.
+    pub fn output_type(&self) -> TokenStream {
+        if self.bytes {
+            let count = usize::from(self.bit_width.bit_count() / 8);
+            return quote! { [u8; #count] };
+        }
+
+        if let Some((_, t)) = &self.lookup {
+            return t.clone();
+        }
+
+        if self.sign.is_some() {
+            return self.signed_type();
+        }
+
+        if self.char_offset.is_some() {
+            return quote! { char };
+        }
+
+        if let Some((_, t)) = &self.map_into {
+            return t.clone();
+        }
+
+        if self.twos_complement {
+            return self.twos_complement_type();
+        }
+
+        self.ratio.map_or_else(|| self.bit_width.to_token_stream(), Float::to_token_stream)
+    }
+
+    // Whether the Field will be a ratio (float) rather than an integer or bool.
+    pub const fn is_ratio(&self) -> bool {
+        self.ratio.is_some()
+    }
+
+    // Whether the Field is a signed integer combined from a magnitude and a sign.
+    pub const fn is_signed(&self) -> bool {
+        self.sign.is_some()
+    }
+
+    // Whether the Field will be a char rather than an integer.
+    pub const fn is_char(&self) -> bool {
+        self.char_offset.is_some()
+    }
+
+    // Whether the Field is a two's complement signed integer.
+    pub const fn is_twos_complement(&self) -> bool {
+        self.twos_complement
+    }
+
+    // Whether the Field will be the result of a function rather than an integer.
+    pub const fn is_mapped(&self) -> bool {
+        self.map_into.is_some()
+    }
+
+    // Whether the Field will be an array of bytes rather than an integer.
+    pub const fn is_bytes(&self) -> bool {
+        self.bytes
+    }
+
+    // Whether the Field's value comes from a lookup table rather than directly from its bits.
+    pub const fn is_lookup(&self) -> bool {
+        self.lookup.is_some()
+    }
+
+    // The bit ranges that the Field occupies within its Template, high bits first (e.g. "7:6, 1:0").
+    pub fn bit_ranges(&self, numbering: BitNumbering, template_width: u8) -> String {
+        self.segments.iter()
+            .rev()
+            .map(|segment| segment.location().to_range_string(numbering, template_width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // How far the lowest bit of the Field is from the low bit of its Template.
+    pub fn offset(&self) -> u8 {
+        self.segments.iter()
+            .map(|segment| segment.location().mask_offset())
+            .min()
+            .expect("Fields should always have at least one segment")
+    }
+
+    /* The offset of the Field as numbered within a template of the specified width. For MSB-0
+     * numbering, that's how far the highest bit of the Field is from the high bit of its Template.
+     */
+    pub fn numbered_offset(&self, numbering: BitNumbering, template_width: u8) -> u8 {
+        match numbering {
+            BitNumbering::Lsb0 => self.offset(),
+            BitNumbering::Msb0 => {
+                let high = self.segments.iter()
+                    .map(|segment| segment.location().mask_offset() + segment.width() - 1)
+                    .max()
+                    .expect("Fields should always have at least one segment");
+                template_width - 1 - high
+            }
+        }
+    }
+
     // TODO: Determine how this is used differently from bit_width().
     pub fn width(&self) -> u8 {
         self.segments.iter()
@@ -149,3 +627,26 @@ impl Field {
             .sum()
     }
 }
+
+// How the extracted bits of a Field are converted into the Field's type.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Conversion {
+    // Use try_from().unwrap(), which would panic if the extracted bits didn't fit.
+    Checked,
+    // Use an 'as' cast, which can't fail since the extracted bits are always masked to fit.
+    Masked,
+    // Use try_from(), returning an Err from the enclosing try_splitbits! if the bits didn't fit.
+    Fallible,
+}
+
+impl Conversion {
+    // Convert a lower-case str into its corresponding Conversion value.
+    pub fn parse(text: &str) -> Result<Conversion, String> {
+        Ok(match text {
+            "checked" => Conversion::Checked,
+            "masked" => Conversion::Masked,
+            conversion => return Err(format!(
+                "'{conversion}' is an invalid conversion option. Options: 'checked', 'masked'.")),
+        })
+    }
+}
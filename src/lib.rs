@@ -5,14 +5,30 @@
 // ** Add optimization passes for performance and clarity.
 // ** Fix combinebits! from failing when the template width is less than an input width.
 // * Extract argument parsing.
+// ** Benchmark macro expansion time and size once the parser can be called outside of a proc-macro.
 // * Ensure overflow behavior usability in const contexts.
-// * Add base 8, base 32, and base 64.
+// ** Blocked on stable const traits: inputs are widened with From::from (which rejects inputs
+// wider than the template at compile time, and accepts ux inputs) and non-literal inputs are bound
+// with clone() (which dereferences borrowed inputs). 'as' casts would be const, but would lose all
+// three. Only then would the formatted overflow=panic message need to become a static one.
+// * Add base 32 and base 64.
 // ** Add build-your-own splitbits with other Bases.
-// * Enable splitbits to fail if literal pattern not matched
 // * Allow const variable templates.
 // * Allow non-const variable templates (as a separate macro).
-// * Allow non-standard template lengths.
-// * Add splitbits_capture.
+// ** A runtime API (string templates and values) must live in a separate, non-proc-macro crate,
+// since a proc-macro crate can't export runtime items. Its value parser should accept "0x"
+// prefixes and '_' or ' ' delimiters in pasted hex values, returning an Err for invalid values.
+// Its combine counterpart is only needed for runtime templates, since combinebits! already takes
+// (name, value) pairs from a runtime map with the map setting. It should take an overflow policy,
+// reuse Location to place each field, and return an Err for unknown names or overflows.
+// ** Its split function, splitbits_dynamic(value, template), should return a Vec<(char, u128)>,
+// since field widths (and so types) aren't known at compile time. Binary and hex templates of
+// standard widths are enough to start. Template parsing must first be factored out of
+// Template::from_expr and extract_fields so that it doesn't depend on syn::Expr.
+// ** try_splitbits! and try_combinebits! return String errors, since a proc-macro crate can't
+// export an Error enum. A typed Error belongs in the separate runtime crate.
+// ** A bit reader over a &[u8] (reading by width or by template, advancing a bit cursor) would
+// cover bitstreams, but as a runtime type it must also live in the separate runtime crate.
 // * Add file-level config for overflow and min.
 // * Always use overflow=corrupt for combinebits! and replacebits! if the input variable size
 // exactly matches the field slot size.
@@ -98,9 +114,34 @@
 //! than bits (binary digits). The variants are [`splithex!`], [`combinehex!`],
 //! [`splithex_then_combine!`], and [`replacehex!`].
 //!
+//! #### Octal
+//! All four base macros also have equivalents that use octal digits for their templates, such as
+//! for Unix permission masks: [`splitoct!`], [`combineoct!`], [`splitoct_then_combine!`], and
+//! [`replaceoct!`], as well as [`splitoct_named!`]. Each octal digit is three bits, so no whole
+//! number of digits is a standard width. Instead, octal templates are padded on the left with
+//! placeholder bits up to the next standard width: a four-digit template is a `u16` whose top four
+//! bits are ignored.
+//!
+//! #### Combinebits variants
+//! - [`combinebits_builder!`] - Create a builder with a setter for each field of a template, for
+//!   when the fields are computed at different points in the code.
+//! - [`combinebits_at!`] - Combine integers by specifying the bit offset and width of each, rather
+//!   than a template.
+//! - [`try_combinebits!`] - Return an `Err` instead of panicking when a field overflows under
+//!   `overflow=panic`.
+//!
+//! #### Replacebits variants
+//! - [`replacebits_swap!`] - Replace bits and also return the values of the fields that were
+//!   replaced, for read-modify-write sequences that need the previous values.
+//!
+//! #### Splitbits-then-combine variants
+//! - [`movebits!`] - Rearrange the bits of a single integer, such as swapping its bytes or
+//!   relocating a bit field.
+//!
 //! #### Splitbits variants
 //! [`splitbits!`] itself has many variants which are intended for better ergonomics for the generated
 //! variables. The basic variants are:
+//! - [`try_splitbits!`] - Return a `Result` rather than panicking if a field can't be converted.
 //! - [`splitbits_named!`] - Used when single-letter variable names aren't descriptive enough. This
 //!   variant returns a tuple (instead of a struct) of the resulting fields, allowing the caller to
 //!   assign individual long field names in the `let` binding.
@@ -109,10 +150,40 @@
 //!   before it reaches the caller. This is useful for when the default type (the smallest integer
 //!   type that will fit the field) is a smaller type than the caller would like to use, or if the
 //!   caller has a newtype that they would like to use instead.
+//! - [`splitbits_capture!`] - Assign the fields to existing variables of the same names, rather
+//!   than returning them.
+//! - [`splitbits_debug!`] - Same as [`splitbits!`], but also prints a breakdown of the extracted
+//!   fields. Intended for temporary use while exploring an unfamiliar layout.
+//! - [`splitbits_header!`] - Extract bit fields from the header at the start of a byte slice,
+//!   returning the remainder of the slice too.
+//! - [`splitbits_field!`] - Extract a single field, specified by its bit offset and width rather
+//!   than a template.
+//! - [`splitbits_record!`] - Extract fields from several integers at once, such as the words of a
+//!   multi-word descriptor, into a single struct.
+//! - [`splitbits_column!`] - Extract the same field from every element of an array, producing an
+//!   array of that field's values.
+//! - [`splitbits_enum!`] - Extract fields into a variant of an enum, with the variant and its
+//!   template chosen by the value of a discriminant field.
+//! - [`splitbits_lazy!`] - Define a nameable newtype over a whole integer, with an accessor for
+//!   each field that extracts it only when called.
 //! - [`splitbits_ux!`] - Used when exact-width integers (e.g. u4, u7, u20) are needed, instead of
-//!   just the standard types (u8, u16, u32, u64, u128, and bool). Requires the [ux] crate.
+//!   just the standard types (u8, u16, u32, u64, u128, and bool). Requires the [ux] crate (and the
+//!   `ux` feature of splitbits, which is enabled by default), or the [arbitrary-int] crate if the
+//!   `arbitrary-int` feature of splitbits is enabled instead. The two features are mutually
+//!   exclusive, so default features must be disabled to use `arbitrary-int`.
+//!
+//! #### Other macros
+//! - [`matches_template!`] - Check whether the literal bits of an integer match a template, without
+//!   extracting fields.
+//! - [`template_consts!`] - Define consts for the literal bits of a template, such as a magic
+//!   number, for use outside of macro calls.
+//! - [`splitbits_roundtrip_test!`] - Check that splitting then combining a set of sample values with
+//!   a template gives back the original values, for use in a test.
+//! - [`assert_templates_compatible!`] - Check at compile time that the fields shared by two
+//!   templates have the same widths.
 //!
 //! [ux]: <https://docs.rs/ux/latest/ux/>
+//! [arbitrary-int]: <https://docs.rs/arbitrary-int/latest/arbitrary_int/>
 //! # Template syntax
 //! Templates are a string of characters that represent the names and bit-placements of fields
 //! within an integer.
@@ -121,40 +192,295 @@
 //!
 //! The possible elements of a template are:
 //! - Names - a single letter that indicates the name of a field. (Currently only ASCII allowed.)
+//!   Longer names can be written between angle brackets, such as `<mode>`, and can be mixed freely
+//!   with single-letter names. A bracketed name takes up a single digit, just like a single letter:
+//!   `"<mode><mode>aa bbbb"`. Bracketed names must be lowercase letters and underscores, starting
+//!   with a letter. `rest`, `raw`, and `build` are reserved, since the generated code uses them.
+//!   Settings that list fields refer to bracketed names without the brackets: `invert(mode, a)`.
 //! - Placeholders - a period that indicates a digit that will be ignored.
 //! - Literals - a literal digit of the numeric base of the template (e.g. binary or hexadecimal).
 //! - Whitespaces - an empty space character used to make formatting more human-friendly,
 //!   paralleling how underscores can be added to integer literals.
+//! - Byte separators - a pipe that separates the bytes of a template, such as
+//!   `"aaaaaaaa|bbbbcccc|dddddddd"`. Unlike whitespace, pipes are checked: each must fall on a
+//!   byte boundary, and the template must be a whole number of bytes. This catches a miscounted
+//!   byte at compile time, rather than silently shifting the fields after it.
+//! - Repeated groups - a group of template characters in parentheses followed by a repeat count
+//!   in braces, such as `"(ab){4}"`, which is the same as `"abababab"`. Groups can be nested. Each
+//!   field that appears in a group is a single field made of several segments, as below.
 //!
 //! The bits of a field are usually contiguous within a template, but they don't have to be:
 //! `"aabbbbaa"`. This template will interpret `a` as a single field, with no bits present between
 //! the halves.
 //!
+//! Templates with long runs of the same field can instead be written as an array of field widths
+//! and string segments, which is expanded into a template string. This avoids counting out
+//! characters, such as for pixel formats:
+//! ```
+//! use splitbits::{splitbits, combinebits};
+//!
+//! // Same as "rrrrr gggggg bbbbb".
+//! let pixel = splitbits!(0b10000_111111_00001u16, [r = 5, g = 6, b = 5]);
+//! assert_eq!(pixel.r, 0b10000);
+//! assert_eq!(pixel.g, 0b111111);
+//! assert_eq!(pixel.b, 0b00001);
+//!
+//! // Same as "1000 aaaa".
+//! let result = combinebits!(0b1010u8, ["1000", a = 4]);
+//! assert_eq!(result, 0b1000_1010);
+//! ```
+//!
+//! Regular structures, such as a bitmap of repeating bit pairs, can be written as repeated groups:
+//! ```
+//! use splitbits::splitbits;
+//!
+//! // Same as "abababab".
+//! let pairs = splitbits!(0b10_11_01_10u8, "(ab){4}");
+//! assert_eq!(pairs.a, 0b1101);
+//! assert_eq!(pairs.b, 0b0110);
+//! ```
+//!
+//! Templates that are shared with other tools (such as documentation generators) can instead be
+//! read from a file at compile time by passing `template_file = "path"` in place of the template.
+//! The path is relative to the root of the crate, and line breaks in the file are treated as
+//! spaces. Like `include_str!`, a change to the file causes the invoking crate to be rebuilt:
+//! ```
+//! use splitbits::splitbits;
+//!
+//! // The file contains "eeee eeee" then "ssss ssss" on the next line.
+//! let status = splitbits!(0x8001u16, template_file = "tests/templates/status.tmpl");
+//! assert_eq!(status.e, 0x80);
+//! assert_eq!(status.s, 0x01);
+//! ```
+//!
+//! #### Protocol headers
+//! The leftmost character of a template is the most significant bit, which matches how RFC header
+//! diagrams number their bits. A header that spans multiple 32-bit words can be transcribed as a
+//! single wider template, as long as the words are assembled in network order (the first word in
+//! the high bits). [`splitbits_header!`] does this directly from bytes, and `from_be_bytes` or a
+//! shift does it from words:
+//! ```
+//! use splitbits::splitbits;
+//!
+//! // The first two 32-bit words of an IPv4 header.
+//! let words: [u32; 2] = [0x4500_0054, 0x1C46_4000];
+//! let input = (u64::from(words[0]) << 32) | u64::from(words[1]);
+//! let header = splitbits!(input,
+//!     "vvvv hhhh dddd ddee llll llll llll llll iiii iiii iiii iiii fffo oooo oooo oooo");
+//! assert_eq!(header.v, 4);
+//! assert_eq!(header.h, 5);
+//! assert_eq!(header.l, 84);
+//! assert_eq!(header.i, 0x1C46);
+//! assert_eq!(header.f, 0b010);
+//! assert_eq!(header.o, 0);
+//! ```
+//!
 //! #### Restrictions
-//! - Templates (currently) must have a standard integer width (8, 16, 32, 64, or 128 bits).
-//! - Placeholders cannot be used in the template for [`combinebits!`], nor in the output template
-//!   of [`splitbits_then_combine!`]. They are not meaningful in those contexts.
-//! - Literals (currently) cannot be used in the template for [`splitbits!`] nor the input templates
-//!   of [`splitbits_then_combine!`]. In the future, literals could be used in these contexts for
-//!   input validation.
+//! - Templates must be at most 128 bits wide. A template that isn't a standard integer width (such
+//!   as a 24-bit RGB color) is treated as the next larger standard type, with the extra high bits
+//!   ignored.
+//! - Placeholders cannot be used in the template for [`combinebits!`] (unless its `base` setting is
+//!   set), nor in the output template of [`splitbits_then_combine!`]. They are not meaningful in
+//!   those contexts.
+//! - Literals (currently) cannot be used in the input templates of [`splitbits_then_combine!`].
+//!   In the template of [`splitbits!`], they validate the input: a panic occurs if the input's bits
+//!   don't match the literals (or a compile error, if the input is an integer literal).
 //!
 //! # Settings
 //! Settings can be passed as the first argument to a macro to change some behaviors from the
 //! default. Their syntax is similar to named arguments in Python: `setting_type=setting_value`.
 //!
-//! There are currently two setting types:
+//! There are currently forty-five setting types:
 //! - **min** - sets the minimum size of variable that can be produced by the [`splitbits!`] family of
-//!   macros. Must be set if you don't want booleans generated for 1-bit fields.
+//!   macros. Must be set if you don't want booleans generated for 1-bit fields. Setting `min=bool`
+//!   is the same as not setting it: 1-bit fields are booleans and wider fields keep their
+//!   inferred integer types.
 //!   - For standard (non-ux) macros, the valid setting values are `bool` (the default), `u8`, `u16`, `u32`,
 //!     `u64`, and `u128`. See examples at [`splitbits!`].
 //!   - For ux macros, the valid setting values are `bool` (the default) or `uX`, where X is
 //!     between 1 and 128 (both inclusive). See examples at [`splitbits_ux!`].
+//!   - Fully-qualified primitive types, such as `core::primitive::u16`, are also accepted (here and
+//!     in `width`). Type aliases aren't, since macros can't see what they refer to.
+//!   - Signed types (`i8`, `i16`, `i32`, `i64`, and `i128`) set the same minimum width as the
+//!     unsigned types of the same width. Fields listed in the `signed` setting become that signed
+//!     type, while other fields become the unsigned type.
 //! - **overflow** - sets the behavior to use if the value of an input variable is larger than the
 //!   corresponding slot in the template. Used in [`combinebits!`] and [`replacebits!`]. Valid
-//!   setting values are `truncate` (the default), `panic`, `corrupt`, or `saturate`.
+//!   setting values are `truncate` (the default), `panic`, `corrupt`, `saturate`,
+//!   `debug_checked` (panic in debug builds, truncate in release builds), or `clamp` (clamp signed
+//!   inputs to the signed range of their slots, for [`combinebits!`] only). Enabling the
+//!   `default-panic-overflow` feature of splitbits makes `panic` the default instead, for builds
+//!   where a silently truncated value is never acceptable. [`replacebits!`] also accepts a behavior
+//!   per field, written like a function call: `overflow(a = panic, c = truncate)`. Fields that aren't
+//!   listed use the default.
+//! - **bools_only** - if set to `true`, the [`splitbits!`] family of macros will fail to compile
+//!   unless every field is a single bit, guaranteeing that every field is a `bool`. Can't be combined
+//!   with a `min` other than `bool`. Valid setting values are `false` (the default) and `true`.
+//! - **width** - sets the type of the integer that a macro operates on when it can't be inferred
+//!   from a template: the result of [`combinebits_at!`], or a [`replacebits!`] target that is wider
+//!   than its template. Valid setting values are `u8`, `u16`, `u32`, `u64`, and `u128`.
+//! - **clear_unused** - if set to `true`, [`replacebits!`] will zero every bit of its result that
+//!   isn't covered by a field or a literal of the template, rather than keeping the target's bits
+//!   at placeholder positions (and above the template, if the target is wider). Valid setting values
+//!   are `false` (the default) and `true`. See examples at [`replacebits!`].
+//! - **invert** - the names of the fields that should have their bits flipped after being extracted
+//!   by the [`splitbits!`] family of macros, written like a function call (e.g. `invert(a, c)`).
+//!   See examples at [`splitbits!`].
+//! - **signed** - the names of the fields that the [`splitbits!`] family of macros should produce as
+//!   two's complement signed integers, sign-extended from their widths, written like a function call
+//!   (e.g. `signed(a, mode)`). A field becomes the signed type of the same width as its unsigned type
+//!   (`i8` for a 1-bit field). Only supported for standard types. See examples at [`splitbits!`].
+//! - **conversion** - sets how the [`splitbits!`] family of macros converts extracted bits into
+//!   field types. Valid setting values are `checked` (the default, which uses `try_from().unwrap()`)
+//!   and `masked` (which uses an `as` cast, leaving no panic path in the generated code). The
+//!   extracted bits are masked to fit their fields either way. `masked` isn't supported for ux types.
+//! - **ratio** - the fields that the [`splitbits!`] family of macros should produce as a ratio of
+//!   their maximum value (from `0.0` to `1.0`), each with an optional float type (`f32` by
+//!   default), written like a function call: `ratio(a, b = f64)`. See examples at [`splitbits!`].
+//! - **range** - the fields that the [`splitbits!`] family of macros should check after extraction,
+//!   each with the range that its value must fall within, written like a function call:
+//!   `range(m = 1..=12, d = 1..=31)`. Panics if a field is out of range. See examples at
+//!   [`splitbits!`].
+//! - **bytes** - the fields that the [`splitbits!`] family of macros should produce as arrays of
+//!   big-endian bytes rather than integers, written like a function call: `bytes(a, b)`. A `u32`
+//!   field becomes a `[u8; 4]`, for example. See examples at [`splitbits!`].
+//! - **order** - the order of the fields produced by the [`splitbits!`] family of macros, if it
+//!   should differ from the order that they first appear in the template. Every field must be
+//!   listed, written like a function call: `order(c, a, b)`. See examples at [`splitbits!`].
+//! - **warn_noncontiguous** - if set to `true`, fail to compile if any field is split into
+//!   non-adjacent segments (such as `a` in `"aabaa"`), since that may be a typo. Valid setting values
+//!   are `false` (the default) and `true`. Used in the [`splitbits!`] family of macros,
+//!   [`combinebits!`], and [`replacebits!`]. See examples at [`splitbits!`].
+//! - **noncontiguous** - the fields that are intentionally split into non-adjacent segments, when
+//!   `warn_noncontiguous=true` is set. Written like a function call: `noncontiguous(a, b)`.
+//! - **group** - if set, fail to compile unless every group of digits in the template (separated by
+//!   spaces or byte separators) is the specified size. Valid setting values are `nibble` (4 bits)
+//!   and `byte` (8 bits). Since a hexadecimal digit is 4 bits, `group=byte` requires pairs of digits
+//!   in hexadecimal templates, but runs of eight digits in binary templates. Used in the
+//!   [`splitbits!`] family of macros, [`combinebits!`], and [`replacebits!`]. See examples at
+//!   [`splithex!`].
+//! - **align** - sets whether a template that is narrower than its input matches against the low
+//!   bits or the high bits of the input in the [`splitbits!`] family of macros. Valid setting values
+//!   are `low` (the default) and `high`. See examples at [`splitbits!`].
+//! - **field_order** - the fields of the [`splitbits!`] family of macros whose segments (when split
+//!   across the template) should be assembled in a different order, written like a function call:
+//!   `field_order(a = lsb_first)`. Valid values are `msb_first` (the default, where the leftmost
+//!   segment holds the most significant bits) and `lsb_first`. See examples at [`splitbits!`].
+//! - **bit_order** - sets the order of the bits within each byte of the input of the [`splitbits!`]
+//!   family of macros. Valid setting values are `msb` (the default) and `lsb_in_byte` (each byte
+//!   is least significant bit first, but bytes are still most significant byte first). See
+//!   examples at [`splitbits!`].
+//! - **rotate_input** - the number of bits that the [`splitbits!`] family of macros should rotate
+//!   the input right by before extracting, wrapping within the width of the template rather than
+//!   the width of the input's type. Must be less than the template width. See examples at
+//!   [`splitbits!`].
+//! - **diff** - if set to `true`, [`splitbits!`] will generate a `diff()` method that lists which
+//!   fields differ between two of its results. Valid setting values are `false` (the default) and
+//!   `true`. See examples at [`splitbits!`].
+//! - **flags_enum** - the fields that [`splitbits!`] should generate a flag iterator for, each with
+//!   the enum variants that its bits correspond to, written like a function call:
+//!   `flags_enum(f = [Flag::A, Flag::B])`. See examples at [`splitbits!`].
+//! - **rest** - if set to `true`, [`splitbits!`] will add a `rest` field holding the bits at the
+//!   template's placeholder positions, and a `recombine()` method that restores the original value.
+//!   Valid setting values are `false` (the default) and `true`. See examples at [`splitbits!`].
+//! - **keep_raw** - if set to `true`, [`splitbits!`] will keep the input value that the fields were
+//!   extracted from, accessible through a `raw()` method. Formatting the result with `{:b}` or `{:x}`
+//!   prints the kept value. Valid setting values are `false` (the default) and `true`. See examples
+//!   at [`splitbits!`].
+//! - **default** - a reset value that [`splitbits!`] should generate a `Default` impl from, with
+//!   each field extracted from the reset value just as they are from the input. The reset value
+//!   must be a literal or a const. Useful for registers whose documented reset value isn't all
+//!   zeros. See examples at [`splitbits!`].
+//! - **lookup** - the fields whose extracted values index into a table, such as a divider table in
+//!   a clock configuration register, each with its table: `lookup(d = [1u32, 2, 4, 8])`. The field
+//!   is produced as the table entry rather than the index, with the type of the table's entries
+//!   (taken from the suffix of the first entry). Each table must have an entry for every value of
+//!   its field. [`combinebits!`] does the reverse, placing the index of its input in the table,
+//!   and panics if the input isn't in the table. See examples at [`splitbits!`] and
+//!   [`combinebits!`].
+//! - **char** - the fields that the [`splitbits!`] family of macros should produce as `char`s, each
+//!   with an optional offset to add to its value to get its code point, written like a function
+//!   call: `char(a = 0x20, b)`. Panics if a code point isn't a valid char, which is only possible
+//!   if a field's code points don't all fit in a byte. See examples at [`splitbits!`].
+//! - **map_into** - the fields that the [`splitbits!`] family of macros should pass through a
+//!   function (such as a constructor of a unit type) after extraction, written like a function call:
+//!   `map_into(t = Duration::from_millis)`. The field's type is the type that the path of the
+//!   function is in, unless it is written explicitly: `map_into(t = to_celsius as Celsius)`. Each
+//!   value is converted into the function's parameter type with `Into`. See examples at
+//!   [`splitbits!`].
+//! - **sign_magnitude** - the magnitude field and the 1-bit sign field of a sign-and-magnitude value,
+//!   written like a function call: `sign_magnitude(v, s)`. The [`splitbits!`] family of macros
+//!   combines them into a single signed field (named after the magnitude field) that is wide
+//!   enough for the magnitude plus a sign bit, such as `i8` for a 7-bit magnitude. See examples at
+//!   [`splitbits!`].
+//! - **trait_impl** - the traits that the struct generated by [`splitbits!`] should implement,
+//!   written like a function call: `trait_impl(Decoded, Register)`. Only traits without required
+//!   methods (such as marker traits) can be implemented, since the macro can't fill in methods.
+//!   Auto traits such as `Send` and `Sync` are rejected, since the compiler already implements them.
+//!   Useful for bounding decoded types in generic code. See examples at [`splitbits!`].
+//! - **layout** - if set to `true`, [`splitbits!`] will generate a `WIDTH` and an `OFFSET` const
+//!   for each field (e.g. `A_WIDTH` and `A_OFFSET`), and a `layout()` method that lists them. It
+//!   also generates `STRUCT_SIZE` and `STRUCT_PADDING` consts for the struct itself. Valid setting
+//!   values are `false` (the default) and `true`. See examples at [`splitbits!`].
+//! - **bit_numbering** - sets which bit is displayed as bit 0 by [`splitbits_debug!`]. Valid
+//!   setting values are `lsb0` (the default, bit 0 is the least significant bit) and `msb0` (bit 0
+//!   is the most significant bit, as in some datasheets). Extraction itself is unaffected, but the
+//!   `X_OFFSET` consts of the layout setting follow the same numbering.
+//! - **combine** - sets how [`combinebits!`] merges its fields and literals together. Valid setting
+//!   values are `or` (the default) and `xor`. They only differ when fields overlap each other or
+//!   the literals, which `overflow=corrupt` allows. See examples at [`combinebits!`].
+//! - **base** - an integer whose bits fill the placeholder positions of a [`combinebits!`] template.
+//!   Placeholders aren't allowed in [`combinebits!`] templates unless this is set. See examples at
+//!   [`combinebits!`].
+//! - **optional** - the fields whose inputs are `Option`s in [`combinebits!`], each with an optional
+//!   default value to use if the input is `None` (zero otherwise). Unlike the other settings, it is
+//!   written like a function call: `optional(a = 5, b)`. See examples at [`combinebits!`].
+//! - **constant** - the fields in [`combinebits!`] that take a fixed value rather than an input,
+//!   written like a function call: `constant(a = 5, b = 0x1F)`. See examples at [`combinebits!`].
+//! - **map** - a map that [`combinebits!`] should take its inputs from, keyed by the fields'
+//!   template names as chars, rather than from arguments or captured variables. See examples at
+//!   [`combinebits!`].
+//! - **names** - the variables that [`combinebits!`] should capture for fields, if they differ from
+//!   the fields' template names, written like a function call: `names(b = beginning, e = end)`.
+//!   Only applies when inputs aren't passed as arguments. See examples at [`combinebits!`].
+//! - **from** - the structs that [`combinebits!`] should take its inputs from, each with the fields
+//!   that it provides, written like a function call: `from(header = h, self.payload = (p, f))`.
+//!   Every field must be listed under exactly one struct. See examples at [`combinebits!`].
+//! - **const_check** - the fields in [`combinebits!`] whose inputs must equal a specific value,
+//!   written like a function call: `const_check(a = 3)`. Panics if an input has a different value,
+//!   or fails to compile if the input is an integer literal. See examples at [`combinebits!`].
+//! - **result** - sets whether [`combinebits!`] produces an unsigned integer (the default) or a
+//!   signed integer of the same width as its template, such as `result=i16` for a 16-bit template.
+//!   The bits are reinterpreted rather than converted, so the top bit of the template is the sign
+//!   bit. Setting `result=fit` instead produces the smallest unsigned integer that can hold every
+//!   bit that the fields and literals can set, for templates that are padded with leading zeros.
+//!   See examples at [`combinebits!`].
+//! - **placeholder** - sets the char that marks ignored bits in templates, for templates copied
+//!   from sources that don't use periods, such as `placeholder='-'`. Periods are the default. Chars
+//!   that already have a meaning in templates (field names and literals) aren't allowed. Used in
+//!   the [`splitbits!`] family of macros, [`combinebits!`], and [`replacebits!`].
+//! - **output_base** - sets the base of the output template of [`splitbits_then_combine!`] and
+//!   [`splithex_then_combine!`], if it differs from the base of their input templates. Valid
+//!   setting values are `binary` and `hexadecimal`. See examples at [`splitbits_then_combine!`].
+//! - **output** - sets whether [`splitbits!`] and its hexadecimal and ux variants return a struct,
+//!   a tuple, or an array. Valid setting values are `fields` (the default, a struct), `tuple`, and
+//!   `array` (a `[u128; N]` of the field values in template order, with bools as 0 or 1).
+//!   `output=tuple` is the same as using the corresponding [`splitbits_named!`] macro.
+//! - **always_tuple** - if set to `true`, the [`splitbits_named!`] family of macros (and
+//!   `output=tuple`) will return a 1-tuple for a single-field template, rather than a bare value.
+//!   Valid setting values are `false` (the default) and `true`.
+//! - **types** - the types that [`splitbits_named_into!`] should convert fields into, written like a
+//!   function call: `types(a = u32, b = Count)`. Fails to compile if a field is too wide for its
+//!   primitive type, with an error that names the field's width. See examples at
+//!   [`splitbits_named_into!`].
 
 #![forbid(unsafe_code)]
 
+// Both backends would provide the same non-standard types, so only one can be used at a time.
+#[cfg(all(feature = "ux", feature = "arbitrary-int"))]
+compile_error!("The `ux` and `arbitrary-int` features of splitbits are mutually exclusive. \
+    Disable default features to use `arbitrary-int`.");
+
 extern crate proc_macro;
 
 mod base;
@@ -163,20 +489,26 @@ mod field;
 mod location;
 mod name;
 mod segment;
+mod setting;
 mod template;
 mod r#type;
 
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Token, Expr, ExprAssign};
+use std::collections::BTreeMap;
+
+use proc_macro2::{TokenStream, Span};
+use quote::{quote, format_ident, ToTokens};
+use syn::{Token, Expr, ExprArray, ExprLit, ExprPath, ExprTuple, Lit, LitInt, parse_quote};
 use syn::parse::Parser;
+use syn::spanned::Spanned;
 use syn::punctuated::Punctuated;
 
 use crate::base::Base;
-use crate::field::Field;
-use crate::location::OnOverflow;
+use crate::field::{Field, Conversion};
+use crate::location::{Location, OnOverflow, BitNumbering};
+use crate::name::Name;
+use crate::setting::{Settings, Align, BitOrder, Operator, Output, ResultType, SegmentOrder};
 use crate::template::Template;
-use crate::r#type::{Type, Precision};
+use crate::r#type::{Type, Precision, Float};
 
 /// Extract bit fields from an integer data type by matching against a template,
 /// storing them as fields in a generated struct.
@@ -270,11 +602,404 @@ use crate::r#type::{Type, Precision};
 /// assert_eq!(coordinates.y, 0b0000);
 /// ```
 ///
+/// A template can be narrower than its input. By default, it matches against the low bits of the
+/// input. To match against the high bits instead, set `align=high`:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let input: u16 = 0b1010_0110_0000_1111;
+/// let low = splitbits!(input, "aaaa bbbb");
+/// assert_eq!(low.a, 0b0000);
+/// assert_eq!(low.b, 0b1111);
+///
+/// let high = splitbits!(align=high, input, "aaaa bbbb");
+/// assert_eq!(high.a, 0b1010);
+/// assert_eq!(high.b, 0b0110);
+/// ```
+/// The input must have an explicit type, and it must be at least as wide as the template.
+///
+/// Some buses (such as a few I2C sensors) transmit the bits of each byte least significant bit
+/// first, while still transmitting the bytes themselves most significant byte first. Setting
+/// `bit_order=lsb_in_byte` reverses the bits within each byte of the input before extracting, so
+/// the template can be written in the normal order. The template must be made of whole bytes:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let input: u16 = 0x0180;
+/// let fields = splitbits!(bit_order=lsb_in_byte, input, "aaaa aaaa bbbb bbbb");
+/// assert_eq!(fields.a, 0x80);
+/// assert_eq!(fields.b, 0x01);
+/// ```
+///
+/// Values that are stored rotated (such as some CAN bus signals) can be rotated back into place
+/// before extraction by setting `rotate_input`, which rotates the input right by that many bits.
+/// The rotation wraps within the width of the template:
+/// ```
+/// use splitbits::splitbits;
+///
+/// // Rotated right by 4, the input is 0x5234.
+/// let fields = splitbits!(rotate_input=4, 0x2345u16, "aaaa bbbb bbbb bbbb");
+/// assert_eq!(fields.a, 0x5);
+/// assert_eq!(fields.b, 0x234);
+/// ```
+///
+/// Fields listed in the invert setting have their bits flipped after extraction, which is useful
+/// for active-low signals. Only the bits within the field's width are flipped:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(invert(a, e), 0b1010_0110, "aaab bbbe");
+/// assert_eq!(fields.a, 0b010);
+/// assert_eq!(fields.b, 0b0011);
+/// assert_eq!(fields.e, true);
+/// ```
+///
+/// Fields listed in the signed setting are two's complement signed integers, such as packed sensor
+/// deltas. They are sign-extended from their widths, and can be widened with a signed min:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let deltas = splitbits!(signed(a, b), 0b111_01101, "aaab bbbb");
+/// assert_eq!(deltas.a, -1i8);
+/// assert_eq!(deltas.b, 13i8);
+///
+/// let deltas = splitbits!(signed(a, b), min=i16, 0b111_10011, "aaab bbbb");
+/// assert_eq!(deltas.a, -1i16);
+/// assert_eq!(deltas.b, -13i16);
+/// ```
+///
+/// If the input is an integer literal, the fields are computed during macro expansion rather than
+/// at runtime, so they can be used in const contexts:
+/// ```
+/// use splitbits::splitbits;
+///
+/// const KIND: u8 = splitbits!(0b1011_0010, "kkkk llll").k;
+/// assert_eq!(KIND, 0b1011);
+/// ```
+///
+/// Any other input expression is evaluated exactly once, no matter how many fields are extracted
+/// from it. This makes it safe to pass a register read that has side effects (such as clearing
+/// flags) directly. A closure input is called once too:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let mut reads = 0;
+/// let mut read_status = || { reads += 1; 0b1011_0010u8 };
+/// let fields = splitbits!(read_status(), "kkkk llll");
+/// assert_eq!(fields.k, 0b1011);
+/// assert_eq!(fields.l, 0b0010);
+/// let fields = splitbits!(|| 0b0110_1001u8, "kkkk llll");
+/// assert_eq!(fields.k, 0b0110);
+/// assert_eq!(reads, 1);
+/// ```
+///
+/// Setting `diff=true` generates a `diff()` method that returns the names of the fields that differ
+/// between two results of the same macro invocation. The names are returned as an iterator, so no
+/// allocation is needed unless the caller collects them:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let decode = |status: u8| splitbits!(diff=true, status, "eeee rrrb");
+/// let before = decode(0b1010_0001);
+/// let after = decode(0b1010_1100);
+/// let changed: Vec<&str> = after.diff(&before).collect();
+/// assert_eq!(changed, ["r", "b"]);
+/// ```
+/// Each macro invocation generates its own struct, so only results from the same invocation can be
+/// compared, such as from the same closure or the same loop body.
+///
+/// The ratio setting produces fields as a fraction of their maximum value, from 0.0 to 1.0. This is
+/// common for sensor readings, such as a 12-bit ADC value. The float type defaults to `f32`:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let reading = splitbits!(ratio(v, c = f64), 0b1111_1111_1111_0100u16, "vvvv vvvv vvvv cc..");
+/// assert_eq!(reading.v, 1.0f32);
+/// assert_eq!(reading.c, 1.0f64 / 3.0);
+/// ```
+///
+/// The range setting checks that fields fall within the specified ranges, panicking otherwise. This
+/// keeps validation next to the layout, such as for a FAT-style packed date:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let date = splitbits!(range(m = 1..=12, d = 1..=31), 0b0101_1000_1001_0000u16, "yyyy yyym mmmd dddd");
+/// assert_eq!(date.y, 44);
+/// assert_eq!(date.m, 4);
+/// assert_eq!(date.d, 16);
+/// ```
+/// ```should_panic
+/// use splitbits::splitbits;
+///
+/// // Month 13 doesn't exist.
+/// let _ = splitbits!(range(m = 1..=12, d = 1..=31), 0b0101_1001_1011_0000u16, "yyyy yyym mmmd dddd");
+/// ```
+///
+/// Setting `warn_noncontiguous=true` fails compilation if a field is split into non-adjacent
+/// segments, to catch typos in dense templates. Intentionally split fields must be acknowledged
+/// with the `noncontiguous` setting:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(warn_noncontiguous=true, noncontiguous(a), 0b1101_0010u8, "aabb bbaa");
+/// assert_eq!(fields.a, 0b1110);
+/// assert_eq!(fields.b, 0b0100);
+/// ```
+///
+/// Fields listed in the char setting are produced as `char`s, such as for text that is packed into
+/// registers. Each field can have an offset that is added to its value to get its code point, such
+/// as for 6-bit characters that start at the space character (0x20):
+/// ```
+/// use splitbits::splitbits;
+///
+/// let label = splitbits!(char(a = 0x20, b = 0x20), 0x28A4u16, "..aa aaaa bbbb bb..");
+/// assert_eq!(label.a, 'H');
+/// assert_eq!(label.b, 'I');
+/// ```
+///
+/// Fields listed in the map_into setting are passed through a function after extraction, such as
+/// to produce a `Duration` directly from a timing register. The field's type is taken from the path
+/// of the function (`Duration` in `Duration::from_millis`), or can be written explicitly with `as`:
+/// ```
+/// use core::time::Duration;
+/// use splitbits::splitbits;
+///
+/// fn to_celsius(raw: u8) -> i16 {
+///     i16::from(raw) - 40
+/// }
+///
+/// let timing = splitbits!(map_into(d = Duration::from_millis, t = to_celsius as i16), 0xFA3Cu16, "dddd dddd tttt tttt");
+/// assert_eq!(timing.d, Duration::from_millis(250));
+/// assert_eq!(timing.t, 20);
+/// ```
+///
+/// The leftmost segment of a split field holds its most significant bits by default. Setting
+/// `field_order` to `lsb_first` for a field makes its leftmost segment hold its least significant
+/// bits instead, for hardware that spreads a field's high bits later in a register:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let msb_first = splitbits!(0b1101_0010u8, "aabb bbaa");
+/// assert_eq!(msb_first.a, 0b1110);
+/// let lsb_first = splitbits!(field_order(a = lsb_first), 0b1101_0010u8, "aabb bbaa");
+/// assert_eq!(lsb_first.a, 0b1011);
+/// ```
+///
+/// The lookup setting maps the raw value of a field through a table, producing the table entry
+/// instead of the index. The table must have an entry for every possible raw value of the field,
+/// and the type of the field is taken from the suffix of the first entry:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(lookup(d = [1u32, 2, 4, 8, 16, 32, 64, 128]), 0b1011_0010u8, "eddd ....");
+/// assert_eq!(fields.d, 8);
+/// assert_eq!(fields.e, true);
+/// ```
+///
+/// The sign_magnitude setting decodes values that are stored as a magnitude and a separate sign bit,
+/// rather than as two's complement. The magnitude field becomes a signed integer that is wide enough
+/// for the magnitude plus the sign, and the sign field is consumed:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(sign_magnitude(v, s), 0b1000_0101u8, "svvv vvvv");
+/// assert_eq!(fields.v, -5i8);
+/// let fields = splitbits!(sign_magnitude(v, s), 0b0000_0101u8, "svvv vvvv");
+/// assert_eq!(fields.v, 5i8);
+/// ```
+///
+/// The trait_impl setting implements marker traits (or any trait without required methods) for the
+/// generated struct, so that decoded values can be passed to code that is generic over them:
+/// ```
+/// use splitbits::splitbits;
+///
+/// trait Decoded {
+///     fn kind(&self) -> &'static str { "register" }
+/// }
+///
+/// fn log(value: &impl Decoded) -> &'static str { value.kind() }
+///
+/// let fields = splitbits!(trait_impl(Decoded), 0b1011_0010u8, "kkkk llll");
+/// assert_eq!(log(&fields), "register");
+/// ```
+///
+/// The order setting declares the order of the fields, such as to match a serialization schema,
+/// rather than the order that they first appear in the template. It applies to tuple output too:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let (length, kind) = splitbits!(order(l, k), output=tuple, 0b1011_0010u8, "kkkk llll");
+/// assert_eq!(length, 0b0010);
+/// assert_eq!(kind, 0b1011);
+/// ```
+///
+/// The bytes setting produces fields as arrays of big-endian bytes, ready to be written to a
+/// byte-oriented output:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(bytes(a), 0xDEAD_BEEF_0000_0000_u64, "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa \
+///     .... .... .... .... .... .... .... ....");
+/// assert_eq!(fields.a, [0xDE, 0xAD, 0xBE, 0xEF]);
+/// ```
+///
+/// Setting `rest=true` adds a `rest` field that holds the bits at the placeholder positions of the
+/// template (left in place), and a `recombine()` method that reassembles the fields and the rest of
+/// the bits into the original value. This guarantees a lossless round-trip when only a few fields
+/// need to be modified:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let register: u16 = 0b1010_0110_1100_0011;
+/// let mut fields = splitbits!(rest=true, register, "aaa. .... ..bb ....");
+/// assert_eq!(fields.rest, 0b0000_0110_1100_0011);
+/// fields.b = 0b01;
+/// assert_eq!(fields.recombine(), 0b1010_0110_1101_0011);
+/// ```
+///
+/// The default setting generates a `Default` impl whose fields are extracted from the specified
+/// reset value, such as a register's documented reset value:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let mut control = splitbits!(default=0b1000_0011, 0b0000_0000u8, "e... .mmm");
+/// assert_eq!(control.m, 0b000);
+/// control = Default::default();
+/// assert_eq!(control.e, true);
+/// assert_eq!(control.m, 0b011);
+/// ```
+///
+/// Setting `keep_raw=true` keeps the input value alongside the fields, accessible through a `raw()`
+/// method, such as for logging both forms. If the template is narrower than the input, only the bits
+/// that the template was matched against are kept:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(keep_raw=true, 0b1011_0010u8, "kkkk llll");
+/// assert_eq!(fields.k, 0b1011);
+/// assert_eq!(fields.raw(), 0b1011_0010);
+/// ```
+///
+/// The kept value is also what gets printed when the result is formatted as binary or hex, so that
+/// it can be logged in the same form as a datasheet shows it:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(keep_raw=true, 0xB2u8, "kkkk llll");
+/// assert_eq!(format!("{fields:b}"), "10110010");
+/// assert_eq!(format!("{fields:#04x}"), "0xb2");
+/// ```
+///
+/// Setting `layout=true` generates `X_WIDTH` and `X_OFFSET` consts for each field X, and a
+/// `layout()` method that returns the name, width, and offset of each field. The offset of a field
+/// is the position of its lowest bit, counting from the least significant bit of the template:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(layout=true, 0b1011_0010u8, "kkkl llll");
+/// assert_eq!(fields.layout(), [("k", 3, 5), ("l", 5, 0)]);
+/// ```
+///
+/// Setting `layout=true` also generates `STRUCT_SIZE` and `STRUCT_PADDING` consts, and
+/// `struct_size()` and `struct_padding()` methods that return them, to show the memory cost of the
+/// generated struct. The generated struct uses the default Rust representation, so the compiler is
+/// free to reorder its fields to minimize padding: the `order` setting doesn't affect these values.
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(layout=true, 0xDEAD_BEEFu32, "aaaa aaaa aaaa aaaa bbbb bbbb cccc cccc");
+/// assert_eq!(fields.struct_size(), 4);
+/// assert_eq!(fields.struct_padding(), 0);
+/// ```
+///
+/// Setting `output=tuple` returns a tuple of the field values (in template order) rather than a
+/// struct, the same as [`splitbits_named!`]:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let (kind, length) = splitbits!(output=tuple, 0b1011_0010u8, "kkkk llll");
+/// assert_eq!(kind, 0b1011);
+/// assert_eq!(length, 0b0010);
+/// ```
+///
+/// The placeholder setting changes which char marks ignored bits, so that templates can be copied
+/// from datasheets that use something other than periods:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let fields = splitbits!(placeholder='-', 0b1011_0010u8, "kk-- -lll");
+/// assert_eq!(fields.k, 0b10);
+/// assert_eq!(fields.l, 0b010);
+/// ```
+///
+/// Setting `output=array` returns the field values as a `[u128; N]` (in template order), so that
+/// they can all be processed uniformly. Single-bit fields become 0 or 1:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let values = splitbits!(output=array, 0b1011_0010u8, "kkkk lllf");
+/// assert_eq!(values, [0b1011, 0b001, 0]);
+/// ```
+///
+/// The flags_enum setting generates an `iter_set_X()` method for a field X, which iterates over the
+/// variants of an enum whose bits are set in the field. Variants are listed starting from the field's
+/// least significant bit, with `_` for any bits that don't correspond to a variant:
+/// ```
+/// use splitbits::splitbits;
+///
+/// #[derive(PartialEq, Debug)]
+/// enum Permission { Execute, Write, Read }
+///
+/// let mode = splitbits!(
+///     flags_enum(p = [Permission::Execute, Permission::Write, Permission::Read, _]),
+///     0b1010_0110u8,
+///     "....pppp",
+/// );
+/// assert_eq!(mode.p, 0b0110);
+/// assert_eq!(mode.iter_set_p().collect::<Vec<_>>(), [Permission::Write, Permission::Read]);
+/// ```
+///
+/// Literals in the template aren't extracted, but the input must match them, which is useful for
+/// fixed-format opcodes. A mismatched input causes a panic, or a compile error if the input is an
+/// integer literal:
+/// ```
+/// use splitbits::splitbits;
+///
+/// let opcode: u8 = 0b1010_0110;
+/// let fields = splitbits!(opcode, "1010aaaa");
+/// assert_eq!(fields.a, 0b0110);
+/// ```
+/// ```should_panic
+/// use splitbits::splitbits;
+///
+/// let opcode: u8 = 0b1110_0110;
+/// // Panics: the top nibble is 0b1110, not 0b1010.
+/// let fields = splitbits!(opcode, "1010aaaa");
+/// ```
+///
 /// [`splitbits!`] generates unique, undocumented, struct names. Changes to the struct name format
 /// will not be considered breaking changes, so don't rely on the format staying the same!
 #[proc_macro]
 pub fn splitbits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_base(input, Base::Binary, Precision::Standard)
+    with_template_files(splitbits_base(input, Base::Binary, Precision::Standard))
+}
+
+/// Same as [`splitbits!`], except that the result is a `Result<Fields, String>`. Each runtime check
+/// returns an `Err` rather than panicking: an input that doesn't match the literals of the template,
+/// a field outside of its `range`, a field that isn't a valid `char`, and a field that doesn't fit
+/// in its `min` type during a checked conversion. The template itself is still checked at compile
+/// time. Inputs are evaluated in the calling function, so `?` within them still applies to it. The
+/// error message requires `std`.
+/// ```
+/// use splitbits::try_splitbits;
+///
+/// let fields = try_splitbits!(0b11110000u8, "aaabbbbb").unwrap();
+/// assert_eq!(fields.a, 0b111u8);
+/// assert_eq!(fields.b, 0b10000u8);
+/// ```
+#[proc_macro]
+pub fn try_splitbits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(splitbits_base_with(input, Base::Binary, Precision::Standard, true))
 }
 
 /// Same as [`splitbits!`], except that the widths of the generated fields are precise to-the-bit.
@@ -317,7 +1042,11 @@ pub fn splitbits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// ```
 #[proc_macro]
 pub fn splitbits_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_base(input, Base::Binary, Precision::Ux)
+    if let Some(error) = missing_ux_backend("splitbits_ux") {
+        return error;
+    }
+
+    with_template_files(splitbits_base(input, Base::Binary, Precision::Ux))
 }
 
 /// Same as [`splitbits!`], except that the template characters represent hexadecimal digits.
@@ -362,9 +1091,21 @@ pub fn splitbits_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// assert_eq!(fields.c, 0x10DB8u64);
 /// assert_eq!(fields.z, 0x85A30000u64);
 /// ```
+///
+/// Setting `group=byte` checks that the template is grouped into bytes (pairs of hexadecimal
+/// digits), to catch misaligned grouping when transcribing byte-oriented datasheet tables. A
+/// template such as `"aa abb"` fails to compile with this setting:
+/// ```
+/// use splitbits::splithex;
+///
+/// let fields = splithex!(group=byte, 0x12345678u32, "aa ab bb cc");
+/// assert_eq!(fields.a, 0x123);
+/// assert_eq!(fields.b, 0x456);
+/// assert_eq!(fields.c, 0x78);
+/// ```
 #[proc_macro]
 pub fn splithex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_base(input, Base::Hexadecimal, Precision::Standard)
+    with_template_files(splitbits_base(input, Base::Hexadecimal, Precision::Standard))
 }
 
 /// Same as [`splithex!`], except that the widths of the generated fields are precise to-the-bit.
@@ -401,13 +1142,130 @@ pub fn splithex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// ```
 #[proc_macro]
 pub fn splithex_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_base(input, Base::Hexadecimal, Precision::Ux)
+    if let Some(error) = missing_ux_backend("splithex_ux") {
+        return error;
+    }
+
+    with_template_files(splitbits_base(input, Base::Hexadecimal, Precision::Ux))
+}
+
+/// Same as [`splitbits!`], except with octal digits in the template. Each digit is three bits, and
+/// the template is padded on the left with ignored bits up to the next standard width.
+/// ```
+/// use splitbits::splitoct;
+///
+/// // Parse Unix permissions: the special bits, then the user, group, and other bits.
+/// let permissions = splitoct!(0o4755u16, "sugo");
+/// assert_eq!(permissions.s, 0o4);
+/// assert_eq!(permissions.u, 0o7);
+/// assert_eq!(permissions.g, 0o5);
+/// assert_eq!(permissions.o, 0o5);
+/// ```
+#[proc_macro]
+pub fn splitoct(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(splitbits_base(input, Base::Octal, Precision::Standard))
+}
+
+/// Same as [`splitbits!`], except that a breakdown of the extracted fields is also printed to
+/// stderr: each field's name, its bit range within the template, and its value (in binary and
+/// hexadecimal). Useful when exploring an unfamiliar register layout.
+/// ```
+/// use splitbits::splitbits_debug;
+///
+/// let fields = splitbits_debug!(0b1011_0110, "aaaa bbcd");
+/// // Prints:
+/// // splitbits_debug!(0b1011_0110, "aaaa bbcd"):
+/// //   a [7:4] = 0b1011 (0xB)
+/// //   b [3:2] = 0b1 (0x1)
+/// //   c [1] = true
+/// //   d [0] = false
+/// assert_eq!(fields.a, 0b1011);
+/// assert_eq!(fields.b, 0b01);
+/// assert_eq!(fields.c, true);
+/// assert_eq!(fields.d, false);
+/// ```
+///
+/// Bit ranges are numbered with bit 0 as the least significant bit by default. For datasheets
+/// that number bit 0 as the most significant bit instead, set `bit_numbering=msb0`. Only the
+/// printed bit ranges change, not the extracted values:
+/// ```
+/// use splitbits::splitbits_debug;
+///
+/// let fields = splitbits_debug!(bit_numbering=msb0, 0b1011_0110, "aaaa bbcd");
+/// // Prints:
+/// // splitbits_debug!(bit_numbering=msb0, 0b1011_0110, "aaaa bbcd"):
+/// //   a [0:3] = 0b1011 (0xB)
+/// //   b [4:5] = 0b1 (0x1)
+/// //   c [6] = true
+/// //   d [7] = false
+/// assert_eq!(fields.a, 0b1011);
+/// ```
+///
+/// The layout setting follows the bit numbering too, so with `msb0` the offset of each field is
+/// the position of its leftmost bit, matching the printed bit ranges:
+/// ```
+/// use splitbits::splitbits_debug;
+///
+/// let fields = splitbits_debug!(bit_numbering=msb0, layout=true, 0b1011_0110, "aaaa bbcd");
+/// assert_eq!(fields.layout(), [("a", 4, 0), ("b", 2, 4), ("c", 1, 6), ("d", 1, 7)]);
+/// ```
+///
+/// The breakdown is only printed in builds with debug assertions enabled, so a stray
+/// `splitbits_debug!` won't print anything in release builds. Still, it's intended for temporary
+/// use: replace it with [`splitbits!`] once the layout is understood.
+#[proc_macro]
+pub fn splitbits_debug(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: TokenStream = input.into();
+    let settings = [STRUCT_SETTINGS, &["bit_numbering"]].concat();
+    let (value, template, settings) =
+        match parse_splitbits_input("splitbits_debug", &input, Base::Binary, Precision::Standard, &settings) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    let (binding, value) = bind_value(value);
+    let check = literal_check(&template, &value, &settings, false);
+    let fields = extract_fields(&template, &value, &settings, Precision::Standard);
+    let (struct_definition, struct_value) = to_struct(&template, &value, &fields, &settings);
+
+    let header = format!("splitbits_debug!({input}):");
+    let numbering = settings.bit_numbering();
+    let template_width = template.width().bit_count();
+    let lines = fields.iter().map(|field| {
+        let name = field.name().to_ident();
+        let line = format!("  {name} [{}] = ", field.bit_ranges(numbering, template_width));
+        // Only integers can be formatted as binary and hex.
+        if field.is_mapped() || field.is_lookup() || field.is_char() || field.is_bytes() {
+            quote! { eprintln!("{}{:?}", #line, fields.#name); }
+        } else if field.bit_width() == Type::Bool || field.is_ratio() {
+            quote! { eprintln!("{}{}", #line, fields.#name); }
+        } else {
+            quote! { eprintln!("{}{:#b} ({:#X})", #line, fields.#name, fields.#name); }
+        }
+    });
+
+    let result = quote! {
+        {
+            #struct_definition
+            #binding
+            #check
+            let fields = #struct_value;
+            if cfg!(debug_assertions) {
+                eprintln!("{}", #header);
+                #(#lines)*
+            }
+
+            fields
+        }
+    };
+
+    with_template_files(result)
 }
 
 /// Same as [`splitbits!`], except that full-length variable names can be used. Returns a tuple
 /// instead of a generated struct. If there is only a single field specified in the template,
-/// returns a single variable instead (not a 1-tuple). Fields are returned in the order that they
-/// first appear in the template, and the single character template names are discarded.
+/// returns a single variable instead (not a 1-tuple), unless `always_tuple=true` is set. Fields are
+/// returned in the order that they first appear in the template, and the single character template
+/// names are discarded.
 /// ```
 /// use splitbits::splitbits_named;
 ///
@@ -441,9 +1299,18 @@ pub fn splithex_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// assert_eq!(apple_count, 0b1000u32);
 /// assert_eq!(banana_count, 0b11u32);
 /// ```
+///
+/// Code that always expects a tuple (such as code generated by other macros) can set
+/// `always_tuple=true` so that a single-field template produces a 1-tuple too:
+/// ```
+/// use splitbits::splitbits_named;
+///
+/// let (apple_count,) = splitbits_named!(always_tuple=true, 0b1110_0000, "aaa. ....");
+/// assert_eq!(apple_count, 0b111);
+/// ```
 #[proc_macro]
 pub fn splitbits_named(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_base(input, Base::Binary, Precision::Standard)
+    with_template_files(splitbits_named_base(input, Base::Binary, Precision::Standard))
 }
 
 /// Same as [`splitbits_named!`], except that the widths of the generated fields are precise to-the-bit.
@@ -458,7 +1325,43 @@ pub fn splitbits_named(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 /// ```
 #[proc_macro]
 pub fn splitbits_named_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_base(input, Base::Binary, Precision::Ux)
+    if let Some(error) = missing_ux_backend("splitbits_named_ux") {
+        return error;
+    }
+
+    with_template_files(splitbits_named_base(input, Base::Binary, Precision::Ux))
+}
+
+/// Assign the extracted fields to already-declared variables of the same names, rather than
+/// returning them. Avoids restating the variable names when they are already declared. The fields
+/// must have the same types as the variables, so the `min` setting is useful for widening them.
+/// ```
+/// use splitbits::splitbits_capture;
+///
+/// let a: u16;
+/// let b: u16;
+/// splitbits_capture!(min=u16, 0b1111_0000_0000_0011u16, "aaaa bbbb bbbb bbbb");
+/// assert_eq!(a, 0b1111);
+/// assert_eq!(b, 0b11);
+/// ```
+///
+/// A field without a variable of the same name in scope is a compile error on that name.
+#[proc_macro]
+pub fn splitbits_capture(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (value, template, settings) =
+        match parse_splitbits_input("splitbits_capture", &input.into(), Base::Binary, Precision::Standard, &[]) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    let (binding, value) = bind_value(value);
+    let check = literal_check(&template, &value, &settings, false);
+    let fields = extract_fields(&template, &value, &settings, Precision::Standard);
+    let assignments = fields.iter().map(|field| {
+        let name = field.name().to_ident();
+        let value = field.to_token_stream();
+        quote! { #name = #value; }
+    });
+    with_template_files(quote! { { #binding #check #(#assignments)* } })
 }
 
 /// Same as [`splitbits_named!`] except with hexadecimal digits in the template.
@@ -476,13 +1379,27 @@ pub fn splitbits_named_ux(input: proc_macro::TokenStream) -> proc_macro::TokenSt
 /// ```
 #[proc_macro]
 pub fn splithex_named(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_base(input, Base::Hexadecimal, Precision::Standard)
+    with_template_files(splitbits_named_base(input, Base::Hexadecimal, Precision::Standard))
 }
 
-/// Same as [`splithex_named!`], except that the widths of the generated fields are precise
-/// to-the-bit. A dependency on the ux crate is required.
+/// Same as [`splitbits_named!`] except with octal digits in the template.
 /// ```
-/// use splitbits::splithex_named_ux;
+/// use splitbits::splitoct_named;
+///
+/// let (user, group, other) = splitoct_named!(0o0750u16, ".ugo");
+/// assert_eq!(user,  0o7);
+/// assert_eq!(group, 0o5);
+/// assert_eq!(other, 0o0);
+/// ```
+#[proc_macro]
+pub fn splitoct_named(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(splitbits_named_base(input, Base::Octal, Precision::Standard))
+}
+
+/// Same as [`splithex_named!`], except that the widths of the generated fields are precise
+/// to-the-bit. A dependency on the ux crate is required.
+/// ```
+/// use splitbits::splithex_named_ux;
 /// use ux::{u4, u20};
 ///
 /// let (zebras, bees, beavers, fish) = splithex_named_ux!(
@@ -496,7 +1413,11 @@ pub fn splithex_named(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 /// ```
 #[proc_macro]
 pub fn splithex_named_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_base(input, Base::Hexadecimal, Precision::Ux)
+    if let Some(error) = missing_ux_backend("splithex_named_ux") {
+        return error;
+    }
+
+    with_template_files(splitbits_named_base(input, Base::Hexadecimal, Precision::Ux))
 }
 
 /// Same as [`splitbits_named!`], except the caller can provide the field types, rather than the
@@ -551,9 +1472,29 @@ pub fn splithex_named_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 /// assert_eq!(apple_count, 0b111);
 /// assert_eq!(banana_count, 0b10000);
 /// ```
+///
+/// As with [`splitbits_named!`], `always_tuple=true` produces a 1-tuple for a single-field
+/// template:
+/// ```
+/// use splitbits::splitbits_named_into;
+///
+/// let (apple_count,): (u32,) = splitbits_named_into!(always_tuple=true, 0b1110_0000, "aaa. ....");
+/// assert_eq!(apple_count, 0b111);
+/// ```
+///
+/// The types of fields can instead be specified with the types setting, which is useful when the
+/// bindings aren't annotated. A field that is too wide for its specified primitive type fails to
+/// compile with an error that names the field's width, rather than a missing `From` impl:
+/// ```
+/// use splitbits::splitbits_named_into;
+///
+/// let (apple_count, banana_count) = splitbits_named_into!(types(a = u32, b = u16), 0b11110000, "aaabbbbb");
+/// assert_eq!(apple_count, 0b111u32);
+/// assert_eq!(banana_count, 0b10000u16);
+/// ```
 #[proc_macro]
 pub fn splitbits_named_into(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_into_base(input, Base::Binary, Precision::Standard)
+    with_template_files(splitbits_named_into_base(input, Base::Binary, Precision::Standard))
 }
 
 /// Same as [`splitbits_named_into!`], except that the widths of the generated fields are precise
@@ -601,7 +1542,11 @@ pub fn splitbits_named_into(input: proc_macro::TokenStream) -> proc_macro::Token
 /// ```
 #[proc_macro]
 pub fn splitbits_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_into_base(input, Base::Binary, Precision::Ux)
+    if let Some(error) = missing_ux_backend("splitbits_named_into_ux") {
+        return error;
+    }
+
+    with_template_files(splitbits_named_into_base(input, Base::Binary, Precision::Ux))
 }
 
 /// Same as [`splithex_named!`], except the caller can provide the field types, rather than the
@@ -617,7 +1562,7 @@ pub fn splitbits_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::To
 /// See [`splitbits_named_into!`] for more examples.
 #[proc_macro]
 pub fn splithex_named_into(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_into_base(input, Base::Hexadecimal, Precision::Standard)
+    with_template_files(splitbits_named_into_base(input, Base::Hexadecimal, Precision::Standard))
 }
 
 /// Same as [`splithex_named_into!`], except the widths of the generated fields are precise
@@ -634,7 +1579,114 @@ pub fn splithex_named_into(input: proc_macro::TokenStream) -> proc_macro::TokenS
 /// See [`splitbits_named_into_ux!`] for more examples.
 #[proc_macro]
 pub fn splithex_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    splitbits_named_into_base(input, Base::Hexadecimal, Precision::Ux)
+    if let Some(error) = missing_ux_backend("splithex_named_into_ux") {
+        return error;
+    }
+
+    with_template_files(splitbits_named_into_base(input, Base::Hexadecimal, Precision::Ux))
+}
+
+/// Same as [`splitbits!`], except the input is a byte slice that starts with a header matching the
+/// template. Returns both the extracted fields and the remainder of the slice after the header, for
+/// parsing the header and then the payload of a message.
+///
+/// The header is as many bytes as the template is wide, and is read as big-endian (the first byte
+/// of the slice matches the left-most bits of the template). Panics if the slice is shorter than
+/// the header. The template must be a whole number of bytes, but needn't be a standard width.
+/// ```
+/// use splitbits::splitbits_header;
+///
+/// let message: &[u8] = &[0b1010_0011, 0b0000_0010, 0xAB, 0xCD];
+/// let (header, payload) = splitbits_header!(message, "vvvv tttt llll llll");
+/// assert_eq!(header.v, 0b1010);
+/// assert_eq!(header.t, 0b0011);
+/// assert_eq!(header.l, 2);
+/// assert_eq!(payload, &[0xAB, 0xCD]);
+/// ```
+#[proc_macro]
+pub fn splitbits_header(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = input.into();
+    let (bytes, template, settings) =
+        match parse_splitbits_input("splitbits_header", &input, Base::Binary, Precision::Standard, STRUCT_SETTINGS) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    let value: Expr = parse_quote! { header };
+    let check = literal_check(&template, &value, &settings, false);
+    let fields = extract_fields(&template, &value, &settings, Precision::Standard);
+    let (struct_definition, struct_value) = to_struct(&template, &value, &fields, &settings);
+    let t = template.width().to_token_stream();
+    assert!(template.logical_width() % 8 == 0,
+        "The template of splitbits_header! must be a whole number of bytes, but was {} bits.", template.logical_width());
+    let header_len = usize::from(template.logical_width() / 8);
+    let width_len = usize::from(template.width().bit_count() / 8);
+    let message = format!("The input slice must be at least as long as the header ({header_len} bytes).");
+    // A header that isn't a standard width fills the low bytes of the next larger standard type.
+    let header = if header_len == width_len {
+        quote! { #t::from_be_bytes(*header) }
+    } else {
+        quote! {
+            {
+                let mut bytes = [0u8; #width_len];
+                bytes[#width_len - #header_len..].copy_from_slice(header);
+                #t::from_be_bytes(bytes)
+            }
+        }
+    };
+    with_template_files(quote! {
+        {
+            #struct_definition
+
+            let (header, remainder) = <[u8]>::split_first_chunk::<#header_len>(#bytes).expect(#message);
+            let header = #header;
+            #check
+            (#struct_value, remainder)
+        }
+    })
+}
+
+/// Extract a single field from an integer, given the field's bit offset and width, rather than a
+/// template. Useful for pulling one field out of a wide value without counting out placeholders.
+/// Follows the same typing rules as [`splitbits!`]: 1-bit fields are bools, wider fields are the
+/// smallest integer type that will fit them.
+/// ```
+/// use splitbits::splitbits_field;
+///
+/// let register: u64 = 0x0000_0000_0000_5000;
+/// // Bits 14:12 of the register.
+/// let mode = splitbits_field!(register, "m", at = 12, width = 3);
+/// assert_eq!(mode, 0b101u8);
+///
+/// let enabled = splitbits_field!(register, "e", at = 14, width = 1);
+/// assert_eq!(enabled, true);
+/// ```
+#[proc_macro]
+pub fn splitbits_field(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: TokenStream = input.into();
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.clone())
+        .expect("splitbits_field! argument list should be formatted sanely");
+    let mut parts: Vec<_> = parts.into_iter().collect();
+    assert!(parts.len() >= 2,
+        "splitbits_field must take an input value, a field name, then its settings. Found:\n`{input}`");
+    let value = parts.remove(0);
+    let name = Template::template_string(&parts.remove(0));
+    let name = name.chars().next()
+        .filter(|_| name.chars().count() == 1)
+        .and_then(|name| Name::new(name).ok())
+        .unwrap_or_else(|| panic!("splitbits_field must take a single-letter field name, but found '{name}'."));
+
+    let settings = Settings::take("splitbits_field", &mut parts, &["at", "width"]);
+    assert!(parts.is_empty(), "splitbits_field must take its settings after its input and field name.");
+    let mask_offset = settings.integer("at").expect("splitbits_field must specify 'at', the offset of the field.");
+    let width = settings.integer("width").expect("splitbits_field must specify 'width', the width of the field.");
+    assert!(width > 0, "Fields cannot have zero bits.");
+    assert!(u16::from(mask_offset) + u16::from(width) <= 128, "Integers larger than u128 are not supported.");
+
+    // Extract from the smallest integer type that covers the field, regardless of the input's type.
+    let input_type = Type::for_nonstandard_template(mask_offset + width).unwrap();
+    let location = Location { width, mask_offset };
+    let field = Field::new(name, input_type, &value, Precision::Standard, None, &[location]);
+    with_template_files(field.to_token_stream())
 }
 
 /// Combine bits of multiple variables into a single variable as defined by a template.
@@ -651,6 +1703,37 @@ pub fn splithex_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::Tok
 /// assert_eq!(result,       0b1010_1010_1111_0000);
 /// ```
 ///
+/// If a field in the template doesn't have a variable of the same name in scope, compilation
+/// fails with a "cannot find value" error pointing at the template. Macros can't see which
+/// variables are in scope, so this error comes from the compiler rather than from combinebits!,
+/// and a compiler suggestion to replace the template with a similarly named variable should be
+/// ignored: declare the missing variable (or map the field to another variable) instead.
+///
+/// Variables with descriptive names can be captured instead by mapping template names to them with
+/// the names setting. Template names that aren't mapped are captured from single-letter variables:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let beginning: u8 = 0b1010_1010;
+/// let m: u8 = 0b1111;
+/// let end: u8 = 0b0000;
+/// let result = combinebits!(names(b = beginning, e = end), "bbbb bbbb mmmm eeee");
+/// assert_eq!(result,                                       0b1010_1010_1111_0000);
+/// ```
+///
+/// Fields spread across several structs (such as a parsed header and a computed payload) can be
+/// taken from those structs directly with the from setting, listing which fields each struct
+/// provides. Each struct can be any expression, such as a field of another struct. A field listed
+/// under two structs, or under none, fails to compile:
+/// ```
+/// use splitbits::{splitbits, combinebits};
+///
+/// let header = splitbits!(0b1010_0110u8, "hhhh ....");
+/// let payload = splitbits!(0b0011_1100u8, "pppp ffff");
+/// let result = combinebits!(from(header = h, payload = (p, f)), "hhhh pppp ffff ffff");
+/// assert_eq!(result,                                            0b1010_0011_0000_1100);
+/// ```
+///
 /// If descriptive variable names are desired, then variables can be passed in as arguments.
 /// These variables must occur in the same order in the argument list as the name characters occur
 /// in the template. The single character template names are ignored beyond this.
@@ -664,6 +1747,19 @@ pub fn splithex_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::Tok
 /// assert_eq!(result,                           0b1010_1010_1111_0000);
 /// ```
 ///
+/// Each argument is evaluated only once, even if its field is split into multiple segments.
+/// Arguments can be references, which are dereferenced automatically. This is convenient when the
+/// inputs are borrowed from a struct or a slice:
+/// ```
+/// use splitbits::combinebits;
+///
+/// struct Config { mode: u8, level: u8 }
+/// let config = Config { mode: 0b101, level: 0b1_0011 };
+/// let configs = [&config];
+/// let result = combinebits!(&configs[0].mode, &config.level, "mmml llll");
+/// assert_eq!(result,                                         0b1011_0011);
+/// ```
+///
 /// An input variable can be split into multiple segments by the template:
 /// ```
 /// use splitbits::combinebits;
@@ -697,12 +1793,136 @@ pub fn splithex_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::Tok
 /// assert_eq!(result,                                           0b11100000_10010000);
 /// ```
 ///
+/// Every bit of the result that isn't covered by a field or a '1' literal is zero. Fields are
+/// masked to their slots (unless `overflow=corrupt` is set), and placeholders are only allowed if
+/// `base` fills them, so no other bits can leak into the result:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let a: u8 = 0xFF;
+/// let result = combinebits!("0000 aa00");
+/// assert_eq!(result,       0b0000_1100);
+/// ```
+///
+/// Setting `map` takes the inputs from a map of field values keyed by their template names, such as
+/// a `BTreeMap<char, u128>` or a `HashMap<char, u32>`, for when the values are only known at
+/// runtime. Each value is converted to the type of the template first, following the overflow
+/// setting if it doesn't fit. A field that is missing from the map, or a key that isn't a field of
+/// the template, causes a panic:
+/// ```
+/// use std::collections::BTreeMap;
+/// use splitbits::combinebits;
+///
+/// let values = BTreeMap::from([('a', 0b101u128), ('b', 0b1_0010)]);
+/// let result = combinebits!(map=values, "aaab bbbb");
+/// assert_eq!(result,                   0b1011_0010);
+/// ```
+///
+/// Inputs listed in the optional setting are `Option`s. A `None` input contributes its default
+/// value to its slot (or zero, if no default is specified):
+/// ```
+/// use splitbits::combinebits;
+///
+/// let header: Option<u8> = Some(0b1010);
+/// let flags: Option<u8> = None;
+/// let checksum: Option<u8> = None;
+/// let result = combinebits!(optional(h, f = 0b11, c), header, flags, checksum, "hhhh ffcc");
+/// assert_eq!(result,                                                          0b1010_1100);
+/// ```
+///
+/// Fields listed in the constant setting take a fixed value instead of an input. Constant values
+/// follow the same overflow rules as inputs do:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let length: u8 = 0b101;
+/// let result = combinebits!(constant(v = 0x2, c = 0x3), length, "vvvc clll");
+/// assert_eq!(result,                                            0b0101_1101);
+/// ```
+///
+/// Fields listed in the const_check setting still take an input, but that input must equal the
+/// specified value. This is useful for a field that is only configurable in some builds. A
+/// mismatched input causes a panic, or a compile error if the input is an integer literal:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let version: u8 = 0x2;
+/// let length: u8 = 0b101;
+/// let result = combinebits!(const_check(v = 0x2), version, length, "vvvv vlll");
+/// assert_eq!(result,                                               0b0001_0101);
+/// ```
+///
+/// Setting `base` to an integer fills the placeholder positions of the template with the bits of
+/// that integer, rather than spelling out a wide constant with literals. Only the base value's bits
+/// at placeholder positions are used, so fields and literals are never overlapped by it:
+/// ```
+/// use splitbits::combinebits;
+///
+/// const PREAMBLE: u32 = 0xAAAA_AAAA;
+/// let k: u8 = 0x3;
+/// let l: u8 = 0x42;
+/// let result = combinebits!(base=PREAMBLE, ".... .... .... .... kkkk kkkk llll llll");
+/// assert_eq!(result, 0xAAAA_0342);
+/// ```
+///
+/// The lookup setting is the reverse of the one for [`splitbits!`]: the input of a field is a real
+/// value, and the index of that value in the field's table is what gets placed in the template.
+/// A value that isn't in the table causes a panic:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let d: u32 = 400;
+/// let e = true;
+/// let result = combinebits!(lookup(d = [100u32, 200, 400, 800, 1600, 3200, 6400, 12800]), "eddd 0000");
+/// assert_eq!(result, 0b1010_0000);
+/// ```
+///
+/// Setting `combine=xor` merges the fields and literals together with XOR rather than OR. Since
+/// fields and literals occupy separate positions in the template, this only makes a difference
+/// when they overlap, which is intentional in this mode: an oversized field (with
+/// `overflow=corrupt`) will flip the bits of the literals and fields before it, rather than setting
+/// them. This is useful for applying a whitening pattern to a value in one step:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let a: u8 = 0b1111_0000;
+/// // The field 'a' overflows into every position of the literal whitening pattern, flipping them.
+/// let result = combinebits!(combine=xor, overflow=corrupt, "1010 101a");
+/// assert_eq!(result,                                      0b0101_1010);
+/// ```
+///
+/// Setting `result` to the signed type of the template's width (such as `result=i16` for a 16-bit
+/// template) produces a signed integer. The combined bits are reinterpreted rather than converted,
+/// so the top bit of the template becomes the sign bit:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let s = true;
+/// let m: u16 = 0x7FFE;
+/// let result = combinebits!(result=i16, "smmm mmmm mmmm mmmm");
+/// assert_eq!(result, -2i16);
+/// ```
+///
+/// Setting `result=fit` produces the smallest unsigned type that can hold the highest bit that the
+/// fields, literals, and base value (if any) can set, rather than the type of the template's width:
+/// ```
+/// use splitbits::combinebits;
+///
+/// let a: u8 = 0b101;
+/// // The highest bit that can be set is bit 8, so the result is a u16 rather than a u32.
+/// let result: u16 = combinebits!(result=fit, "0000 0000 0000 0000 0000 0001 0000 0aaa");
+/// assert_eq!(result,                                             0b0001_0000_0101);
+/// ```
+///
 /// # Field overflow behavior
 /// If an input **value** is too large for its slot in the template, by default its most
 /// significant bits are truncated (but other overflow behavior options exist).
 ///
 /// Note that input variable **types** frequently have more bits than the slots that they go into,
-/// which is why overflow behavior is needed in the first place.
+/// which is why overflow behavior is needed in the first place. Integer literal arguments and
+/// constants, on the other hand, are known at compile time, so when overflowing would panic, a
+/// literal that is too large for its slot is a compile error instead. Other overflow settings
+/// apply to literals just like any other input.
 ///
 /// In each of the following examples, the value of "a" requires 7 bits to represent, but its slot
 /// in the template is only 6 bits wide. So "a" is too large, causing its first '1' bit to overflow.
@@ -720,6 +1940,8 @@ pub fn splithex_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::Tok
 /// ```
 ///
 /// ### overflow=truncate (same as default behavior)
+/// Keeps only the low bits that fit in the slot. Since every slot is a whole number of bits, this
+/// is the same as wrapping the value modulo the slot size, so there is no separate `wrap` option.
 /// ```
 /// use splitbits::combinebits;
 ///
@@ -768,9 +1990,52 @@ pub fn splithex_named_into_ux(input: proc_macro::TokenStream) -> proc_macro::Tok
 /// // Compiles to: assert!((a << 1) <= 0b01111110)
 /// let _ = combinebits!(overflow=panic, "0aaaaaa0");
 /// ```
+///
+/// ### overflow=debug_checked
+/// Results in a panic if "a" overflows its slot in debug builds, but truncates it like the default
+/// behavior in release builds. Mirrors how Rust's own integer overflow checks work.
+/// ```should_panic
+/// use splitbits::combinebits;
+///
+/// let a: u8 = 0b01100001;
+/// // Compiles to: debug_assert!(a <= 0b00111111); (a & 0b00111111) << 1
+/// let _ = combinebits!(overflow=debug_checked, "0aaaaaa0");
+/// ```
+///
+/// ### overflow=clamp
+/// For signed inputs: clamps each field to the signed range of its slot (-32 to 31 for a 6-bit
+/// slot), then stores it in two's complement. Unlike `saturate`, a negative value that is too
+/// large clamps to the most negative value of the slot, rather than wrapping. Unsigned inputs are
+/// clamped to the unsigned range of their slots (the same as `saturate`), and bools are stored as
+/// they are. Only supported by [`combinebits!`].
+/// ```
+/// use splitbits::combinebits;
+///
+/// let a: i8 = -100;
+/// // Clamped to -32, which is 0b100000 in 6 bits.
+/// let result = combinebits!(overflow=clamp, "0aaaaaa0");
+/// assert_eq!(result,                       0b01000000);
+/// ```
 #[proc_macro]
 pub fn combinebits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    combinebits_base(input, Base::Binary)
+    with_template_files(combinebits_base(input, Base::Binary))
+}
+
+/// Same as [`combinebits!`], except that the result is a `Result`, with an `Err(String)` in place of
+/// each runtime panic: an input that is too big for `overflow=panic`, an input that doesn't equal
+/// its `const_check` value, an input that isn't in its `lookup` table, and a missing or unknown key
+/// of a `map`. Other overflow settings can't fail. The error message requires `std`.
+/// ```
+/// use splitbits::try_combinebits;
+///
+/// let a: u8 = 0b01100001;
+/// let b: u8 = 0b1;
+/// assert!(try_combinebits!(overflow=panic, "0aaaaaa0").is_err());
+/// assert_eq!(try_combinebits!(overflow=panic, "0bbbbbb0"), Ok(0b00000010));
+/// ```
+#[proc_macro]
+pub fn try_combinebits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(combinebits_base_with(input, Base::Binary, true))
 }
 
 /// Same as [`combinebits!`] except the template uses hexadecimal digits rather than binary digits.
@@ -788,7 +2053,169 @@ pub fn combinebits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// ```
 #[proc_macro]
 pub fn combinehex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    combinebits_base(input, Base::Hexadecimal)
+    with_template_files(combinebits_base(input, Base::Hexadecimal))
+}
+
+/// Same as [`combinebits!`] except the template uses octal digits rather than binary digits. The
+/// bits to the left of the template (which pad it to a standard width) are zeros.
+/// ```
+/// use splitbits::combineoct;
+///
+/// let u: u8 = 0o7;
+/// let g: u8 = 0o5;
+/// let o: u8 = 0o4;
+/// let result = combineoct!("1ugo");
+/// assert_eq!(result,      0o1754u16);
+/// ```
+#[proc_macro]
+pub fn combineoct(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(combinebits_base(input, Base::Octal))
+}
+
+/// Create a builder for combining fields into an integer as defined by a template. The builder has
+/// a setter method for each field of the template, and `build()` combines the fields that have
+/// been set so far. Fields that haven't been set are zeroes. Useful when the fields are computed at
+/// different points in the code.
+///
+/// Each setter takes the smallest type that fits its field (`bool` for 1-bit fields).
+/// ```
+/// use splitbits::combinebits_builder;
+///
+/// let mut builder = combinebits_builder!("mmmm eeee cccc dddd");
+/// builder = builder.m(0b1010).e(0b0011);
+/// // ... later ...
+/// builder = builder.d(0b1111);
+/// assert_eq!(builder.build(), 0b1010_0011_0000_1111);
+/// ```
+///
+/// Literals in the template are always set, and the overflow setting is supported just as in
+/// [`combinebits!`]:
+/// ```
+/// use splitbits::combinebits_builder;
+///
+/// let builder = combinebits_builder!(overflow=saturate, "01aa aaab");
+/// assert_eq!(builder.a(0b11_1111).b(true).build(), 0b0111_1111);
+/// ```
+#[proc_macro]
+pub fn combinebits_builder(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
+        .expect("combinebits_builder! argument list should be formatted sanely");
+    let mut parts: Vec<_> = parts.into_iter().collect();
+    let settings = Settings::take("combinebits_builder", &mut parts, &["overflow"]);
+    Settings::reject_misplaced(&parts);
+    assert!(parts.len() == 1, "combinebits_builder must take exactly one argument after its settings: the template.");
+
+    let template = Template::from_expr(&parts[0], Base::Binary, Precision::Ux);
+    if template.has_placeholders() {
+        let bad_template = Template::template_string(&parts[0]);
+        panic!(
+            "Template ({bad_template}) must not have placeholders (periods) in it. \
+            Use literals instead as appropriate.");
+    }
+
+    let builder_name = template.to_builder_name();
+    let (names, types): (Vec<_>, Vec<_>) = template.field_types().into_iter()
+        .map(|(name, t)| (name.to_ident(), t.to_token_stream()))
+        .unzip();
+    let inputs: Vec<_> = template.field_types().into_iter()
+        .map(|(name, _)| {
+            let ident = name.to_ident();
+            (name, quote! { self.#ident })
+        })
+        .collect();
+    let t = template.width().to_token_stream();
+    let on_overflow = settings.overflow();
+    reject_clamp(on_overflow, "combinebits_builder");
+    let combined = template.combine(on_overflow, Operator::Or, None, &inputs);
+    with_template_files(quote! {
+        {
+            #[derive(Clone, Copy, Default)]
+            struct #builder_name {
+                #(#names: #types,)*
+            }
+
+            impl #builder_name {
+                #(
+                    fn #names(mut self, #names: #types) -> Self {
+                        self.#names = #names;
+                        self
+                    }
+                )*
+
+                fn build(&self) -> #t {
+                    (#combined)
+                }
+            }
+
+            #builder_name::default()
+        }
+    })
+}
+
+/// Combine integers into a single integer by placing each at a specified bit offset, rather than
+/// using a template. Bits that aren't covered by any input are zeroes. Useful when only a few
+/// fields of a wide value need to be set.
+///
+/// The width setting (one of `u8`, `u16`, `u32`, `u64`, or `u128`) is the type of the result. Each
+/// input is followed by its offset (`at`) and its width (`bits`) in the result.
+/// ```
+/// use splitbits::combinebits_at;
+///
+/// let mode: u8 = 0b101;
+/// let id: u8 = 0b1001;
+/// let result = combinebits_at!(width = u16, (mode, at = 12, bits = 3), (id, at = 0, bits = 4));
+/// assert_eq!(result, 0b0101_0000_0000_1001);
+/// ```
+///
+/// Inputs that are too large for their bits follow the overflow setting, just like
+/// [`combinebits!`]:
+/// ```
+/// use splitbits::combinebits_at;
+///
+/// let result = combinebits_at!(width = u8, overflow = saturate, (0xFFu8, at = 2, bits = 3));
+/// assert_eq!(result, 0b0001_1100);
+/// ```
+#[proc_macro]
+pub fn combinebits_at(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
+        .expect("combinebits_at! argument list should be formatted sanely");
+    let mut parts: Vec<_> = parts.into_iter().collect();
+    let settings = Settings::take("combinebits_at", &mut parts, &["width", "overflow"]);
+    let width = settings.width().expect("combinebits_at must specify the 'width' of its result, e.g. 'width = u16'.");
+    let on_overflow = settings.overflow();
+    reject_clamp(on_overflow, "combinebits_at");
+    assert!(!parts.is_empty(), "combinebits_at must take at least one input.");
+
+    let mut occupied = 0u128;
+    let mut field_streams = Vec::new();
+    for part in parts {
+        let Expr::Tuple(ExprTuple { elems, .. }) = part else {
+            panic!("Each input to combinebits_at must be formatted like '(input, at = 0, bits = 1)'.");
+        };
+        let mut elems: Vec<_> = elems.into_iter().collect();
+        assert!(!elems.is_empty() && Settings::parse(&elems[0]).is_none(),
+            "Each input to combinebits_at must start with its value.");
+        let value = elems.remove(0);
+        let input_settings = Settings::take("combinebits_at", &mut elems, &["at", "bits"]);
+        assert!(elems.is_empty(), "Each input to combinebits_at must be formatted like '(input, at = 0, bits = 1)'.");
+        let label = value.to_token_stream().to_string();
+        let mask_offset = input_settings.integer("at")
+            .unwrap_or_else(|| panic!("Input '{label}' must specify 'at', the offset of its bits."));
+        let bits = input_settings.integer("bits")
+            .unwrap_or_else(|| panic!("Input '{label}' must specify 'bits', the number of its bits."));
+        assert!(bits > 0, "Input '{label}' must have at least one bit.");
+        assert!(u16::from(mask_offset) + u16::from(bits) <= u16::from(width.bit_count()),
+            "Input '{label}' doesn't fit within a {width}.");
+
+        let location = Location { width: bits, mask_offset };
+        assert!(occupied & location.to_mask() == 0, "Input '{label}' overlaps the bits of another input.");
+        occupied |= location.to_mask();
+
+        let t = width.to_token_stream();
+        field_streams.push(location.place_field_segment(&quote! { #label }, &quote! { #t::from(#value) }, width, on_overflow));
+    }
+
+    with_template_files(quote! { #(#field_streams)|* })
 }
 
 /// Extract bits from multiple input integers by matching against input templates, then combine
@@ -872,11 +2299,25 @@ pub fn combinehex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// assert_eq!(output, 0b1111_0011_0000_1010);
 /// ```
 ///
+/// The output template can use a different base than the input templates, if the bits are easier
+/// to read that way:
+/// ```
+/// use splitbits::splithex_then_combine;
+///
+/// let output = splithex_then_combine!(
+///     output_base=binary,
+///     0xAB_CD, "xx ..",
+///     0x12_34, ".. yy",
+///              "xxxx xxxx 0000 0000 yyyy yyyy 1111 1111",
+/// );
+/// assert_eq!(output, 0b1010_1011_0000_0000_0011_0100_1111_1111);
+/// ```
+///
 /// Having all these features in one macro means that there are multiple ways to achieve an
 /// outcome, so consider which way leads to the best readability on a case-by-case basis.
 #[proc_macro]
 pub fn splitbits_then_combine(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    split_then_combine_base(input, Base::Binary)
+    with_template_files(split_then_combine_base(input, Base::Binary))
 }
 
 /// Same as [`splitbits_then_combine!`], except with hexadecimal digits in the template.
@@ -892,79 +2333,446 @@ pub fn splitbits_then_combine(input: proc_macro::TokenStream) -> proc_macro::Tok
 /// ```
 #[proc_macro]
 pub fn splithex_then_combine(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    split_then_combine_base(input, Base::Hexadecimal)
+    with_template_files(split_then_combine_base(input, Base::Hexadecimal))
 }
 
-/// Replace some of the bits in an integer with bits from other variables, as specified by a
-/// template. Placeholders (periods) mark bits that will not be replaced.
-///
-/// Limitation: Currently this macro doesn't take input variables as parameters, they must be
-/// captured from single-letter variables.
+/// Same as [`splitbits_then_combine!`], except with octal digits in the template.
 /// ```
-/// use splitbits::replacebits;
+/// use splitbits::splitoct_then_combine;
 ///
-/// let a: u16 = 0b101;
-/// let b: u8 = 0b00001;
-/// let c: u128 = 0b0101;
-/// let d = true;
-///
-/// let original = 0b1111_1111_0000_0000;
-/// let replaced = replacebits!(original, "aaab bbbb .d.. cccc");
-/// assert_eq!(replaced,                 0b1010_0001_0100_0101);
+/// // Swap the user and other permissions.
+/// let output = splitoct_then_combine!(0o4750u16, "sugo", "sogu");
+/// assert_eq!(output, 0o4057);
 /// ```
-///
-/// Literals can be placed in the template to override specific bits:
+#[proc_macro]
+pub fn splitoct_then_combine(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(split_then_combine_base(input, Base::Octal))
+}
+
+/// Rearrange the bits of a single integer: fields are extracted according to the first template,
+/// then placed into the positions that the second template specifies. The same as
+/// [`splitbits_then_combine!`] with a single input, but named for the common use-case of permuting
+/// bits, such as swapping bytes or nibbles, or relocating a bit field.
 /// ```
-/// use splitbits::replacebits;
+/// use splitbits::movebits;
 ///
-/// let a: u16 = 0b10;
-/// let result = replacebits!(0b10000001, "aa.11..0");
-/// assert_eq!(result,                   0b10011000);
-/// ```
+/// // Swap the nibbles of a byte.
+/// let swapped = movebits!(0b1011_0010, "hhhh llll", "llll hhhh");
+/// assert_eq!(swapped, 0b0010_1011);
 ///
-/// Input variables can be split across multiple template slots:
+/// // Swap the bytes of a u16.
+/// let swapped = movebits!(0xBEEFu16, "hhhh hhhh llll llll", "llll llll hhhh hhhh");
+/// assert_eq!(swapped, 0xEFBE);
 /// ```
-/// use splitbits::replacebits;
 ///
-/// let a: u16 = 0b101;
-/// let result = replacebits!(0b1111_1111, "a... ..aa");
-/// assert_eq!(result,                    0b1111_1101);
+/// Any permutation can be expressed by naming each bit individually, such as reversing the bits:
 /// ```
+/// use splitbits::movebits;
 ///
-/// # Field overflow behavior
-/// If an input **value** is too large for its slot in the template, by default its most
-/// significant bits are truncated (but other overflow behavior options exist).
-///
-/// Note that input variable **types** frequently have more bits than the slots that they go into,
-/// which is why overflow behavior is needed in the first place.
-///
-/// In each of the following examples, the value of "a" requires 7 bits to represent, but its slot
-/// in the template is only 6 bits wide. So "a" is too large, causing its first '1' bit to overflow.
-///
-/// ### Default behavior (no overflow setting specified)
-/// Truncate the most significant bits of the field when an overflow occurs so that the field still
-/// fits.
+/// let reversed = movebits!(0b1100_1010, "abcd efgh", "hgfe dcba");
+/// assert_eq!(reversed, 0b0101_0011);
 /// ```
-/// use splitbits::replacebits;
-///
-/// let original: u8 = 0b00001000;
 ///
-/// let a: u8 = 0b01100001;
-/// // Compiles to: ((a << 1) & 0b01111110) | (original & 0b10000001)
-/// let replaced = replacebits!(original, ".aaaaaa.");
-/// assert_eq!(replaced,                 0b01000010);
+/// Unlike [`splitbits_then_combine!`], the output template can't refer to bits of other inputs,
+/// but it can still contain literals:
 /// ```
+/// use splitbits::movebits;
 ///
-/// ### overflow=truncate (same as default behavior)
+/// let relocated = movebits!(0b0000_0101, ".... .fff", "1fff 0000");
+/// assert_eq!(relocated, 0b1101_0000);
 /// ```
-/// use splitbits::replacebits;
-///
-/// let original: u8 = 0b00001000;
-///
-/// let a: u8 = 0b01100001;
-/// // Compiles to: ((a << 1) & 0b01111110) | (original & 0b10000001)
-/// let replaced = replacebits!(overflow=truncate, original, ".aaaaaa.");
-/// assert_eq!(replaced,                                    0b01000010);
+#[proc_macro]
+pub fn movebits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.clone().into())
+        .expect("movebits! argument list should be formatted sanely");
+    assert!(parts.len() == 3,
+        "movebits! must take exactly three arguments: an input value, an input template, then an output template.");
+    with_template_files(split_then_combine_base(input, Base::Binary))
+}
+
+/// Extract fields from several words at once, such as for a record or descriptor that spans
+/// multiple integers, producing a single struct that holds the fields of every word. Takes a
+/// sequence of `(input, template)` pairs. Field names must be unique across all the templates.
+/// ```
+/// use splitbits::splitbits_record;
+///
+/// let words: [u16; 3] = [0xA1B2, 0x00FF, 0x1234];
+/// let descriptor = splitbits_record!(
+///     (words[0], "aaaa aaaa bbbb bbbb"),
+///     (words[1], "c... .... dddd dddd"),
+///     (words[2], "llll llll llll llll"),
+/// );
+/// assert_eq!(descriptor.a, 0xA1);
+/// assert_eq!(descriptor.b, 0xB2);
+/// assert_eq!(descriptor.c, false);
+/// assert_eq!(descriptor.d, 0xFF);
+/// assert_eq!(descriptor.l, 0x1234);
+/// ```
+#[proc_macro]
+pub fn splitbits_record(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
+        .expect("splitbits_record! argument list should be formatted sanely");
+    if parts.is_empty() {
+        return syn::Error::new(Span::call_site(),
+            "splitbits_record! must take at least one (input, template) pair.")
+            .to_compile_error()
+            .into();
+    }
+
+    let settings = Settings::empty("splitbits_record");
+    let mut bindings = Vec::new();
+    let mut struct_names = Vec::new();
+    let mut fields: Vec<(usize, Field)> = Vec::new();
+    for (index, part) in parts.iter().enumerate() {
+        let pair = match part {
+            Expr::Tuple(ExprTuple { elems, .. }) if elems.len() == 2 => elems,
+            _ => return syn::Error::new(part.span(),
+                "Each argument of splitbits_record! must be an (input, template) pair.")
+                .to_compile_error()
+                .into(),
+        };
+
+        // Each input is evaluated only once, so it gets a binding of its own unless it's trivial.
+        let value = match &pair[0] {
+            value @ (Expr::Lit(_) | Expr::Path(_)) => value.clone(),
+            value => {
+                let word = format_ident!("_word{index}");
+                bindings.push(quote! { let #word = #value; });
+                parse_quote! { #word }
+            }
+        };
+
+        let template = Template::from_expr(&pair[1], Base::Binary, Precision::Standard);
+        bindings.push(literal_check(&template, &value, &settings, false));
+        struct_names.push(template.to_struct_name().to_string());
+        for field in extract_fields(&template, &value, &settings, Precision::Standard) {
+            if let Some((other_index, _)) = fields.iter().find(|(_, other)| other.name() == field.name()) {
+                panic!("Field '{}' must only be in one template of splitbits_record!, \
+                    but is in templates {other_index} and {index}.", field.name());
+            }
+
+            fields.push((index, field));
+        }
+    }
+
+    let struct_name = format_ident!("Record·{}", struct_names.join("·"));
+    let names: Vec<_> = fields.iter().map(|(_, field)| field.name().to_ident()).collect();
+    let types = fields.iter().map(|(_, field)| field.output_type());
+    let values = fields.iter().map(|(_, field)| field.to_token_stream());
+    with_template_files(quote! {
+        {
+            #(#bindings)*
+
+            struct #struct_name {
+                #(#names: #types,)*
+            }
+
+            #struct_name {
+                #(#names: #values,)*
+            }
+        }
+    })
+}
+
+/// Extract the same field from every element of an array, producing an array of that field's
+/// values. Useful for packed data such as a series of sensor readings, when only one field's series
+/// is needed. Takes the array, a template for its elements, then the name of the field to extract.
+/// Accepts the same settings as [`splitbits!`] that apply to individual fields.
+/// ```
+/// use splitbits::splitbits_column;
+///
+/// let readings: [u16; 3] = [0x1_0A5, 0x2_0B6, 0x3_0C7];
+/// let channels = splitbits_column!(readings, "cccc .... .... ....", c);
+/// assert_eq!(channels, [1u8, 2, 3]);
+/// let values = splitbits_column!(readings, ".... vvvv vvvv vvvv", v);
+/// assert_eq!(values, [0x0A5u16, 0x0B6, 0x0C7]);
+/// ```
+#[proc_macro]
+pub fn splitbits_column(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
+        .expect("splitbits_column! argument list should be formatted sanely");
+    let mut parts: Vec<Expr> = parts.into_iter().collect();
+    let name = match parts.pop() {
+        Some(Expr::Path(path)) if path.path.get_ident().is_some() => path.path.get_ident().unwrap().to_string(),
+        _ => return syn::Error::new(Span::call_site(),
+            "splitbits_column! must take an array, a template, then the name of the field to extract.")
+            .to_compile_error()
+            .into(),
+    };
+    let name = Name::bracketed(&name)
+        .unwrap_or_else(|err| panic!("Invalid field name passed to splitbits_column!. {err}"));
+
+    let input = quote! { #(#parts),* };
+    let (array, template, settings) =
+        match parse_splitbits_input("splitbits_column", &input, Base::Binary, Precision::Standard, &[]) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    let element: Expr = parse_quote! { element };
+    let check = literal_check(&template, &element, &settings, false);
+    let field = extract_fields(&template, &element, &settings, Precision::Standard)
+        .into_iter()
+        .find(|field| field.name() == name)
+        .unwrap_or_else(|| panic!("Field '{name}' was passed to splitbits_column!, but isn't in the template."));
+    let value = field.to_token_stream();
+    with_template_files(quote! { (#array).map(|element| { #check #value }) })
+}
+
+/// Extract fields into a variant of a user-declared enum, where the variant (and the template that
+/// its fields are extracted with) is chosen by the value of a discriminant field. Takes the path of
+/// the enum, the input value, the name of the discriminant field, then a match-like block of arms.
+/// Each arm maps a pattern for the discriminant's value to a template and the variant to produce.
+///
+/// Every variant must have a named field for each field of its template, including the
+/// discriminant, which must be in the same position in every template. The result is an `Option`,
+/// which is `None` if no arm matches the discriminant (unless there is a wildcard arm):
+/// ```
+/// use splitbits::splitbits_enum;
+///
+/// #[derive(PartialEq, Debug)]
+/// enum Packet {
+///     Data { t: u8, a: u8 },
+///     Ack { t: u8, s: bool, b: u8 },
+/// }
+///
+/// let data = splitbits_enum!(Packet, 0b0010_1101u8, t, {
+///     0 => "ttaa aaaa" as Data,
+///     1 => "ttsb bbbb" as Ack,
+/// });
+/// assert_eq!(data, Some(Packet::Data { t: 0, a: 0b10_1101 }));
+///
+/// let ack = splitbits_enum!(Packet, 0b0110_0011u8, t, {
+///     0 => "ttaa aaaa" as Data,
+///     1 => "ttsb bbbb" as Ack,
+/// });
+/// assert_eq!(ack, Some(Packet::Ack { t: 1, s: true, b: 0b0_0011 }));
+/// ```
+#[proc_macro]
+pub fn splitbits_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parser = |stream: syn::parse::ParseStream| {
+        let enum_path: syn::Path = stream.parse()?;
+        stream.parse::<Token![,]>()?;
+        let value: Expr = stream.parse()?;
+        stream.parse::<Token![,]>()?;
+        let discriminant: syn::Ident = stream.parse()?;
+        stream.parse::<Token![,]>()?;
+        let content;
+        syn::braced!(content in stream);
+        let mut arms = Vec::new();
+        while !content.is_empty() {
+            arms.push(content.parse::<syn::Arm>()?);
+        }
+
+        stream.parse::<Option<Token![,]>>()?;
+        Ok((enum_path, value, discriminant, arms))
+    };
+    let (enum_path, value, discriminant, arms) = match Parser::parse2(parser, input.into()) {
+        Ok(parsed) => parsed,
+        Err(err) => return syn::Error::new(err.span(), format!(
+            "splitbits_enum! must take an enum, an input value, the name of the discriminant field, \
+            then a block of arms such as `{{ 0 => \"ttaa aaaa\" as Data }}`. {err}"))
+            .to_compile_error()
+            .into(),
+    };
+    let discriminant = Name::bracketed(&discriminant.to_string())
+        .unwrap_or_else(|err| panic!("Invalid discriminant name passed to splitbits_enum!. {err}"));
+    assert!(!arms.is_empty(), "splitbits_enum! must have at least one arm.");
+
+    let (binding, value) = bind_value(value);
+    let settings = Settings::empty("splitbits_enum");
+    let mut discriminant_position = None;
+    let mut discriminant_value = None;
+    let mut match_arms = Vec::new();
+    for arm in &arms {
+        assert!(arm.guard.is_none(), "The arms of splitbits_enum! must not have guards.");
+        let Expr::Cast(cast) = &*arm.body else {
+            panic!("Each arm of splitbits_enum! must be a template then a variant, such as `0 => \"ttaa aaaa\" as Data`.");
+        };
+        let syn::Type::Path(variant) = &*cast.ty else {
+            panic!("Each arm of splitbits_enum! must name a variant of the enum after 'as'.");
+        };
+
+        let template = Template::from_expr(&cast.expr, Base::Binary, Precision::Standard);
+        let mask = template.field_mask(discriminant).unwrap_or_else(|| panic!(
+            "Discriminant field '{discriminant}' must be in every template of splitbits_enum!, but isn't in '{}'.",
+            Template::template_string(&cast.expr)));
+        let position = (template.width(), mask);
+        assert!(discriminant_position.is_none_or(|expected| expected == position),
+            "Discriminant field '{discriminant}' must be in the same position in every template of splitbits_enum!.");
+        discriminant_position = Some(position);
+
+        let fields = extract_fields(&template, &value, &settings, Precision::Standard);
+        if discriminant_value.is_none() {
+            discriminant_value = fields.iter()
+                .find(|field| field.name() == discriminant)
+                .map(Field::to_token_stream);
+        }
+
+        // Only the literals of the arm that matched are checked.
+        let check = literal_check(&template, &value, &settings, false);
+        let names = fields.iter().map(|field| field.name().to_ident());
+        let values = fields.iter().map(Field::to_token_stream);
+        let pattern = &arm.pat;
+        match_arms.push(quote! {
+            #pattern => {
+                #check
+                ::core::option::Option::Some(#enum_path::#variant { #(#names: #values,)* })
+            }
+        });
+    }
+
+    // A wildcard arm already covers every discriminant value, so another would be unreachable.
+    if !arms.iter().any(|arm| matches!(arm.pat, syn::Pat::Wild(_))) {
+        match_arms.push(quote! { _ => ::core::option::Option::None, });
+    }
+
+    with_template_files(quote! {
+        {
+            #binding
+            match #discriminant_value {
+                #(#match_arms)*
+            }
+        }
+    })
+}
+
+/// Check at compile time that two templates are compatible: every field name that they share must
+/// have the same width in both. This is the same check that [`splitbits_then_combine!`] makes
+/// between its input and output templates, but without a value to transform, so template pairs that
+/// are used in separate macro calls can be checked on their own. Expands to nothing if the templates
+/// are compatible, and to a compile error otherwise.
+/// ```
+/// use splitbits::assert_templates_compatible;
+///
+/// assert_templates_compatible!("aaaa bbbb", "bbbb aaaa");
+/// // Names that are only in one of the templates aren't checked.
+/// assert_templates_compatible!("aaaa bbbb", "00aa aacc");
+/// ```
+///
+/// A mismatch fails to compile:
+/// ```compile_fail
+/// use splitbits::assert_templates_compatible;
+///
+/// assert_templates_compatible!("aaaa bbbb", "bbba aaaa");
+/// ```
+#[proc_macro]
+pub fn assert_templates_compatible(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
+        .expect("assert_templates_compatible! argument list should be formatted sanely");
+    let parts: Vec<Expr> = parts.into_iter().collect();
+    if parts.len() != 2 {
+        return syn::Error::new(Span::call_site(),
+            "assert_templates_compatible! must take exactly two arguments: the two templates to compare.")
+            .to_compile_error()
+            .into();
+    }
+
+    let first = Template::from_expr(&parts[0], Base::Binary, Precision::Standard);
+    let second = Template::from_expr(&parts[1], Base::Binary, Precision::Standard);
+    let errors = first.width_mismatches(&second).into_iter()
+        .map(|(name, width, other_width)| syn::Error::new(second.span(), format!(
+            "The width of field '{name}' must match between the templates, \
+            but it has {width} bits in the first template and {other_width} bits in the second.")))
+        .reduce(|mut errors, error| {
+            errors.combine(error);
+            errors
+        });
+    with_template_files(errors.map_or_else(TokenStream::new, |errors| errors.to_compile_error()))
+}
+
+/// Replace some of the bits in an integer with bits from other variables, as specified by a
+/// template. Placeholders (periods) mark bits that will not be replaced.
+///
+/// Limitation: Currently this macro doesn't take input variables as parameters, they must be
+/// captured from single-letter variables.
+/// ```
+/// use splitbits::replacebits;
+///
+/// let a: u16 = 0b101;
+/// let b: u8 = 0b00001;
+/// let c: u128 = 0b0101;
+/// let d = true;
+///
+/// let original = 0b1111_1111_0000_0000;
+/// let replaced = replacebits!(original, "aaab bbbb .d.. cccc");
+/// assert_eq!(replaced,                 0b1010_0001_0100_0101);
+/// ```
+///
+/// Literals can be placed in the template to override specific bits:
+/// ```
+/// use splitbits::replacebits;
+///
+/// let a: u16 = 0b10;
+/// let result = replacebits!(0b10000001, "aa.11..0");
+/// assert_eq!(result,                   0b10011000);
+/// ```
+///
+/// Input variables can be split across multiple template slots:
+/// ```
+/// use splitbits::replacebits;
+///
+/// let a: u16 = 0b101;
+/// let result = replacebits!(0b1111_1111, "a... ..aa");
+/// assert_eq!(result,                    0b1111_1101);
+/// ```
+///
+/// If the target is wider than the template, set width to the target's type. Only the low bits of
+/// the target are replaced, and its upper bits are kept as-is:
+/// ```
+/// use splitbits::replacebits;
+///
+/// let a: u8 = 0b0101;
+/// let target: u32 = 0xDEAD_BEEF;
+/// let result = replacebits!(width=u32, target, "aaaa ....");
+/// assert_eq!(result, 0xDEAD_BE5F);
+/// ```
+///
+/// Setting `clear_unused=true` zeroes every bit of the result that isn't covered by a field or a
+/// '1' literal, rather than keeping the target's bits there. This includes placeholder positions
+/// and, for a wider target, every bit above the template:
+/// ```
+/// use splitbits::replacebits;
+///
+/// let a: u8 = 0b0101;
+/// let target: u32 = 0xDEAD_BEEF;
+/// let result = replacebits!(width=u32, clear_unused=true, target, "aaaa ..1.");
+/// assert_eq!(result, 0x0000_0052);
+/// ```
+///
+/// # Field overflow behavior
+/// If an input **value** is too large for its slot in the template, by default its most
+/// significant bits are truncated (but other overflow behavior options exist).
+///
+/// Note that input variable **types** frequently have more bits than the slots that they go into,
+/// which is why overflow behavior is needed in the first place.
+///
+/// In each of the following examples, the value of "a" requires 7 bits to represent, but its slot
+/// in the template is only 6 bits wide. So "a" is too large, causing its first '1' bit to overflow.
+///
+/// ### Default behavior (no overflow setting specified)
+/// Truncate the most significant bits of the field when an overflow occurs so that the field still
+/// fits.
+/// ```
+/// use splitbits::replacebits;
+///
+/// let original: u8 = 0b00001000;
+///
+/// let a: u8 = 0b01100001;
+/// // Compiles to: ((a << 1) & 0b01111110) | (original & 0b10000001)
+/// let replaced = replacebits!(original, ".aaaaaa.");
+/// assert_eq!(replaced,                 0b01000010);
+/// ```
+///
+/// ### overflow=truncate (same as default behavior)
+/// ```
+/// use splitbits::replacebits;
+///
+/// let original: u8 = 0b00001000;
+///
+/// let a: u8 = 0b01100001;
+/// // Compiles to: ((a << 1) & 0b01111110) | (original & 0b10000001)
+/// let replaced = replacebits!(overflow=truncate, original, ".aaaaaa.");
+/// assert_eq!(replaced,                                    0b01000010);
 /// ```
 ///
 /// ### overflow=corrupt
@@ -1011,55 +2819,674 @@ pub fn splithex_then_combine(input: proc_macro::TokenStream) -> proc_macro::Toke
 /// // Compiles to: assert!((a << 1) <= 0b01111110))
 /// let _ = replacebits!(overflow=panic, original, ".aaaaaa.");
 /// ```
+///
+/// ### overflow=debug_checked
+/// Results in a panic if "a" overflows its slot in debug builds, but truncates it like the default
+/// behavior in release builds.
+/// ```should_panic
+/// use splitbits::replacebits;
+///
+/// let original: u8 = 0b00001000;
+///
+/// let a: u8 = 0b01100001;
+/// let _ = replacebits!(overflow=debug_checked, original, ".aaaaaa.");
+/// ```
+///
+/// ### Per-field overflow
+/// Each field can have its own overflow behavior instead. Fields that aren't listed use the default.
+/// ```should_panic
+/// use splitbits::replacebits;
+///
+/// let original: u8 = 0b00001000;
+///
+/// // 'c' comes from a trusted constant, so it is truncated. 'a' must fit, so it panics.
+/// let a: u8 = 0b10001;
+/// let c: u8 = 0b111;
+/// let _ = replacebits!(overflow(a = panic, c = truncate), original, "aaaa ..cc");
+/// ```
 #[proc_macro]
 pub fn replacebits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    replacebits_base(&input, Base::Binary)
+    with_template_files(replacebits_base(&input, Base::Binary, false))
+}
+
+/// Same as [`replacebits!`], except the digits in the template are hexadecimal rather than binary.
+/// ```
+/// use splitbits::replacehex;
+///
+/// let w = 0xABC;
+/// let x = 0x01234;
+/// let y = 0x9876;
+/// let z: u8 = 5;
+///
+/// let input = 0x555_5555_5F55_5555;
+/// let output = replacehex!(input, "wwwx xxxx .z.. yyyy");
+/// assert_eq!(output,             0xABC0_1234_5555_9876);
+/// ```
+#[proc_macro]
+pub fn replacehex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(replacebits_base(&input, Base::Hexadecimal, false))
+}
+
+/// Same as [`replacebits!`], except the digits in the template are octal rather than binary. The
+/// bits to the left of the template (which pad it to a standard width) are left in place.
+/// ```
+/// use splitbits::replaceoct;
+///
+/// let g: u8 = 0o0;
+/// let o: u8 = 0o0;
+///
+/// let output = replaceoct!(0o4755u16, "..go");
+/// assert_eq!(output,       0o4700);
+/// ```
+#[proc_macro]
+pub fn replaceoct(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(replacebits_base(&input, Base::Octal, false))
+}
+
+/// Same as [`replacebits!`], except the values that the fields had before they were replaced are
+/// returned too, as `(new_value, old_fields)`. The old fields are a tuple in template order (or a
+/// single value if there is only one field), the same as [`splitbits_named!`]. The target is only
+/// evaluated once. Useful for read-modify-write sequences that need to log the previous settings.
+/// ```
+/// use splitbits::replacebits_swap;
+///
+/// let config: u8 = 0b1010_0110;
+/// let m: u8 = 0b01;
+/// let s: u8 = 0b111;
+/// let (config, (old_m, old_s)) = replacebits_swap!(config, "mm.. .sss");
+/// assert_eq!(config, 0b0110_0111);
+/// assert_eq!(old_m,  0b10);
+/// assert_eq!(old_s,  0b110);
+/// ```
+#[proc_macro]
+pub fn replacebits_swap(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    with_template_files(replacebits_base(&input, Base::Binary, true))
+}
+
+/// Check whether the literal bits of an integer match the literals in a template, without
+/// extracting any fields. Names and placeholders in the template are ignored. Useful for
+/// classifying a value (e.g. by a magic number or opcode prefix) before fully decoding it.
+/// ```
+/// use splitbits::{matches_template, splitbits};
+///
+/// let frame: u16 = 0b1010_0110_1100_0011;
+/// if matches_template!(frame, "1010 .... .... ....") {
+///     let fields = splitbits!(frame, ".... aaaa bbbb bbbb");
+///     assert_eq!(fields.a, 0b0110);
+/// } else {
+///     unreachable!();
+/// }
+///
+/// assert!(!matches_template!(frame, "0101 .... .... ...."));
+/// assert!(matches_template!(frame, ".... .... 11.. ..11"));
+/// ```
+#[proc_macro]
+pub fn matches_template(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
+        .expect("matches_template! argument list should be formatted sanely");
+    let parts: Vec<_> = parts.into_iter().collect();
+    Settings::reject_misplaced(&parts);
+    assert!(parts.len() == 2, "matches_template must take exactly two arguments: an input value then a template.");
+
+    let template = Template::from_expr(&parts[1], Base::Binary, Precision::Standard);
+    let value = &parts[0];
+    let result = template.matches_literal(value);
+    with_template_files(quote! { (#result) })
+}
+
+/// Define a newtype over a whole integer, with an accessor method for each field of a template.
+/// Unlike [`splitbits!`], which extracts every field up front, each accessor extracts its field
+/// only when it's called, so reading a single field of a wide register doesn't decode the rest.
+/// Since the type is defined at the call site, it can be named (and passed around) like any other.
+/// Attributes and a visibility can precede the type name, and the visibility also applies to the
+/// wrapped integer and the accessors. Converting an integer with `From` panics if it doesn't match
+/// the literals of the template, but constructing the type directly doesn't check them.
+/// ```
+/// use splitbits::splitbits_lazy;
+///
+/// splitbits_lazy!(#[derive(Clone, Copy)] pub Status, "eeee .... cccc cccc");
+///
+/// let status = Status(0xA0_42);
+/// assert_eq!(status.e(), 0xA);
+/// assert_eq!(status.c(), 0x42);
+/// assert_eq!(u16::from(status), 0xA0_42);
+/// assert_eq!(Status::from(0x1234).c(), 0x34);
+/// ```
+#[proc_macro]
+pub fn splitbits_lazy(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parser = |stream: syn::parse::ParseStream| {
+        let attributes = stream.call(syn::Attribute::parse_outer)?;
+        let visibility: syn::Visibility = stream.parse()?;
+        let name: syn::Ident = stream.parse()?;
+        stream.parse::<Token![,]>()?;
+        let template: Expr = stream.parse()?;
+        stream.parse::<Option<Token![,]>>()?;
+        Ok((attributes, visibility, name, template))
+    };
+    let (attributes, visibility, name, template) = match Parser::parse2(parser, input.into()) {
+        Ok(parsed) => parsed,
+        Err(err) => return syn::Error::new(err.span(), format!(
+            "splitbits_lazy! must take the name of the type to define, then a template. {err}"))
+            .to_compile_error()
+            .into(),
+    };
+
+    let template = Template::from_expr(&template, Base::Binary, Precision::Standard);
+    let t = template.width().to_token_stream();
+    let fields = template.extract_fields(&parse_quote! { self.0 }, None);
+    // The literals are checked when a value is converted, since the accessors can't fail.
+    let check = template.check_literals(&parse_quote! { value }, false);
+    let accessors = fields.iter().map(|field| {
+        let accessor = field.name().to_ident();
+        let output_type = field.output_type();
+        let value = field.to_token_stream();
+        quote! {
+            #visibility fn #accessor(&self) -> #output_type {
+                #value
+            }
+        }
+    });
+
+    with_template_files(quote! {
+        #(#attributes)*
+        #visibility struct #name(#visibility #t);
+
+        impl #name {
+            #(#accessors)*
+        }
+
+        impl ::core::convert::From<#t> for #name {
+            fn from(value: #t) -> Self {
+                #check
+                Self(value)
+            }
+        }
+
+        impl ::core::convert::From<#name> for #t {
+            fn from(value: #name) -> Self {
+                value.0
+            }
+        }
+    })
+}
+
+/// Define consts for the literal bits of a template, so that code outside of macro calls can check
+/// or strip a magic number without repeating it. For a given prefix X, `X_LITERAL_MASK` has a '1'
+/// at every literal position of the template, and `X_LITERAL_VALUE` has the values of the literals.
+/// Both have the type of the template's width. Names and placeholders are ignored. A visibility
+/// can precede the prefix:
+/// ```
+/// use splitbits::template_consts;
+///
+/// template_consts!(pub FRAME, "1010 .... aaaa 0011");
+/// assert_eq!(FRAME_LITERAL_MASK, 0b1111_0000_0000_1111u16);
+/// assert_eq!(FRAME_LITERAL_VALUE, 0b1010_0000_0000_0011u16);
+///
+/// let frame: u16 = 0b1010_1111_0110_0011;
+/// assert!(frame & FRAME_LITERAL_MASK == FRAME_LITERAL_VALUE);
+/// ```
+///
+/// The number of bits in each category of the template is defined too, as `u32`s: `X_FIELD_BITS`,
+/// `X_PLACEHOLDER_BITS`, and `X_LITERAL_BITS`. Every bit is in exactly one category, so they sum to
+/// the width of the template. This is useful for checking a template against the bit allocation
+/// table of a datasheet, such as that a register has exactly 4 reserved bits:
+/// ```
+/// use splitbits::template_consts;
+///
+/// template_consts!(CONTROL, "1010 .... aaaa bbbb");
+/// assert_eq!(CONTROL_FIELD_BITS, 8);
+/// assert_eq!(CONTROL_PLACEHOLDER_BITS, 4);
+/// assert_eq!(CONTROL_LITERAL_BITS, 4);
+/// ```
+#[proc_macro]
+pub fn template_consts(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parser = |stream: syn::parse::ParseStream| {
+        let visibility: syn::Visibility = stream.parse()?;
+        let prefix: syn::Ident = stream.parse()?;
+        stream.parse::<Token![,]>()?;
+        let template: Expr = stream.parse()?;
+        stream.parse::<Option<Token![,]>>()?;
+        Ok((visibility, prefix, template))
+    };
+    let (visibility, prefix, template) = match Parser::parse2(parser, input.into()) {
+        Ok(parsed) => parsed,
+        Err(err) => return syn::Error::new(err.span(), format!(
+            "template_consts! must take a name prefix for the consts then a template. {err}"))
+            .to_compile_error()
+            .into(),
+    };
+
+    let template = Template::from_expr(&template, Base::Binary, Precision::Standard);
+    let t = template.width().to_token_stream();
+    let mask_name = format_ident!("{prefix}_LITERAL_MASK");
+    let value_name = format_ident!("{prefix}_LITERAL_VALUE");
+    let mask = template.literal_mask();
+    let value = template.literal_value();
+    let field_bits_name = format_ident!("{prefix}_FIELD_BITS");
+    let placeholder_bits_name = format_ident!("{prefix}_PLACEHOLDER_BITS");
+    let literal_bits_name = format_ident!("{prefix}_LITERAL_BITS");
+    let (field_bits, placeholder_bits, literal_bits) = template.bit_counts();
+    with_template_files(quote! {
+        #visibility const #mask_name: #t = #mask as #t;
+        #visibility const #value_name: #t = #value as #t;
+        #visibility const #field_bits_name: u32 = #field_bits;
+        #visibility const #placeholder_bits_name: u32 = #placeholder_bits;
+        #visibility const #literal_bits_name: u32 = #literal_bits;
+    })
+}
+
+/// Check that splitting a value with a template and then combining its fields with the same
+/// template gives back the original value, for a set of sample values: all zeros, all ones, and a
+/// few bit patterns. Only the bits covered by fields are compared, since placeholders and literals
+/// aren't extracted. Panics if any sample doesn't survive the round trip. Meant to be the body of a
+/// test, so that each template in use gets coverage of its own masking:
+/// ```
+/// use splitbits::splitbits_roundtrip_test;
+///
+/// // Typically inside of a #[test] fn.
+/// splitbits_roundtrip_test!("aaaa bbbb");
+/// splitbits_roundtrip_test!("aa.. bbaa cccc cccc 1010 .... dddd dddd");
+/// ```
+#[proc_macro]
+pub fn splitbits_roundtrip_test(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
+        .expect("splitbits_roundtrip_test! argument list should be formatted sanely");
+    let parts: Vec<_> = parts.into_iter().collect();
+    Settings::reject_misplaced(&parts);
+    assert!(parts.len() == 1, "splitbits_roundtrip_test! must take exactly one argument: a template.");
+
+    let template = Template::from_expr(&parts[0], Base::Binary, Precision::Standard);
+    let template_string = Template::template_string(&parts[0]);
+    let t = template.width().to_token_stream();
+    let max = u128::MAX >> (128 - u32::from(template.width().bit_count()));
+    let samples = [0, u128::MAX, 0x5555_5555_5555_5555_5555_5555_5555_5555, 0xAAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA,
+        0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F, 0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210]
+        .map(|sample| sample & max);
+
+    // Each field is bound to its own local, so that field names can't collide with other locals.
+    let fields = template.extract_fields(&parse_quote! { value }, None);
+    let locals: Vec<_> = fields.iter().map(|field| format_ident!("field_{}", field.name().to_ident())).collect();
+    let values: Vec<TokenStream> = fields.iter().map(Field::to_token_stream).collect();
+    let inputs: Vec<(Name, TokenStream)> = fields.iter().zip(&locals)
+        .map(|(field, local)| (field.name(), quote! { (#local as #t) }))
+        .collect();
+    let combined = template.combine(OnOverflow::Truncate, Operator::Or, None, &inputs);
+    let field_mask = max & !(template.placeholder_mask() | template.literal_mask());
+    with_template_files(quote! {
+        for value in [#(#samples as #t),*] {
+            #(let #locals = #values;)*
+            let combined: #t = #combined;
+            assert_eq!(combined & (#field_mask as #t), value & (#field_mask as #t),
+                "Splitting then combining {:#X} with template '{}' didn't give back its field bits.",
+                value, #template_string);
+        }
+    })
+}
+
+// The settings that are supported by every macro in the splitbits! family, via extract_fields().
+const EXTRACTION_SETTINGS: &[&str] =
+    &["min", "bools_only", "invert", "align", "conversion", "ratio", "range", "placeholder", "bytes", "lookup", "sign_magnitude", "order",
+        "bit_order", "rotate_input", "field_order", "char", "map_into", "signed", "warn_noncontiguous", "noncontiguous", "group"];
+
+/* A compile error for the _ux macros if neither of the crates that provide exact-width integer types
+ * is enabled, rather than an error about a missing crate somewhere in the macro expansion.
+ */
+fn missing_ux_backend(macro_name: &str) -> Option<proc_macro::TokenStream> {
+    if cfg!(any(feature = "ux", feature = "arbitrary-int")) {
+        return None;
+    }
+
+    let message = format!(
+        "The `ux` feature (or the `arbitrary-int` feature) of splitbits must be enabled to use {macro_name}!.");
+    Some(syn::Error::new(Span::call_site(), message).to_compile_error().into())
 }
 
-/// Same as [`replacebits!`], except the digits in the template are hexadecimal rather than binary.
-/// ```
-/// use splitbits::replacehex;
-///
-/// let w = 0xABC;
-/// let x = 0x01234;
-/// let y = 0x9876;
-/// let z: u8 = 5;
-///
-/// let input = 0x555_5555_5F55_5555;
-/// let output = replacehex!(input, "wwwx xxxx .z.. yyyy");
-/// assert_eq!(output,             0xABC0_1234_5555_9876);
-/// ```
-#[proc_macro]
-pub fn replacehex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    replacebits_base(&input, Base::Hexadecimal)
+// Traits that the compiler implements automatically, which can't be implemented by trait_impl.
+const AUTO_TRAITS: &[&str] = &["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+// The settings that are supported by the splitbits! macros that generate a struct, via to_struct().
+const STRUCT_SETTINGS: &[&str] = &["diff", "flags_enum", "rest", "layout", "keep_raw", "default", "trait_impl"];
+
+fn splitbits_base(
+    input: proc_macro::TokenStream,
+    base: Base,
+    precision: Precision,
+) -> proc_macro::TokenStream {
+    splitbits_base_with(input, base, precision, false)
+}
+
+/* Generate the splitbits! family expansion. If the expansion is fallible, it is a Result, with
+ * failed runtime checks returned as Errs rather than panics.
+ */
+fn splitbits_base_with(
+    input: proc_macro::TokenStream,
+    base: Base,
+    precision: Precision,
+    fallible: bool,
+) -> proc_macro::TokenStream {
+    let settings = [STRUCT_SETTINGS, &["output", "always_tuple"]].concat();
+    let (value, template, settings) =
+        match parse_splitbits_input("splitbits", &input.into(), base, precision, &settings) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    let (binding, value) = bind_value(value);
+    let check = literal_check(&template, &value, &settings, fallible);
+    let mut fields = extract_fields(&template, &value, &settings, precision);
+    if fallible {
+        fields = fields.into_iter().map(Field::fallible).collect();
+    }
+
+    let output = settings.output();
+    if output != Output::Fields {
+        let output = format!("{output:?}").to_lowercase();
+        for setting in STRUCT_SETTINGS {
+            assert!(!settings.contains(setting), "Setting '{setting}' must not be combined with 'output={output}'.");
+        }
+    }
+
+    if output != Output::Tuple {
+        assert!(!settings.contains("always_tuple"), "Setting 'always_tuple' requires 'output=tuple'.");
+    }
+
+    let result = match output {
+        Output::Fields => {
+            let (struct_definition, struct_value) = to_struct(&template, &value, &fields, &settings);
+            quote! {
+                #struct_definition
+
+                #struct_value
+            }
+        }
+        Output::Tuple => to_tuple(&fields, &settings, false),
+        Output::Array => to_array(&fields),
+    };
+    let result = quote! {
+        {
+            #binding
+            #check
+            #result
+        }
+    };
+
+    if fallible {
+        fallible_result(&result).into()
+    } else {
+        result.into()
+    }
+}
+
+// Generate the definition of the struct that holds the extracted fields, and an instance of it.
+fn to_struct(
+    template: &Template,
+    value: &Expr,
+    fields: &[Field],
+    settings: &Settings,
+) -> (TokenStream, TokenStream) {
+    let struct_name = template.to_struct_name();
+    let mut names: Vec<_> = fields.iter().map(|field| field.name().to_ident()).collect();
+    let mut types: Vec<_> = fields.iter().map(Field::output_type).collect();
+    let values = struct_values(template, value, fields, settings);
+
+    let mut methods = Vec::new();
+    if settings.flag("rest") {
+        names.push(format_ident!("rest"));
+        types.push(template.width().to_token_stream());
+        methods.push(recombine_method(template, fields, settings));
+    }
+
+    let mut raw_fmt_impls = quote! {};
+    if settings.flag("keep_raw") {
+        let t = template.width().to_token_stream();
+        names.push(format_ident!("raw"));
+        types.push(t.clone());
+        methods.push(quote! {
+            // The bits of the input that the fields were extracted from.
+            fn raw(&self) -> #t {
+                self.raw
+            }
+        });
+        // Formatting as binary or hex prints the raw value, matching how registers are documented.
+        raw_fmt_impls = quote! {
+            impl ::core::fmt::Binary for #struct_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Binary::fmt(&self.raw, f)
+                }
+            }
+
+            impl ::core::fmt::LowerHex for #struct_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::LowerHex::fmt(&self.raw, f)
+                }
+            }
+        };
+    }
+
+    if settings.flag("diff") {
+        let name_strings = fields.iter().map(|field| field.name().to_string());
+        let names = fields.iter().map(|field| field.name().to_ident());
+        methods.push(quote! {
+            // The names of the fields that have different values in the other instance.
+            fn diff(&self, other: &Self) -> impl Iterator<Item = &'static str> {
+                [#((#name_strings, self.#names != other.#names),)*]
+                    .into_iter()
+                    .filter_map(|(name, changed)| changed.then_some(name))
+            }
+        });
+    }
+
+    if settings.flag("layout") {
+        methods.push(layout_method(fields, settings.bit_numbering(), template.width().bit_count()));
+        methods.push(size_method(&types));
+    }
+
+    for (name, flags) in settings.field_values("flags_enum") {
+        let field = fields.iter()
+            .find(|field| field.name() == name)
+            .unwrap_or_else(|| panic!(
+                "Field '{}' was passed to setting 'flags_enum', but isn't in the template.", name));
+        let flags = flags.unwrap_or_else(|| panic!(
+            "Setting 'flags_enum' must list the flags of field '{}', e.g. 'flags_enum({} = [Flag::A, Flag::B])'.",
+            name, name));
+        methods.push(iter_set_method(field, &flags));
+    }
+
+    // The default is extracted from a reset value, the same way as the fields are from the input.
+    let default_impl = settings.expr("default").map(|reset| {
+        let aligned_reset = aligned_value(template, reset, settings);
+        let default_fields: Vec<Field> = fields.iter()
+            .map(|field| field.with_input(&aligned_reset))
+            .collect();
+        let defaults = struct_values(template, reset, &default_fields, settings);
+        quote! {
+            impl Default for #struct_name {
+                fn default() -> Self {
+                    #struct_name {
+                        #(#names: #defaults,)*
+                    }
+                }
+            }
+        }
+    });
+
+    // The traits must not have any methods without default implementations, such as marker traits.
+    let traits = settings.paths("trait_impl");
+    for path in &traits {
+        let name = path.segments.last().expect("A trait path should have a name").ident.to_string();
+        assert!(!AUTO_TRAITS.contains(&name.as_str()),
+            "Setting 'trait_impl' must not list auto trait '{name}', since it is implemented automatically \
+            (and manual implementations require unsafe code).");
+    }
+
+    let definition = quote! {
+        struct #struct_name {
+            #(#names: #types,)*
+        }
+
+        impl #struct_name {
+            #(#methods)*
+        }
+
+        #default_impl
+        #raw_fmt_impls
+        #(impl #traits for #struct_name {})*
+    };
+    let value = quote! {
+        #struct_name {
+            #(#names: #values,)*
+        }
+    };
+
+    (definition, value)
+}
+
+/* Generate width and offset consts for each field (e.g. A_WIDTH and A_OFFSET), and a layout()
+ * method that lists them, since the generated struct can't be named outside of its macro call.
+ * Offsets follow the bit numbering, so they match the bit ranges printed by splitbits_debug!.
+ */
+fn layout_method(fields: &[Field], numbering: BitNumbering, template_width: u8) -> TokenStream {
+    let name_strings: Vec<_> = fields.iter().map(|field| field.name().to_string()).collect();
+    let width_names: Vec<_> = name_strings.iter()
+        .map(|name| format_ident!("{}_WIDTH", name.to_uppercase()))
+        .collect();
+    let offset_names: Vec<_> = name_strings.iter()
+        .map(|name| format_ident!("{}_OFFSET", name.to_uppercase()))
+        .collect();
+    let widths = fields.iter().map(|field| u32::from(field.width()));
+    let offsets = fields.iter().map(|field| u32::from(field.numbered_offset(numbering, template_width)));
+    let count = fields.len();
+    quote! {
+        #(const #width_names: u32 = #widths;)*
+        #(const #offset_names: u32 = #offsets;)*
+
+        // The name, bit width, and bit offset of each field, in template order.
+        fn layout(&self) -> [(&'static str, u32, u32); #count] {
+            [#((#name_strings, Self::#width_names, Self::#offset_names),)*]
+        }
+    }
+}
+
+/* Generate STRUCT_SIZE and STRUCT_PADDING consts for the generated struct, and methods that return
+ * them. The padding is the number of bytes that aren't taken up by any field. The struct isn't
+ * repr(C), so the compiler chooses its field order, regardless of the order setting.
+ */
+fn size_method(types: &[TokenStream]) -> TokenStream {
+    quote! {
+        const STRUCT_SIZE: usize = ::core::mem::size_of::<Self>();
+        const STRUCT_PADDING: usize = Self::STRUCT_SIZE - (0 #(+ ::core::mem::size_of::<#types>())*);
+
+        // The total size of the struct in bytes, including padding.
+        fn struct_size(&self) -> usize {
+            Self::STRUCT_SIZE
+        }
+
+        // The number of bytes of the struct that are padding rather than fields.
+        fn struct_padding(&self) -> usize {
+            Self::STRUCT_PADDING
+        }
+    }
 }
 
-fn splitbits_base(
-    input: proc_macro::TokenStream,
-    base: Base,
-    precision: Precision,
-) -> proc_macro::TokenStream {
-    let (value, template, min_size) =
-        parse_splitbits_input(&input.into(), base, precision);
-    let fields = template.extract_fields(&value, min_size);
+// Generate a method that recombines all the fields (including 'rest') into the original value.
+fn recombine_method(template: &Template, fields: &[Field], settings: &Settings) -> TokenStream {
+    assert!(settings.names("invert").is_empty() && settings.field_values("ratio").is_empty()
+        && settings.field_values("lookup").is_empty() && settings.field_values("sign_magnitude").is_empty()
+        && settings.field_values("field_order").is_empty() && settings.field_values("char").is_empty()
+        && settings.field_values("map_into").is_empty() && settings.field_values("bytes").is_empty(),
+        "Setting 'rest' must not be combined with 'invert', 'ratio', 'lookup', 'sign_magnitude', \
+        'field_order', 'char', 'map_into', or 'bytes', since they change field values.");
+    let t = template.width().to_token_stream();
 
-    let struct_name = template.to_struct_name();
-    let names: Vec<_> = fields.iter().map(|field| field.name().to_ident()).collect();
-    let types: Vec<_> = fields.iter().map(|field| field.bit_width().to_token_stream()).collect();
-    let values: Vec<TokenStream> = fields.iter().map(Field::to_token_stream).collect();
-    let result = quote! {
-        {
-            struct #struct_name {
-                #(#names: #types,)*
-            }
+    // Field values always fit within their slots, so casting them (even from bool) can't lose bits.
+    let inputs: Vec<(Name, TokenStream)> = fields.iter()
+        .map(|field| {
+            let name = field.name().to_ident();
+            (field.name(), quote! { (self.#name as #t) })
+        })
+        .collect();
+    let mut combined = template.combine(OnOverflow::Truncate, Operator::Or, Some(&parse_quote! { self.rest }), &inputs);
+    if let Some(rotation) = settings.integer("rotate_input") {
+        // Undo the rotation first, since it was the last step applied to the input.
+        let rotation = u32::from(rotation);
+        combined = quote! { (#combined).rotate_left(#rotation) };
+    }
 
-            #struct_name {
-                #(#names: #values,)*
-            }
+    if settings.bit_order() == BitOrder::LsbInByte {
+        // Undo the reversal of the bits within each byte, since reversing twice is a no-op.
+        combined = quote! { (#combined).reverse_bits().swap_bytes() };
+    }
+
+    quote! {
+        // Combine the fields and the rest of the bits back into the (aligned) original value.
+        fn recombine(&self) -> #t {
+            #combined
         }
+    }
+}
+
+/* Generate the value of each field of a struct, including the 'rest' field (the bits at the
+ * placeholder positions of the template, left in place) and the 'raw' field, if requested.
+ */
+fn struct_values(template: &Template, value: &Expr, fields: &[Field], settings: &Settings) -> Vec<TokenStream> {
+    let mut values: Vec<TokenStream> = fields.iter().map(Field::to_token_stream).collect();
+    let t = template.width().to_token_stream();
+    let value = aligned_value(template, value, settings);
+    if settings.flag("rest") {
+        let placeholder_mask = template.placeholder_mask();
+        values.push(quote! { (#value as #t) & (#placeholder_mask as #t) });
+    }
+
+    if settings.flag("keep_raw") {
+        values.push(quote! { (#value) as #t });
+    }
+
+    values
+}
+
+/* Generate a method that iterates over the flags of a field whose bits are set. The flags are listed
+ * starting from the field's least significant bit, with '_' for bits that don't have a flag.
+ */
+fn iter_set_method(field: &Field, flags: &Expr) -> TokenStream {
+    let name = field.name();
+    let Expr::Array(ExprArray { elems, .. }) = flags else {
+        panic!("Setting 'flags_enum' must list the flags of field '{name}' in square brackets.");
     };
+    assert_eq!(elems.len(), usize::from(field.width()),
+        "Setting 'flags_enum' must list exactly one flag (or '_') per bit of field '{name}'.");
 
-    result.into()
+    let (indexes, flags): (Vec<_>, Vec<_>) = elems.iter()
+        .enumerate()
+        .filter(|(_, flag)| !matches!(flag, Expr::Infer(_)))
+        .unzip();
+    // The flag type is the enum that the flags are variants of, e.g. 'Flag' for 'Flag::A'.
+    let flag_type = flags.iter()
+        .map(|flag| match flag {
+            Expr::Path(ExprPath { path, .. }) if path.segments.len() > 1 => {
+                let mut flag_type = path.clone();
+                flag_type.segments.pop();
+                flag_type.segments.pop_punct();
+                flag_type
+            }
+            _ => panic!("Setting 'flags_enum' must only list enum variants (e.g. 'Flag::A') or '_'."),
+        })
+        .reduce(|flag_type, other| {
+            assert!(flag_type == other,
+                "Setting 'flags_enum' must only list variants of a single enum for field '{name}'.");
+            flag_type
+        })
+        .unwrap_or_else(|| panic!("Setting 'flags_enum' must list at least one flag for field '{name}'."));
+
+    let field_ident = field.name().to_ident();
+    let method_name = format_ident!("iter_set_{}", name.as_str());
+    quote! {
+        // The flags whose bits are set in this field, from the least significant bit up.
+        fn #method_name(&self) -> impl Iterator<Item = #flag_type> {
+            let value = u128::from(self.#field_ident);
+            [#((#indexes, #flags),)*]
+                .into_iter()
+                .filter_map(move |(index, flag)| ((value >> index) & 1 == 1).then_some(flag))
+        }
+    }
 }
 
 fn splitbits_named_base(
@@ -1067,18 +3494,16 @@ fn splitbits_named_base(
     base: Base,
     precision: Precision,
 ) -> proc_macro::TokenStream {
-    let (value, template, min_size) =
-        parse_splitbits_input(&input.into(), base, precision);
-    let fields = template.extract_fields(&value, min_size);
-    let values: Vec<TokenStream> = fields.iter().map(Field::to_token_stream).collect();
-
-    if let [value] = &values[..] {
-        // Single value
-        quote! { #value }
-    } else {
-        // Tuple
-        quote! { (#(#values,)*) }
-    }.into()
+    let (value, template, settings) =
+        match parse_splitbits_input("splitbits_named", &input.into(), base, precision, &["always_tuple"]) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    let (binding, value) = bind_value(value);
+    let check = literal_check(&template, &value, &settings, false);
+    let fields = extract_fields(&template, &value, &settings, precision);
+    let values = to_tuple(&fields, &settings, false);
+    quote! { { #binding #check #values } }.into()
 }
 
 fn splitbits_named_into_base(
@@ -1086,55 +3511,567 @@ fn splitbits_named_into_base(
     base: Base,
     precision: Precision,
 ) -> proc_macro::TokenStream {
-    let (value, template, min_size) =
-        parse_splitbits_input(&input.into(), base, precision);
-    let fields = template.extract_fields(&value, min_size);
-    let values: Vec<TokenStream> = fields.iter().map(Field::to_token_stream).collect();
+    let (value, template, settings) =
+        match parse_splitbits_input("splitbits_named_into", &input.into(), base, precision, &["always_tuple", "types"]) {
+            Ok(parsed) => parsed,
+            Err(err) => return err.to_compile_error().into(),
+        };
+    let (binding, value) = bind_value(value);
+    let check = literal_check(&template, &value, &settings, false);
+    let fields = extract_fields(&template, &value, &settings, precision);
+    for (name, target) in settings.field_values("types") {
+        let field = fields.iter()
+            .find(|field| field.name() == name)
+            .unwrap_or_else(|| panic!("Field '{}' was passed to setting 'types', but isn't in the template.", name));
+        let target = target.unwrap_or_else(|| panic!(
+            "Setting 'types' must specify a type for field '{}', e.g. 'types({} = u16)'.", name, name));
+        reject_unfit_target(field, &target);
+    }
+
+    let values = to_tuple(&fields, &settings, true);
+    quote! { { #binding #check #values } }.into()
+}
+
+/* Evaluate an input value only once, even though every field is extracted from it, since it may
+ * have side effects (such as a register read that clears flags). Literals and plain variables are
+ * left in place, so that literal inputs can still be folded at compile time. A closure is called.
+ */
+fn bind_value(value: Expr) -> (TokenStream, Expr) {
+    let value = match value {
+        Expr::Lit(_) | Expr::Path(_) => return (quote! {}, value),
+        Expr::Closure(closure) => quote! { (#closure)() },
+        value => quote! { #value },
+    };
+    (quote! { let _input = #value; }, parse_quote! { _input })
+}
+
+/* Check that the literal bits of the input match the literals of the template. Literals aren't
+ * extracted, so this compares against the same aligned value that the fields are extracted from.
+ */
+fn literal_check(template: &Template, value: &Expr, settings: &Settings, fallible: bool) -> TokenStream {
+    template.check_literals(&aligned_value(template, value, settings), fallible)
+}
+
+/* Fail if a field's integer type can't be converted into the primitive type that was specified for
+ * it, which would otherwise be reported as a missing From impl rather than as a width problem.
+ * Non-primitive target types can't be checked, since their From impls aren't visible to macros.
+ */
+fn reject_unfit_target(field: &Field, target: &Expr) {
+    if field.is_ratio() || field.is_lookup() || field.is_signed() || field.is_bytes() || field.is_char()
+            || field.is_mapped() || field.is_twos_complement() {
+        return;
+    }
+
+    let Expr::Path(path) = target else {
+        return;
+    };
+    let segments: Vec<String> = path.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+    let target = match &segments[..] {
+        [t] => t,
+        [krate, primitive, t] if (krate == "core" || krate == "std") && primitive == "primitive" => t,
+        _ => return,
+    };
+
+    let source = field.bit_width();
+    let fits = match target.as_str() {
+        "bool" => source == Type::Bool,
+        "u8" | "u16" | "u32" | "u64" | "u128" => source.bit_count() <= target[1..].parse().unwrap(),
+        // A signed type needs an extra bit for the sign, except for bools.
+        "i8" | "i16" | "i32" | "i64" | "i128" =>
+            source == Type::Bool || source.bit_count() < target[1..].parse().unwrap(),
+        _ => return,
+    };
+    assert!(fits, "Field '{}' is {} bits (extracted as {source}), so it can't fit into {target}.",
+        field.name(), field.width());
+}
+
+/* Generate a tuple of the extracted field values, in template order, converting each with into()
+ * if specified (or with from(), for fields with types specified by the 'types' setting). A single
+ * field is returned as a bare value unless always_tuple is set.
+ */
+fn to_tuple(fields: &[Field], settings: &Settings, into: bool) -> TokenStream {
+    let types = settings.field_values("types");
+    let values: Vec<TokenStream> = fields.iter()
+        .map(|field| {
+            let value = field.to_token_stream();
+            if !into {
+                return value;
+            }
+
+            match types.iter().find(|(name, _)| *name == field.name()) {
+                Some((_, Some(target))) => quote! { <#target>::from(#value) },
+                _ => quote! { (#value).into() },
+            }
+        })
+        .collect();
 
-    if let [value] = &values[..] {
+    match &values[..] {
         // Single value
-        quote! { #value.into() }
-    } else {
+        [value] if !settings.flag("always_tuple") => quote! { #value },
         // Tuple
-        quote! { (#((#values).into(),)*) }
-    }.into()
+        _ => quote! { (#(#values,)*) },
+    }
+}
+
+// Convert fields into an array of their values, widened to u128 (so bools become 0 or 1).
+fn to_array(fields: &[Field]) -> TokenStream {
+    let values: Vec<TokenStream> = fields.iter()
+        .map(|field| {
+            assert!(!field.is_ratio(), "Setting 'ratio' must not be combined with 'output=array'.");
+            assert!(!field.is_lookup(), "Setting 'lookup' must not be combined with 'output=array'.");
+            assert!(!field.is_signed(), "Setting 'sign_magnitude' must not be combined with 'output=array'.");
+            assert!(!field.is_mapped(), "Setting 'map_into' must not be combined with 'output=array'.");
+            assert!(!field.is_twos_complement(), "Setting 'signed' must not be combined with 'output=array'.");
+            let value = field.to_token_stream();
+            quote! { u128::from(#value) }
+        })
+        .collect();
+    quote! { [#(#values,)*] }
 }
 
 fn combinebits_base(
     input: proc_macro::TokenStream,
     base: Base,
+) -> proc_macro::TokenStream {
+    combinebits_base_with(input, base, false)
+}
+
+/* Generate the combinebits! family expansion. If the expansion is fallible, it is a Result, with
+ * overflow=panic and the other runtime checks returning an Err rather than panicking.
+ */
+fn combinebits_base_with(
+    input: proc_macro::TokenStream,
+    base: Base,
+    fallible: bool,
 ) -> proc_macro::TokenStream {
     let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
         .expect("combinebits! argument list should be formatted sanely");
     let mut parts: Vec<_> = parts.into_iter().collect();
-    assert!(!parts.is_empty(), "combinebits! must take at least one argument (the template).");
-
-    let mut on_overflow = OnOverflow::Truncate;
-    // If we've got more than one argument, the first one might be an overflow setting.
-    if let [assignment, _, ..] = &parts[..] {
-        if let Some((setting, value)) = parse_assignment(assignment) {
-            assert_eq!(setting, "overflow",
-                "Only the 'overflow' setting is supported, but found '{setting}'.");
-            parts.remove(0);
-            on_overflow = OnOverflow::parse(&value)
-                .expect("Valid overflow setting value must be passed");
-        }
+    if parts.is_empty() {
+        return syn::Error::new(Span::call_site(), "combinebits! must take at least one argument (the template).")
+            .to_compile_error()
+            .into();
+    }
+
+    // If we've got more than one argument, the first ones might be settings.
+    let settings = Settings::take("combinebits", &mut parts, &["overflow", "combine", "base", "optional", "constant", "const_check", "lookup", "result",
+        "placeholder", "names", "map", "from", "warn_noncontiguous", "noncontiguous", "group"]);
+    let mut on_overflow = settings.overflow();
+    if fallible && matches!(on_overflow, OnOverflow::Panic) {
+        on_overflow = OnOverflow::Error;
     }
 
-    let expr = parts.pop().unwrap();
+    let expr = Template::with_placeholder(&parts.pop().unwrap(), settings.placeholder(base));
+    reject_misaligned_groups(&expr, base, &settings);
     let template = Template::from_expr(&expr, base, Precision::Ux);
-    if template.has_placeholders() {
+    reject_noncontiguous(&template, &settings);
+    // Placeholders are only meaningful if there is a base value for them to take their bits from.
+    if template.has_placeholders() && !settings.contains("base") {
         let bad_template = Template::template_string(&expr);
         panic!(
             "Template ({bad_template}) must not have placeholders (periods) in it. \
             Use literals instead as appropriate.");
     }
 
-    if parts.is_empty() {
+    // Fields with constant values don't take an input from an argument or a variable.
+    let constants: Vec<(Name, TokenStream)> = settings.field_values("constant").into_iter()
+        .map(|(name, value)| {
+            assert!(template.has_name(name),
+                "Field '{}' was passed to setting 'constant', but isn't in the template.", name);
+            let value = value.unwrap_or_else(|| panic!(
+                "Setting 'constant' must specify a value for field '{}', e.g. 'constant({} = 5)'.",
+                name, name));
+            template.reject_oversized_literal(name, &value, on_overflow);
+            (name, quote! { #value })
+        })
+        .collect();
+
+    let mut inputs = if parts.is_empty() {
         // No arguments passed, so take them from the variables preceeding the macro instead.
-        template.combine_with_context(on_overflow).into()
+        template.context_inputs(&constants)
+    } else {
+        match template.arg_inputs(&parts[..], &constants, on_overflow) {
+            Ok(inputs) => inputs,
+            Err(err) => return err.to_compile_error().into(),
+        }
+    };
+
+    // Inputs can come from a map of field values instead, which is checked for missing or extra keys.
+    let map_check = settings.expr("map").map(|map| {
+        assert!(parts.is_empty(), "Setting 'map' must not be combined with input arguments.");
+        assert!(!settings.contains("names"), "Setting 'map' must not be combined with 'names'.");
+        map_inputs(&template, map, &mut inputs, &constants, on_overflow, fallible)
+    });
+
+    // Inputs can be taken from the fields of structs instead, with each field listed under its struct.
+    let sources = settings.struct_fields("from");
+    if !sources.is_empty() {
+        assert!(parts.is_empty(), "Setting 'from' must not be combined with input arguments.");
+        assert!(!settings.contains("map") && !settings.contains("names"),
+            "Setting 'from' must not be combined with 'map' or 'names'.");
+        struct_inputs(&sources, &mut inputs, &constants);
+    }
+
+    // Captured variables can have descriptive names that differ from their template names.
+    for (name, variable) in settings.field_values("names") {
+        assert!(parts.is_empty(),
+            "Setting 'names' must only be used when inputs are captured from variables, not passed as arguments.");
+        assert!(!constants.iter().any(|(constant_name, _)| *constant_name == name),
+            "Field '{}' must not be passed to both 'names' and 'constant'.", name);
+        let variable = match variable {
+            Some(Expr::Path(path)) if path.path.get_ident().is_some() => path,
+            _ => panic!("Setting 'names' must specify a variable name for field '{}', e.g. 'names({} = length)'.",
+                name, name),
+        };
+        *field_input(&mut inputs, name, "names") = quote! { #variable };
+    }
+
+    // Optional inputs fall back to their default value (zero if unspecified) when they are None.
+    for (name, default) in settings.field_values("optional") {
+        let input = field_input(&mut inputs, name, "optional");
+        let default = default.map_or_else(|| quote! { 0 }, |default| quote! { #default });
+        *input = quote! { (#input).unwrap_or(#default) };
+    }
+
+    // The remaining transforms work on owned values, so each input is bound (and dereferenced) first.
+    let bindings = bind_inputs(&mut inputs, &constants);
+
+    // Checked inputs must equal their expected values. Literal inputs are checked at compile time.
+    for (name, expected) in settings.field_values("const_check") {
+        assert!(!constants.iter().any(|(constant_name, _)| *constant_name == name),
+            "Field '{}' must not be passed to both 'const_check' and 'constant'.", name);
+        let expected = expected.unwrap_or_else(|| panic!(
+            "Setting 'const_check' must specify a value for field '{}', e.g. 'const_check({} = 3)'.",
+            name, name));
+        let input = field_input(&mut inputs, name, "const_check");
+        if let (Ok(actual), Expr::Lit(ExprLit { lit: Lit::Int(expected), .. })) =
+                (syn::parse2::<LitInt>(input.clone()), &expected) {
+            let parse = |int: &LitInt| int.base10_parse::<u128>().unwrap_or_else(|_| panic!(
+                "Literal value for field '{}' must be an unsigned integer.", name));
+            assert!(parse(&actual) == parse(expected),
+                "Field '{}' must equal {expected}, but was {actual}.", name);
+        }
+
+        let expected_text = expected.to_token_stream().to_string().replace('{', "{{").replace('}', "}}");
+        let message = format!("Field '{name}' must equal {expected_text}, but was {{:?}}.");
+        let failure = runtime_failure(fallible, &quote! { #message, value });
+        *input = quote! {
+            {
+                let value = #input;
+                if value != #expected {
+                    #failure;
+                }
+
+                value
+            }
+        };
+    }
+
+    // Lookup inputs are real values, which are replaced by their indexes in their lookup tables.
+    for (name, table) in settings.field_values("lookup") {
+        let width = template.field_width(name).unwrap_or_else(|| panic!(
+            "Field '{}' was passed to setting 'lookup', but isn't in the template.", name));
+        let Some(Expr::Array(table)) = table else {
+            panic!("Setting 'lookup' must specify an array for field '{}', e.g. 'lookup({} = [1u32, 2, 4, 8])'.",
+                name, name);
+        };
+        let expected = 1u128 << width;
+        assert!(table.elems.len() as u128 == expected,
+            "The lookup table for field '{}' must have exactly {expected} entries (one for each value of its \
+            {width} bits), but had {}.", name, table.elems.len());
+
+        let element_type = lookup_element_type(name, &table);
+        let len = table.elems.len();
+        let message = format!("The value of field '{name}' isn't in its lookup table: {{:?}}");
+        let failure = runtime_failure(fallible, &quote! { #message, value });
+        let index_type = Type::for_field(width, Precision::Standard)
+            .expect("Field should be shorter than 256 characters");
+        let index = if index_type == Type::Bool {
+            quote! { index != 0 }
+        } else {
+            let index_type = index_type.to_token_stream();
+            quote! { index as #index_type }
+        };
+        let input = field_input(&mut inputs, name, "lookup");
+        *input = quote! {
+            {
+                const TABLE: [#element_type; #len] = #table;
+                let value = #input;
+                let index = match TABLE.iter().position(|entry| *entry == value) {
+                    ::core::option::Option::Some(index) => index,
+                    ::core::option::Option::None => #failure,
+                };
+                #index
+            }
+        };
+    }
+
+    // Clamped inputs are brought into the range of their slots before they are placed.
+    let clamp_trait = matches!(on_overflow, OnOverflow::Clamp).then(|| {
+        let t = template.width().to_token_stream();
+        for (name, input) in &mut inputs {
+            let width = u32::from(template.field_width(*name).expect("Every input should have a field"));
+            *input = quote! { ((#input).clamp_to_slot(#width) as #t) };
+        }
+
+        clamp_to_slot_trait()
+    });
+
+    let combined = template.combine(on_overflow, settings.operator(), settings.expr("base"), &inputs);
+    let combined = quote! { ({ #clamp_trait #map_check #bindings (#combined) }) };
+    let result = match settings.result(template.width()) {
+        ResultType::Unsigned => combined,
+        ResultType::Signed => {
+            // A bit reinterpretation, so the sign bit comes from the top bit of the template.
+            let signed = format_ident!("i{}", template.width().bit_count());
+            quote! { ((#combined) as #signed) }
+        }
+        ResultType::Fit => {
+            // Only the placeholder bits can be set by the base value, so it's covered by their mask.
+            let mut used_mask = template.value_mask();
+            if settings.contains("base") {
+                used_mask |= template.placeholder_mask();
+            }
+
+            let bit_count = (128 - used_mask.leading_zeros()).max(8).next_power_of_two();
+            let t = format_ident!("u{bit_count}");
+            quote! { ((#combined) as #t) }
+        }
+    };
+
+    if fallible {
+        fallible_result(&result).into()
+    } else {
+        result.into()
+    }
+}
+
+/* Make an expansion include each template file that was read while generating it, so that Cargo
+ * rebuilds the invoking crate when a template file changes, the same as for include_str!.
+ */
+fn with_template_files(expansion: impl Into<TokenStream>) -> proc_macro::TokenStream {
+    let expansion = expansion.into();
+    let files = Template::take_files();
+    if files.is_empty() {
+        return expansion.into();
+    }
+
+    let includes = quote! { #(const _: &[u8] = ::core::include_bytes!(#files);)* };
+    // Expressions need a block to hold the includes, while items and statements can sit next to them.
+    if syn::parse2::<Expr>(expansion.clone()).is_ok() {
+        quote! { { #includes #expansion } }.into()
+    } else {
+        quote! { #includes #expansion }.into()
+    }
+}
+
+/* Wrap an expansion in a labeled block so that the Errs that it breaks out with end up in its
+ * Result. Unlike a closure, the block doesn't change what '?' and 'return' mean in the inputs.
+ */
+fn fallible_result(result: &TokenStream) -> TokenStream {
+    let label = fallible_label();
+    quote! {
+        #label: {
+            ::core::result::Result::<_, ::std::string::String>::Ok(#result)
+        }
+    }
+}
+
+// The label of a fallible expansion's block. Hygienic, so nested try_ macros can't collide.
+fn fallible_label() -> syn::Lifetime {
+    syn::Lifetime::new("'splitbits_try", Span::mixed_site())
+}
+
+/* Fail a runtime check with a message and its format arguments. A fallible expansion breaks out of
+ * its block with the message as an Err, while any other expansion panics with it.
+ */
+fn runtime_failure(fallible: bool, message: &TokenStream) -> TokenStream {
+    if fallible {
+        let label = fallible_label();
+        quote! { break #label ::core::result::Result::Err(::std::format!(#message)) }
     } else {
-        template.combine_with_args(on_overflow, &parts[..]).into()
+        quote! { panic!(#message) }
+    }
+}
+
+/* Take the input of each (non-constant) field from a map keyed by the field's name as a char, such
+ * as a BTreeMap<char, u128>. Each value is converted to the template's type, following the overflow
+ * setting if it doesn't fit, since map values are usually wider than the template. Returns the
+ * binding of the map, followed by a check that the map doesn't have any keys that aren't fields.
+ */
+fn map_inputs(
+    template: &Template,
+    map: &Expr,
+    inputs: &mut [(Name, TokenStream)],
+    constants: &[(Name, TokenStream)],
+    on_overflow: OnOverflow,
+    fallible: bool,
+) -> TokenStream {
+    let t = template.width().to_token_stream();
+    let mut keys = Vec::new();
+    for (name, input) in inputs.iter_mut() {
+        // Integer literals are already values, and stay literals so that they're checked at compile time.
+        if constants.iter().any(|(constant_name, _)| constant_name == name)
+                || syn::parse2::<LitInt>(input.clone()).is_ok() {
+            continue;
+        }
+
+        assert!(name.is_single_char(),
+            "Setting 'map' requires single-letter field names, since map keys are chars, but found '{}'.", name);
+        let key = name.as_str().chars().next().unwrap();
+        let missing = format!("Field '{key}' is missing from the map.");
+        let missing = runtime_failure(fallible, &quote! { #missing });
+        let too_big = format!("Field '{key}' is too big for the template: {{}}");
+        let convert = match on_overflow {
+            OnOverflow::Truncate | OnOverflow::Corrupt => quote! { value as #t },
+            // Left unconverted, since it will be clamped as a signed value later.
+            OnOverflow::Clamp => quote! { value },
+            OnOverflow::Saturate => quote! { #t::try_from(value).unwrap_or(#t::MAX) },
+            OnOverflow::Panic | OnOverflow::Error => {
+                let failure = runtime_failure(fallible, &quote! { #too_big, value });
+                quote! {
+                    match #t::try_from(value) {
+                        ::core::result::Result::Ok(value) => value,
+                        ::core::result::Result::Err(_) => #failure,
+                    }
+                }
+            }
+            OnOverflow::DebugChecked => quote! {
+                {
+                    debug_assert!(#t::try_from(value).is_ok(), #too_big, value);
+                    value as #t
+                }
+            },
+        };
+        *input = quote! {
+            {
+                let value = match _map.get(&#key) {
+                    ::core::option::Option::Some(value) => *value,
+                    ::core::option::Option::None => #missing,
+                };
+                #convert
+            }
+        };
+        keys.push(key);
+    }
+
+    let extra = runtime_failure(fallible, &quote! { "Key '{}' of the map isn't a field of the template.", key });
+    quote! {
+        let _map = &(#map);
+        for key in _map.keys() {
+            if ![#(#keys),*].contains(key) {
+                #extra;
+            }
+        }
+    }
+}
+
+/* Take each (non-constant) input from the struct that it is listed under. Every field must be listed
+ * under exactly one struct, since which struct has a field can't be determined during expansion.
+ */
+fn struct_inputs(
+    sources: &[(Expr, Vec<Name>)],
+    inputs: &mut [(Name, TokenStream)],
+    constants: &[(Name, TokenStream)],
+) {
+    let mut source_by_field: BTreeMap<Name, &Expr> = BTreeMap::new();
+    for (source, fields) in sources {
+        for &field in fields {
+            if let Some(other) = source_by_field.insert(field, source) {
+                panic!("Field '{field}' must only be taken from one struct, but was listed for both '{}' and '{}'.",
+                    other.to_token_stream(), source.to_token_stream());
+            }
+        }
+    }
+
+    for (name, input) in inputs.iter_mut() {
+        // Integer literals are already values, and stay literals so that they're checked at compile time.
+        if constants.iter().any(|(constant_name, _)| constant_name == name)
+                || syn::parse2::<LitInt>(input.clone()).is_ok() {
+            continue;
+        }
+
+        let source = source_by_field.remove(name).unwrap_or_else(|| panic!(
+            "Field '{name}' isn't listed for any of the structs in setting 'from'."));
+        let field = name.to_ident();
+        *input = quote! { (#source).#field };
+    }
+
+    if let Some((field, source)) = source_by_field.into_iter().next() {
+        panic!("Field '{field}' was listed for struct '{}' in setting 'from', but isn't an input of the template.",
+            source.to_token_stream());
+    }
+}
+
+/* A trait for clamping an input to the range of a slot with the specified bit width, since the
+ * input types aren't known during expansion. Signed inputs are clamped to the signed range of the
+ * slot (to be stored in two's complement), unsigned inputs to its unsigned range, and bools are
+ * left as they are.
+ */
+fn clamp_to_slot_trait() -> TokenStream {
+    let signed = ["i8", "i16", "i32", "i64", "i128", "isize"].map(|t| format_ident!("{t}"));
+    let unsigned = ["u8", "u16", "u32", "u64", "u128", "usize"].map(|t| format_ident!("{t}"));
+    quote! {
+        trait ClampToSlot {
+            fn clamp_to_slot(self, width: u32) -> i128;
+        }
+
+        #(impl ClampToSlot for #signed {
+            fn clamp_to_slot(self, width: u32) -> i128 {
+                let max = i128::MAX >> (128 - width);
+                (self as i128).clamp(-max - 1, max)
+            }
+        })*
+
+        #(impl ClampToSlot for #unsigned {
+            fn clamp_to_slot(self, width: u32) -> i128 {
+                // Cast back to i128 without changing the bits, since a u128 slot can exceed i128::MAX.
+                (self as u128).min(u128::MAX >> (128 - width)) as i128
+            }
+        })*
+
+        impl ClampToSlot for bool {
+            fn clamp_to_slot(self, _width: u32) -> i128 {
+                i128::from(self)
+            }
+        }
+    }
+}
+
+// Fail if overflow=clamp is passed to a macro that doesn't support signed inputs.
+fn reject_clamp(on_overflow: OnOverflow, macro_name: &str) {
+    assert!(!matches!(on_overflow, OnOverflow::Clamp),
+        "Setting 'overflow=clamp' is only supported by combinebits!, not by {macro_name}!.");
+}
+
+/* Evaluate each (non-constant, non-literal) input once, binding it to a local named after its
+ * field. Calling clone() auto-dereferences inputs that are references (such as &u8 from a borrowed
+ * struct), and is a plain copy otherwise. All inputs are bound in a single tuple so that an input
+ * can refer to a variable with the same name as another field.
+ */
+fn bind_inputs(inputs: &mut [(Name, TokenStream)], constants: &[(Name, TokenStream)]) -> TokenStream {
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    for (name, input) in inputs.iter_mut() {
+        // Integer literals are already values, and stay literals so that they're checked at compile time.
+        if constants.iter().any(|(constant_name, _)| constant_name == name)
+                || syn::parse2::<LitInt>(input.clone()).is_ok() {
+            continue;
+        }
+
+        let ident = name.to_ident();
+        values.push(quote! { (#input).clone() });
+        names.push(ident.clone());
+        *input = quote! { #ident };
+    }
+
+    if names.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        #[allow(clippy::clone_on_copy)]
+        let (#(#names,)*) = (#(#values,)*);
     }
 }
 
@@ -1142,7 +4079,9 @@ fn split_then_combine_base(input: proc_macro::TokenStream, base: Base) -> proc_m
     const PRECISION: Precision = Precision::Standard;
     let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.into())
         .expect("splitbits_then_combine! argument list should be formatted sanely");
-    let parts: Vec<Expr> = parts.into_iter().collect();
+    let mut parts: Vec<Expr> = parts.into_iter().collect();
+    let settings = Settings::take("splitbits_then_combine", &mut parts, &["output_base"]);
+    Settings::reject_misplaced(&parts);
     assert!(parts.len() >= 3);
     assert!(parts.len() % 2 == 1);
 
@@ -1154,7 +4093,7 @@ fn split_then_combine_base(input: proc_macro::TokenStream, base: Base) -> proc_m
     }
 
     let expr = &parts[parts.len() - 1];
-    let target = Template::from_expr(expr, base, PRECISION);
+    let target = Template::from_expr(expr, settings.output_base(base), PRECISION);
     if target.has_placeholders() {
         let bad_template = Template::template_string(expr);
         panic!(
@@ -1169,101 +4108,414 @@ fn split_then_combine_base(input: proc_macro::TokenStream, base: Base) -> proc_m
 fn replacebits_base(
     input: &proc_macro::TokenStream,
     base: Base,
+    swap: bool,
 ) -> proc_macro::TokenStream {
+    let macro_name = if swap { "replacebits_swap" } else { "replacebits" };
     let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, input.clone().into())
-        .expect("replacebits! argument list should be formatted sanely");
+        .unwrap_or_else(|_| panic!("{macro_name}! argument list should be formatted sanely"));
     let mut parts: Vec<_> = parts.into_iter().collect();
-    assert!(parts.len() > 1,
-        "replacebits must take at least two arguments: \
-        an input value then a template. Found:\n`{input}`");
-    assert!(parts.len() <= 3,
-        "replacebits must take at most three arguments: \
-        an overflow setting, then an input value, then a template. Found:\n`{input}`");
+    let settings = Settings::take(macro_name, &mut parts, &["overflow", "width", "placeholder",
+        "clear_unused", "warn_noncontiguous", "noncontiguous", "group"]);
+    Settings::reject_misplaced(&parts);
+    if parts.len() < 2 {
+        return syn::Error::new(Span::call_site(), format!(
+            "{macro_name}! must take at least two arguments: an input value then a template."))
+            .to_compile_error()
+            .into();
+    }
 
-    let mut on_overflow = OnOverflow::Truncate;
-    if parts.len() == 3 {
-        let (setting, value) = parse_assignment(&parts[0])
-            .expect("the first argument to be an 'overflow' setting since three arguments were supplied");
-        assert_eq!(setting, "overflow", "Only 'overflow' is allowed as a setting.");
-        on_overflow = OnOverflow::parse(&value)
-            .unwrap_or_else(|err_string| panic!("Invalid type for setting 'overflow'. {err_string}"));
+    if let Some(extra) = parts.get(2) {
+        return syn::Error::new(extra.span(),
+            format!("{macro_name}! must take at most two arguments after its settings: \
+                an input value then a template. Unexpected argument: `{}`", extra.to_token_stream()))
+            .to_compile_error()
+            .into();
+    }
 
-        parts.remove(0);
+    let value = parts[0].clone();
+    let expr = Template::with_placeholder(&parts[1], settings.placeholder(base));
+    reject_misaligned_groups(&expr, base, &settings);
+    let template = Template::from_expr(&expr, base, Precision::Ux);
+    reject_noncontiguous(&template, &settings);
+    let clear_unused = settings.flag("clear_unused");
+    reject_clamp(settings.overflow(), macro_name);
+    let field_overflows = settings.field_overflows();
+    for (name, &on_overflow) in &field_overflows {
+        assert!(template.field_width(*name).is_some(),
+            "Field '{name}' was passed to setting 'overflow', but isn't in the template.");
+        reject_clamp(on_overflow, macro_name);
     }
 
-    for part in &parts {
-        assert!(parse_assignment(part).is_none(),
-            "Either an input or template was missing, but found a setting instead.");
+    if !swap {
+        return template.replace(settings.overflow(), &field_overflows, &value, settings.width(), clear_unused).into();
     }
 
-    let value = parts[0].clone();
-    let template = Template::from_expr(&parts[1], base, Precision::Ux);
-    let result = template.replace(on_overflow, &value);
-    result.into()
+    // The target is only evaluated once, then both read from (for the old fields) and replaced.
+    let target: Expr = parse_quote! { _target };
+    let replaced = template.replace(settings.overflow(), &field_overflows, &target, settings.width(), clear_unused);
+    let extraction_template = Template::from_expr(&expr, base, Precision::Standard);
+    let old_input = match settings.width() {
+        Some(_) => {
+            let t = extraction_template.width().to_token_stream();
+            parse_quote! { (_target as #t) }
+        }
+        None => target,
+    };
+    let old_fields = to_tuple(&extraction_template.extract_fields(&old_input, None), &settings, false);
+    quote! {
+        {
+            let _target = #value;
+            (#replaced, #old_fields)
+        }
+    }.into()
 }
 
 fn parse_splitbits_input(
+    macro_name: &'static str,
     item: &TokenStream,
     base: Base,
     precision: Precision,
-) -> (Expr, Template, Option<Type>) {
+    extra_settings: &[&str],
+) -> Result<(Expr, Template, Settings), syn::Error> {
     let parts = Parser::parse2(Punctuated::<Expr, Token![,]>::parse_terminated, item.clone())
         .expect("splitbits! argument list should be formatted sanely");
     let mut parts: Vec<_> = parts.into_iter().collect();
-    assert!(parts.len() > 1,
-        "splitbits must take at least two arguments: \
-        an input value then a template. Found:\n`{item}`");
-    assert!(parts.len() <= 3,
-        "splitbits must take at most three arguments: \
-        a min type, then an input value, then a template. Found:\n`{item}`");
+    let supported_settings = [EXTRACTION_SETTINGS, extra_settings].concat();
+    let settings = Settings::take(macro_name, &mut parts, &supported_settings);
+    Settings::reject_misplaced(&parts);
+    if parts.len() < 2 {
+        return Err(syn::Error::new(Span::call_site(),
+            format!("{macro_name}! must take at least two arguments: an input value then a template.")));
+    }
+
+    if let Some(extra) = parts.get(2) {
+        return Err(syn::Error::new(extra.span(),
+            format!("{macro_name}! must take at most two arguments after its settings: \
+                an input value then a template. Unexpected argument: `{}`", extra.to_token_stream())));
+    }
 
-    let mut min_size = None;
-    if parts.len() == 3 {
-        let (setting, value) = parse_assignment(&parts[0])
-            .expect("the first argument to be a 'min' setting since three arguments were supplied");
-        assert_eq!(setting, "min", "Only 'min' is allowed as a setting.");
-        let size = Type::parse(value)
-            .unwrap_or_else(|err_string| panic!("Invalid type for setting 'min'. {err_string}"));
-        assert!(precision != Precision::Standard || size.is_standard(), "Type '{size}' is only supported in _ux macros.");
-        min_size = Some(size);
+    parts[1] = Template::with_placeholder(&parts[1], settings.placeholder(base));
+    reject_misaligned_groups(&parts[1], base, &settings);
+    let template = Template::from_expr(&parts[1], base, precision);
+    reject_noncontiguous(&template, &settings);
+    let value = parts[0].clone();
+    Ok((value, template, settings))
+}
 
-        parts.remove(0);
+// If the 'group' setting is set, fail if the digits of the template aren't grouped as specified.
+fn reject_misaligned_groups(template: &Expr, base: Base, settings: &Settings) {
+    if let Some(group) = settings.group() {
+        template::reject_misaligned_groups(&Template::template_string(template), base, group);
     }
+}
 
-    let template_string = Template::template_string(&parts[1]);
-    for c in template_string.chars() {
-        assert!(!c.is_numeric() && !c.is_ascii_uppercase(),
-            "Literals not allowed in this context, but found '{c}' in '{template_string}'.");
+/* If 'warn_noncontiguous' is set, fail if any field is split into non-adjacent segments, unless the
+ * field is acknowledged by the 'noncontiguous' setting. Proc macros can't emit warnings on stable,
+ * so an unacknowledged split is an error instead.
+ */
+fn reject_noncontiguous(template: &Template, settings: &Settings) {
+    let acknowledged = settings.field_values("noncontiguous");
+    if !settings.flag("warn_noncontiguous") {
+        assert!(acknowledged.is_empty(), "Setting 'noncontiguous' requires 'warn_noncontiguous=true'.");
+        return;
     }
 
-    for part in &parts {
-        assert!(parse_assignment(part).is_none(),
-            "Either an input or template was missing, but found a setting instead.");
+    for (name, value) in &acknowledged {
+        assert!(value.is_none(), "Setting 'noncontiguous' must only list field names, e.g. 'noncontiguous(a, b)'.");
+        assert!(template.has_name(*name),
+            "Field '{name}' was passed to setting 'noncontiguous', but isn't in the template.");
     }
 
-    let value = parts[0].clone();
-    let template = Template::from_expr(&parts[1], base, precision);
-    (value, template, min_size)
+    for name in template.noncontiguous_names() {
+        assert!(acknowledged.iter().any(|(n, _)| *n == name),
+            "Field '{name}' is split into non-adjacent segments, which may be a typo. \
+            If the split is intentional, acknowledge it with 'noncontiguous({name})'.");
+    }
 }
 
-fn parse_assignment(expr: &Expr) -> Option<(String, String)> {
-    if let Expr::Assign(ExprAssign { left, right, ..}) = expr {
-        let left = expr_to_ident(left)
-            .expect("Setting name must be entirely alphabetical characters");
-        let right = expr_to_ident(right)
-            .expect("Setting value must be entirely alphabetical characters");
-        Some((left, right))
-    } else {
-        None
+// Extract the fields of the template from the input value, applying any field-level settings.
+fn extract_fields(template: &Template, value: &Expr, settings: &Settings, precision: Precision) -> Vec<Field> {
+    let value = aligned_value(template, value, settings);
+    let min = settings.min(precision);
+    let fields = template.extract_fields(&value, min);
+    if settings.flag("bools_only") {
+        assert!(min.is_none_or(|min| min == Type::Bool),
+            "Setting 'bools_only' must not be combined with 'min={}', since that prevents bool fields.",
+            min.unwrap());
+        for field in &fields {
+            assert!(field.width() == 1,
+                "Setting 'bools_only' requires every field to be a single bit, but field '{}' has {} bits.",
+                field.name(), field.width());
+        }
+    }
+
+    let lsb_first: Vec<Name> = settings.field_values("field_order").into_iter()
+        .filter_map(|(name, order)| {
+            assert!(fields.iter().any(|field| field.name() == name),
+                "Field '{}' was passed to setting 'field_order', but isn't in the template.", name);
+            let order = match order {
+                Some(Expr::Path(ExprPath { path, .. })) if path.get_ident().is_some() =>
+                    SegmentOrder::parse(&path.get_ident().unwrap().to_string()),
+                _ => Err(format!("Setting 'field_order' must specify an order for field '{name}', \
+                    e.g. 'field_order({name} = lsb_first)'.")),
+            };
+            let order = order.unwrap_or_else(|err_string| panic!("Invalid value for setting 'field_order'. {err_string}"));
+            (order == SegmentOrder::LsbFirst).then_some(name)
+        })
+        .collect();
+
+    let inverted = settings.names("invert");
+    for name in &inverted {
+        assert!(fields.iter().any(|field| field.name() == *name),
+            "Field '{}' was passed to setting 'invert', but isn't in the template.", name);
+    }
+
+    let signed = settings.names("signed");
+    for name in &signed {
+        assert!(fields.iter().any(|field| field.name() == *name),
+            "Field '{}' was passed to setting 'signed', but isn't in the template.", name);
+    }
+
+    let ratios: Vec<(Name, Float)> = settings.field_values("ratio").into_iter()
+        .map(|(name, float)| {
+            assert!(fields.iter().any(|field| field.name() == name),
+                "Field '{}' was passed to setting 'ratio', but isn't in the template.", name);
+            let float = float.map_or(Ok(Float::F32), |float| match float {
+                Expr::Path(ExprPath { path, .. }) if path.get_ident().is_some() =>
+                    Float::parse(&path.get_ident().unwrap().to_string()),
+                _ => Err("Setting 'ratio' values must be 'f32' or 'f64'.".to_string()),
+            });
+            (name, float.unwrap_or_else(|err_string| panic!("Invalid value for setting 'ratio'. {err_string}")))
+        })
+        .collect();
+
+    let ranges: Vec<(Name, Expr)> = settings.field_values("range").into_iter()
+        .map(|(name, range)| {
+            assert!(fields.iter().any(|field| field.name() == name),
+                "Field '{}' was passed to setting 'range', but isn't in the template.", name);
+            let range = range.unwrap_or_else(|| panic!(
+                "Setting 'range' must specify the range of field '{}', e.g. 'range({} = 1..=12)'.",
+                name, name));
+            (name, range)
+        })
+        .collect();
+
+    let bytes = settings.field_values("bytes");
+    for (name, value) in &bytes {
+        assert!(fields.iter().any(|field| field.name() == *name),
+            "Field '{}' was passed to setting 'bytes', but isn't in the template.", name);
+        assert!(value.is_none(), "Setting 'bytes' must only list field names, e.g. 'bytes(a, b)'.");
     }
+
+    let lookups: Vec<(Name, ExprArray, TokenStream)> = settings.field_values("lookup").into_iter()
+        .map(|(name, table)| {
+            assert!(fields.iter().any(|field| field.name() == name),
+                "Field '{}' was passed to setting 'lookup', but isn't in the template.", name);
+            let Some(Expr::Array(table)) = table else {
+                panic!("Setting 'lookup' must specify an array for field '{}', e.g. 'lookup({} = [1u32, 2, 4, 8])'.",
+                    name, name);
+            };
+            let element_type = lookup_element_type(name, &table);
+            (name, table, element_type)
+        })
+        .collect();
+
+    let chars: Vec<(Name, u32)> = settings.field_values("char").into_iter()
+        .map(|(name, offset)| {
+            assert!(fields.iter().any(|field| field.name() == name),
+                "Field '{}' was passed to setting 'char', but isn't in the template.", name);
+            let offset = match offset {
+                None => 0,
+                Some(Expr::Lit(ExprLit { lit: Lit::Int(offset), .. })) => offset.base10_parse().unwrap_or_else(|_|
+                    panic!("The char offset for field '{}' must fit in a u32, but was {offset}.", name)),
+                Some(_) => panic!("Setting 'char' must specify an integer literal offset for field '{}' (if any), \
+                    e.g. 'char({} = 0x20)'.", name, name),
+            };
+            (name, offset)
+        })
+        .collect();
+
+    let mapped: Vec<(Name, Expr, TokenStream)> = settings.field_values("map_into").into_iter()
+        .map(|(name, function)| {
+            assert!(fields.iter().any(|field| field.name() == name),
+                "Field '{}' was passed to setting 'map_into', but isn't in the template.", name);
+            let (function, t) = mapped_function(name, function);
+            (name, function, t)
+        })
+        .collect();
+
+    let conversion = settings.conversion();
+    let fields = fields.into_iter()
+        .map(|field| if lsb_first.contains(&field.name()) { field.lsb_first() } else { field })
+        .map(|field| if inverted.contains(&field.name()) { field.invert() } else { field })
+        .map(|field| if conversion == Conversion::Masked { field.masked() } else { field })
+        .map(|field| match ratios.iter().find(|(name, _)| *name == field.name()) {
+            Some(&(_, float)) => field.ratio(float),
+            None => field,
+        })
+        .map(|field| match ranges.iter().find(|(name, _)| *name == field.name()) {
+            Some((_, range)) => field.range(range.clone()),
+            None => field,
+        })
+        .map(|field| if bytes.iter().any(|(name, _)| *name == field.name()) { field.bytes() } else { field })
+        .map(|field| match lookups.iter().find(|(name, _, _)| *name == field.name()) {
+            Some((_, table, element_type)) => field.lookup(table.clone(), element_type.clone()),
+            None => field,
+        })
+        .map(|field| match chars.iter().find(|(name, _)| *name == field.name()) {
+            Some(&(_, offset)) => field.char(offset),
+            None => field,
+        })
+        .map(|field| if signed.contains(&field.name()) { field.twos_complement() } else { field })
+        .map(|field| match mapped.iter().find(|(name, _, _)| *name == field.name()) {
+            Some((_, function, t)) => field.map_into(function.clone(), t.clone()),
+            None => field,
+        })
+        .collect();
+    let fields = sign_magnitude_fields(fields, settings);
+    ordered_fields(fields, settings)
 }
 
-fn expr_to_ident(expr: &Expr) -> Result<String, String> {
-    if let Expr::Path(path) = expr {
-        path.path.get_ident()
-            .ok_or_else(|| format!("Can't convert expr path to a setting component. Expr path: {path:?}"))
-            .map(ToString::to_string)
-    } else {
-        Err(format!("Can't convert expr to a setting component. Expr: {expr:?}"))
+/* Combine the magnitude and sign fields passed to 'sign_magnitude' into a single signed field, with
+ * the name of the magnitude field. The sign field is removed, since its value is part of the result.
+ */
+fn sign_magnitude_fields(mut fields: Vec<Field>, settings: &Settings) -> Vec<Field> {
+    if !settings.contains("sign_magnitude") {
+        return fields;
+    }
+
+    let names = settings.field_values("sign_magnitude");
+    let [(magnitude, None), (sign, None)] = names[..] else {
+        panic!("Setting 'sign_magnitude' must list the magnitude field then the sign field, \
+            e.g. 'sign_magnitude(v, s)'.");
+    };
+    let position = |fields: &[Field], name: Name| fields.iter()
+        .position(|field| field.name() == name)
+        .unwrap_or_else(|| panic!("Field '{name}' was passed to setting 'sign_magnitude', but isn't in the template."));
+    let sign = fields.remove(position(&fields, sign));
+    let magnitude_index = position(&fields, magnitude);
+    fields[magnitude_index] = fields[magnitude_index].clone().sign_magnitude(sign);
+    fields
+}
+
+/* The function of a map_into field, and the type that it returns. The type is either written
+ * explicitly (`to_celsius as Celsius`), or is the type that the function's path is in (`Duration` in
+ * `Duration::from_millis`), as is usual for constructors.
+ */
+fn mapped_function(name: Name, function: Option<Expr>) -> (Expr, TokenStream) {
+    match function {
+        Some(Expr::Cast(cast)) => {
+            let t = &cast.ty;
+            (*cast.expr, quote! { #t })
+        }
+        Some(Expr::Path(mut path)) if path.path.segments.len() > 1 => {
+            let function = Expr::Path(path.clone());
+            path.path.segments.pop();
+            path.path.segments.pop_punct();
+            (function, quote! { #path })
+        }
+        _ => panic!("Setting 'map_into' must specify a function for field '{}' whose return type can be \
+            determined, e.g. 'map_into({} = Duration::from_millis)' or 'map_into({} = to_celsius as Celsius)'.",
+            name, name, name),
+    }
+}
+
+/* The type of the elements of a lookup table, taken from the suffix of its first element (such as
+ * u32 in `100u32`). Unsuffixed literals get the same types that Rust would infer for them.
+ */
+fn lookup_element_type(name: Name, table: &ExprArray) -> TokenStream {
+    let mut first = table.elems.first();
+    // Negative literals are a negation applied to a literal.
+    if let Some(Expr::Unary(unary)) = first {
+        first = Some(&*unary.expr);
+    }
+
+    let element_type = match first {
+        Some(Expr::Lit(ExprLit { lit: Lit::Int(int), .. })) if int.suffix().is_empty() => "i32",
+        Some(Expr::Lit(ExprLit { lit: Lit::Int(int), .. })) => int.suffix(),
+        Some(Expr::Lit(ExprLit { lit: Lit::Float(float), .. })) if float.suffix().is_empty() => "f64",
+        Some(Expr::Lit(ExprLit { lit: Lit::Float(float), .. })) => float.suffix(),
+        _ => panic!("The first entry of the lookup table for field '{name}' must be a number literal, \
+            so that the type of the field can be determined."),
+    };
+    let element_type = format_ident!("{element_type}");
+    quote! { #element_type }
+}
+
+// Reorder the fields as declared by the 'order' setting, if it was passed.
+fn ordered_fields(mut fields: Vec<Field>, settings: &Settings) -> Vec<Field> {
+    if !settings.contains("order") {
+        return fields;
+    }
+
+    let order = settings.field_values("order");
+    let mut ordered = Vec::new();
+    for (name, value) in order {
+        assert!(value.is_none(), "Setting 'order' must only list field names, e.g. 'order(c, a, b)'.");
+        let index = fields.iter()
+            .position(|field| field.name() == name)
+            .unwrap_or_else(|| panic!("Field '{name}' was passed to setting 'order', but isn't in the template."));
+        ordered.push(fields.remove(index));
     }
+
+    let missing: Vec<String> = fields.iter().map(|field| field.name().to_string()).collect();
+    assert!(missing.is_empty(), "Setting 'order' must list every field of the template, but is missing {missing:?}.");
+    ordered
+}
+
+// The input value, shifted so that the bits that the template matches against are the low bits.
+fn aligned_value(template: &Template, value: &Expr, settings: &Settings) -> Expr {
+    let value = &bit_ordered_value(template, value, settings);
+    let aligned = match settings.align() {
+        Align::Low => value.clone(),
+        // Shift the high bits of the input down to where the template will extract them from.
+        Align::High => {
+            let template_width = u32::from(template.width().bit_count());
+            parse_quote! {
+                (#value >> (::core::mem::size_of_val(&#value) as u32 * 8 - #template_width))
+            }
+        }
+    };
+    rotated_value(template, &aligned, settings)
+}
+
+/* Rotate the aligned input right, if requested. Casting to the template's type first makes the
+ * rotation wrap within the template, rather than within a wider input type.
+ */
+fn rotated_value(template: &Template, value: &Expr, settings: &Settings) -> Expr {
+    let Some(rotation) = settings.integer("rotate_input") else {
+        return value.clone();
+    };
+
+    let template_width = template.width().bit_count();
+    assert!(rotation < template_width,
+        "Setting 'rotate_input' must be less than the template width ({template_width}), but was {rotation}.");
+    let t = template.width().to_token_stream();
+    let rotation = u32::from(rotation);
+    parse_quote! { (#value as #t).rotate_right(#rotation) }
+}
+
+/* Reverse the bits within each byte of the input if it was transmitted least significant bit first,
+ * leaving the bytes themselves in place. Reversing all the bits then swapping the bytes back does
+ * exactly that, for any width of integer.
+ */
+fn bit_ordered_value(template: &Template, value: &Expr, settings: &Settings) -> Expr {
+    match settings.bit_order() {
+        BitOrder::Msb => value.clone(),
+        BitOrder::LsbInByte => {
+            let template_width = template.width().bit_count();
+            assert!(template_width.is_multiple_of(8),
+                "Setting 'bit_order=lsb_in_byte' requires a template made of whole bytes, but it was {template_width} bits.");
+            parse_quote! { (#value).reverse_bits().swap_bytes() }
+        }
+    }
+}
+
+// The input for a field of a combinebits! template, failing if the template doesn't have the field.
+fn field_input<'a>(inputs: &'a mut [(Name, TokenStream)], name: Name, setting: &str) -> &'a mut TokenStream {
+    inputs.iter_mut()
+        .find(|(n, _)| *n == name)
+        .map(|(_, input)| input)
+        .unwrap_or_else(|| panic!(
+            "Field '{}' was passed to setting '{setting}', but isn't in the template.", name))
 }
@@ -30,7 +30,26 @@ impl Location {
 
     // Convert the Location to a bit mask, but starting at the start of the template.
     pub fn to_unshifted_mask(self) -> u128 {
-        2u128.pow(u32::from(self.width)) - 1
+        // Shift down from all ones since 2^128 (for a full-width 128-bit Location) doesn't fit.
+        u128::MAX >> (128 - u32::from(self.width))
+    }
+
+    /* Describe the bit range of the Location within a template of the specified width, leftmost
+     * bit first (e.g. "7:4" or "0"). Only affects how positions are displayed, not extraction.
+     */
+    pub fn to_range_string(self, numbering: BitNumbering, template_width: u8) -> String {
+        let low = self.mask_offset;
+        let high = low + self.width - 1;
+        let (left, right) = match numbering {
+            BitNumbering::Lsb0 => (high, low),
+            BitNumbering::Msb0 => (template_width - 1 - high, template_width - 1 - low),
+        };
+
+        if left == right {
+            format!("{left}")
+        } else {
+            format!("{left}:{right}")
+        }
     }
 
     /* Place the name of a field within its appropriate location in the template,
@@ -48,7 +67,10 @@ impl Location {
         let mask = self.to_unshifted_mask();
         match on_overflow {
             OnOverflow::Corrupt  => quote! { #width::from(#segment) << #shift },
-            OnOverflow::Truncate => quote! { (#width::from(#segment) & (#mask as #width)) << #shift },
+            // Clamped inputs have already been brought into range (as signed values), so they are
+            // only truncated here to drop the sign-extended upper bits.
+            OnOverflow::Truncate | OnOverflow::Clamp =>
+                quote! { (#width::from(#segment) & (#mask as #width)) << #shift },
             OnOverflow::Panic    => quote! {
                 {
                     let n = #width::from(#segment);
@@ -58,6 +80,30 @@ impl Location {
                     n << #shift
                 }
             },
+            OnOverflow::Error => {
+                let failure = crate::runtime_failure(true, &quote! {
+                    "Variable {} is too big for its location in the template. 0b{n:b} > 0b{:b}", #label, #mask
+                });
+                quote! {
+                    {
+                        let n = #width::from(#segment);
+                        if n > #mask as #width {
+                            #failure;
+                        }
+
+                        n << #shift
+                    }
+                }
+            }
+            OnOverflow::DebugChecked => quote! {
+                {
+                    let n = #width::from(#segment);
+                    debug_assert!(n <= #mask as #width,
+                        "Variable {} is too big for its location in the template. 0b{n:b} > 0b{:b}",
+                        #label, #mask);
+                    (n & (#mask as #width)) << #shift
+                }
+            },
             OnOverflow::Saturate => quote! {
                 {
                     let mut n = #width::from(#segment);
@@ -76,6 +122,7 @@ impl Location {
 #[derive(Debug, Clone, Copy)]
 pub enum OnOverflow {
     // Remove the upper bits that don't fit in the template slot.
+    // Equivalent to wrapping (n % (mask + 1)), since every mask is all ones.
     Truncate,
     // Panic if the field is too large for its slot.
     Panic,
@@ -83,6 +130,13 @@ pub enum OnOverflow {
     Corrupt,
     // Set all bits in the slot to 1s if the field is too large.
     Saturate,
+    // Panic in debug builds if the field is too large for its slot, but truncate in release builds.
+    DebugChecked,
+    // Clamp a signed field to the signed range of its slot, then store it in two's complement.
+    Clamp,
+    // Return an Err from the enclosing try_combinebits! if the field is too large for its slot.
+    // Not a setting value: overflow=panic becomes this within try_combinebits!.
+    Error,
 }
 
 impl OnOverflow {
@@ -93,8 +147,31 @@ impl OnOverflow {
             "panic" => OnOverflow::Panic,
             "corrupt" => OnOverflow::Corrupt,
             "saturate" => OnOverflow::Saturate,
+            "debug_checked" => OnOverflow::DebugChecked,
+            "clamp" => OnOverflow::Clamp,
             overflow => return Err(format!("'{overflow}' is an invalid overflow option. \
-                Options: 'truncate', 'panic', 'corrupt', 'saturate'.")),
+                Options: 'truncate', 'panic', 'corrupt', 'saturate', 'debug_checked', 'clamp'.")),
+        })
+    }
+}
+
+// Which bit is considered "bit 0" when bit positions are displayed.
+#[derive(Debug, Clone, Copy)]
+pub enum BitNumbering {
+    // Bit 0 is the least significant (right-most) bit. Rust's convention.
+    Lsb0,
+    // Bit 0 is the most significant (left-most) bit. Used by some datasheets.
+    Msb0,
+}
+
+impl BitNumbering {
+    // Convert a lower-case str into its corresponding BitNumbering value.
+    pub fn parse(text: &str) -> Result<BitNumbering, String> {
+        Ok(match text {
+            "lsb0" => BitNumbering::Lsb0,
+            "msb0" => BitNumbering::Msb0,
+            numbering => return Err(format!("'{numbering}' is an invalid bit numbering option. \
+                Options: 'lsb0', 'msb0'.")),
         })
     }
 }
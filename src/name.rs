@@ -1,33 +1,88 @@
+use std::fmt;
+
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 
-// A single char Field name.
+// The maximum number of chars in a Name.
+const MAX_LENGTH: usize = 32;
+// Names that the generated code already uses for members of its own (by rest, keep_raw, and
+// combinebits_builder!), so fields with them would collide.
+const RESERVED: [&str; 3] = ["rest", "raw", "build"];
+
+/* A Field name. Either a single char, or a longer name that is written between angle brackets in
+ * a template (e.g. "<mode>"). Stored inline so that Names can be copied like chars.
+ */
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
-pub struct Name(char);
+pub struct Name([u8; MAX_LENGTH]);
 
 impl Name {
     // Create a new name, failing if a non-ascii-lowercase char is provided.
     pub fn new(raw_name: char) -> Result<Self, String> {
         if raw_name.is_ascii_lowercase() {
-            Ok(Self(raw_name))
+            let mut name = [0; MAX_LENGTH];
+            name[0] = raw_name as u8;
+            Ok(Self(name))
         } else {
             Err(format!("'{raw_name}' is not a valid Name."))
         }
     }
 
-    // Convert to a unicode char.
-    pub const fn to_char(self) -> char {
-        self.0
+    /* Create a new name from the text between angle brackets in a template. Must start with an
+     * ascii-lowercase char, followed by ascii-lowercase chars and underscores. Keywords aren't
+     * allowed since the name must be usable as a variable name, nor are names reserved for members
+     * of the generated code.
+     */
+    pub fn bracketed(raw_name: &str) -> Result<Self, String> {
+        let valid_chars = raw_name.starts_with(|c: char| c.is_ascii_lowercase())
+            && raw_name.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+        if !valid_chars {
+            return Err(format!("'{raw_name}' is not a valid Name. \
+                Names must be lowercase letters and underscores, starting with a letter."));
+        }
+
+        if raw_name.len() > MAX_LENGTH {
+            return Err(format!("'{raw_name}' is not a valid Name. Names must be at most {MAX_LENGTH} chars."));
+        }
+
+        if syn::parse_str::<Ident>(raw_name).is_err() {
+            return Err(format!("'{raw_name}' is not a valid Name since it is a keyword."));
+        }
+
+        if RESERVED.contains(&raw_name) {
+            return Err(format!("'{raw_name}' is not a valid Name since it is reserved for generated code. \
+                Reserved names: {RESERVED:?}."));
+        }
+
+        let mut name = [0; MAX_LENGTH];
+        name[..raw_name.len()].copy_from_slice(raw_name.as_bytes());
+        Ok(Self(name))
+    }
+
+    // The text of the Name, without angle brackets.
+    pub fn as_str(&self) -> &str {
+        let length = self.0.iter().position(|&b| b == 0).unwrap_or(MAX_LENGTH);
+        std::str::from_utf8(&self.0[..length]).expect("Names should only contain ascii chars")
+    }
+
+    // Whether the Name is a single char, and so doesn't need to be written between angle brackets.
+    pub fn is_single_char(&self) -> bool {
+        self.as_str().len() == 1
     }
 
     // Convert for use in macro output.
     pub fn to_ident(self) -> Ident {
-        format_ident!("{}", self.to_char())
+        format_ident!("{}", self.as_str())
     }
 
     // Convert for use in macro output.
     pub fn to_token_stream(self) -> TokenStream {
-        let name = self.to_char();
+        let name = self.as_str();
         quote! { #name }
     }
 }
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::Expr;
+use syn::{Expr, ExprLit, Lit};
 
 use crate::location::Location;
 use crate::r#type::Type;
@@ -22,12 +22,22 @@ impl Segment {
         Self { input, t, location, offset }
     }
 
+    // Extract the Segment from a different input expression.
+    pub fn set_input(&mut self, input: Expr) {
+        self.input = input;
+    }
+
     // Offset this Segment further within the Template.
     pub fn set_output_offset(&mut self, output_offset: u8) -> Self {
         self.offset += output_offset;
         self.clone()
     }
 
+    // Move this Segment to a different offset within its Field.
+    pub fn set_field_offset(&mut self, offset: u8) {
+        self.offset = offset;
+    }
+
     // Increase how much space the Segment takes up, without changing its value Expr.
     pub fn widen(&mut self, new_type: Type) -> Self {
         if new_type > self.t {
@@ -53,9 +63,46 @@ impl Segment {
 
         let t = self.t.to_token_stream();
         let mask = self.location.to_mask();
+        // A right shift already clears every bit below the Segment, so a mask that covers every bit
+        // above it (up to the top of the type) would be all ones and can be dropped.
+        let type_max = u128::MAX >> (128 - u32::from(self.t.bit_count()));
+        if shift >= 0 && mask == (type_max >> shift) << shift {
+            return quote! { (#input as #t) #shifter };
+        }
+
         quote! { (#input as #t & #mask as #t) #shifter }
     }
 
+    /* The value of the Segment, if its input is an integer literal that fits within the input type.
+     * Allows extraction to be performed during macro expansion rather than at runtime.
+     */
+    pub fn literal_value(&self) -> Option<u128> {
+        let Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) = &self.input else {
+            return None;
+        };
+
+        let input: u128 = int.base10_parse().ok()?;
+        let max = u128::MAX >> (128 - u32::from(self.t.bit_count()));
+        if input > max {
+            // Leave oversized literals to the compiler, which will handle them as it would at runtime.
+            return None;
+        }
+
+        let value = input & self.location.to_mask();
+        let shift = self.shift();
+        Some(if shift >= 0 { value >> shift } else { value << -shift })
+    }
+
+    // The type of the input that the Segment is extracted from.
+    pub const fn input_type(&self) -> Type {
+        self.t
+    }
+
+    // Where the Segment is within the Template.
+    pub const fn location(&self) -> Location {
+        self.location
+    }
+
     // The width of the segment.
     pub const fn width(&self) -> u8 {
         self.location.width()
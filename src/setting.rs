@@ -0,0 +1,557 @@
+use std::collections::BTreeMap;
+
+use quote::ToTokens;
+use syn::{Expr, ExprAssign, ExprCall, ExprLit, Lit, Path};
+
+use crate::base::Base;
+use crate::field::Conversion;
+use crate::location::{OnOverflow, BitNumbering};
+use crate::name::Name;
+use crate::template::Template;
+use crate::r#type::{Type, Precision};
+
+/* The optional arguments that can be passed to a macro before its input value(s) and template,
+ * changing some behaviors from the default. Their syntax is similar to named arguments in
+ * Python: `setting_type=setting_value`. Settings that apply to individual fields instead look
+ * like function calls: `setting_type(a = field_value, b)`.
+ */
+pub struct Settings {
+    // The name of the macro that the settings were passed to. Used in error messages.
+    macro_name: &'static str,
+    // The value expression of each setting that was passed, keyed by setting name.
+    values: BTreeMap<String, Expr>,
+}
+
+impl Settings {
+    /* Remove the leading settings from a macro's argument list.
+     * Fails if a setting is passed that the macro doesn't support, or if one is passed twice.
+     */
+    pub fn take(macro_name: &'static str, parts: &mut Vec<Expr>, supported: &[&str]) -> Self {
+        // A template read from a file isn't a setting, despite looking like one.
+        for part in parts.iter_mut() {
+            if let Some(template) = Template::from_file(part) {
+                *part = template;
+            }
+        }
+
+        let mut values = BTreeMap::new();
+        while let Some((name, value)) = parts.first().and_then(|part| Self::parse_any(part, supported)) {
+            assert!(supported.contains(&name.as_str()),
+                "'{name}' is not a valid setting for {macro_name}!. Valid settings: {}.",
+                supported.iter().map(|s| format!("'{s}'")).collect::<Vec<_>>().join(", "));
+            assert!(!values.contains_key(&name), "Setting '{name}' must not be passed more than once.");
+            values.insert(name, value);
+            parts.remove(0);
+        }
+
+        Self { macro_name, values }
+    }
+
+    // No settings, for macros that don't accept any. Every setting takes its default value.
+    pub fn empty(macro_name: &'static str) -> Self {
+        Self { macro_name, values: BTreeMap::new() }
+    }
+
+    // Split a setting into its name and its value, or None if the expression isn't a setting.
+    pub fn parse(expr: &Expr) -> Option<(String, Expr)> {
+        if let Expr::Assign(ExprAssign { left, right, ..}) = expr {
+            let name = expr_to_ident(left)
+                .expect("Setting name must be entirely alphabetical characters");
+            Some((name, (**right).clone()))
+        } else {
+            None
+        }
+    }
+
+    // Split either kind of setting into its name and its value.
+    fn parse_any(expr: &Expr, supported: &[&str]) -> Option<(String, Expr)> {
+        Self::parse_field_setting(expr, supported).or_else(|| Self::parse(expr))
+    }
+
+    // Split a per-field setting into its name and its value, if it is one that is supported.
+    fn parse_field_setting(expr: &Expr, supported: &[&str]) -> Option<(String, Expr)> {
+        let Expr::Call(ExprCall { func, .. }) = expr else {
+            return None;
+        };
+
+        let name = expr_to_ident(func).ok()?;
+        supported.contains(&name.as_str()).then(|| (name, expr.clone()))
+    }
+
+    // Fail if any of the remaining (non-leading) arguments are settings, since they're misplaced.
+    pub fn reject_misplaced(parts: &[Expr]) {
+        for part in parts {
+            assert!(Self::parse(part).is_none(),
+                "Either an input or template was missing, but found a setting instead.");
+        }
+    }
+
+    // Whether the setting was passed at all.
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    // What form a macro that can produce multiple forms of output should produce.
+    pub fn output(&self) -> Output {
+        self.ident("output")
+            .map_or(Output::Fields, |value| Output::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'output'. {err_string}")))
+    }
+
+    // The smallest type that fields can be generated as. None if the default should be used.
+    pub fn min(&self, precision: Precision) -> Option<Type> {
+        let mut value = self.type_name("min")?;
+        // A signed min only sets the width. Whether a field is signed is set by the 'signed' setting.
+        if let Some(count) = value.strip_prefix('i').filter(|count| matches!(*count, "8" | "16" | "32" | "64" | "128")) {
+            value = format!("u{count}");
+        }
+
+        let size = Type::parse(value)
+            .unwrap_or_else(|err_string| panic!("Invalid type for setting 'min'. {err_string}"));
+        assert!(precision != Precision::Standard || size.is_standard(),
+            "Type '{size}' is only supported in _ux macros.");
+        Some(size)
+    }
+
+    // The type of the integer to produce, for macros that don't have a template to infer it from.
+    pub fn width(&self) -> Option<Type> {
+        let value = self.type_name("width")?;
+        let width = Type::parse(value)
+            .and_then(|width| Type::for_template(width.bit_count()))
+            .unwrap_or_else(|err_string| panic!("Invalid type for setting 'width'. {err_string}"));
+        Some(width)
+    }
+
+    /* What to do if an input value is too large for its slot in the template.
+     * The default is to truncate, unless the default-panic-overflow feature is enabled.
+     */
+    pub fn overflow(&self) -> OnOverflow {
+        let default = if cfg!(feature = "default-panic-overflow") { OnOverflow::Panic } else { OnOverflow::Truncate };
+        if matches!(self.values.get("overflow"), Some(Expr::Call(_))) {
+            return default;
+        }
+
+        self.ident("overflow")
+            .map_or(default, |value| OnOverflow::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'overflow'. {err_string}")))
+    }
+
+    /* The overflow behavior of each field that has its own, when the setting is written per field:
+     * `overflow(a = panic, c = truncate)`. Unlisted fields use the default overflow behavior.
+     */
+    pub fn field_overflows(&self) -> BTreeMap<Name, OnOverflow> {
+        if !matches!(self.values.get("overflow"), Some(Expr::Call(_))) {
+            return BTreeMap::new();
+        }
+
+        self.field_values("overflow").into_iter()
+            .map(|(name, value)| {
+                let value = value.as_ref().and_then(|value| expr_to_ident(value).ok())
+                    .unwrap_or_else(|| panic!("Field '{name}' of setting 'overflow' must be given an overflow behavior, \
+                        e.g. 'overflow({name} = panic)'."));
+                let on_overflow = OnOverflow::parse(&value)
+                    .unwrap_or_else(|err_string| panic!("Invalid value for setting 'overflow'. {err_string}"));
+                (name, on_overflow)
+            })
+            .collect()
+    }
+
+    // Whether a template narrower than its input matches the low or the high bits of the input.
+    pub fn align(&self) -> Align {
+        self.ident("align")
+            .map_or(Align::Low, |value| Align::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'align'. {err_string}")))
+    }
+
+    // The order of the bits within each byte of the input.
+    pub fn bit_order(&self) -> BitOrder {
+        self.ident("bit_order")
+            .map_or(BitOrder::Msb, |value| BitOrder::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'bit_order'. {err_string}")))
+    }
+
+    // How extracted bits are converted into the types of their fields.
+    pub fn conversion(&self) -> Conversion {
+        self.ident("conversion")
+            .map_or(Conversion::Checked, |value| Conversion::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'conversion'. {err_string}")))
+    }
+
+    // How the fields and literals of a template are merged together when combining.
+    pub fn operator(&self) -> Operator {
+        self.ident("combine")
+            .map_or(Operator::Or, |value| Operator::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'combine'. {err_string}")))
+    }
+
+    // How the digits of a template must be grouped by spaces, if the grouping should be checked.
+    pub fn group(&self) -> Option<Group> {
+        self.ident("group")
+            .map(|value| Group::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'group'. {err_string}")))
+    }
+
+    // Which bit should be considered bit 0 when displaying bit positions.
+    pub fn bit_numbering(&self) -> BitNumbering {
+        self.ident("bit_numbering")
+            .map_or(BitNumbering::Lsb0, |value| BitNumbering::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'bit_numbering'. {err_string}")))
+    }
+
+    // The base of the output template. The base of the input templates if not specified.
+    pub fn output_base(&self, input_base: Base) -> Base {
+        self.ident("output_base")
+            .map_or(input_base, |value| Base::parse(&value)
+                .unwrap_or_else(|err_string| panic!("Invalid value for setting 'output_base'. {err_string}")))
+    }
+
+    /* Whether a combined result should be left as the unsigned type of the template's width, be
+     * reinterpreted as the signed type of the same width (e.g. i16 for a 16-bit template), or be
+     * narrowed to the smallest unsigned type that fits every bit that can be set.
+     */
+    pub fn result(&self, width: Type) -> ResultType {
+        let Some(value) = self.ident("result") else {
+            return ResultType::Unsigned;
+        };
+
+        let signed = format!("i{}", width.bit_count());
+        if value == signed {
+            ResultType::Signed
+        } else if value == width.to_string() {
+            ResultType::Unsigned
+        } else if value == "fit" {
+            ResultType::Fit
+        } else {
+            panic!("Invalid value for setting 'result'. '{value}' doesn't match the width of the template. \
+                Options: '{width}', '{signed}', 'fit'.");
+        }
+    }
+
+    /* The char that marks ignored bits in templates, if a char other than '.' should be used.
+     * Fails if the char could be mistaken for a field name or a literal in the specified base.
+     */
+    pub fn placeholder(&self, base: Base) -> Option<char> {
+        let value = self.values.get("placeholder")?;
+        let Expr::Lit(ExprLit { lit: Lit::Char(placeholder), .. }) = value else {
+            panic!("Setting 'placeholder' for {}! must be set to a char literal, e.g. '-'.", self.macro_name);
+        };
+
+        let placeholder = placeholder.value();
+        let is_literal = match base {
+            Base::Binary => placeholder == '0' || placeholder == '1',
+            Base::Octal => ('0'..='7').contains(&placeholder),
+            Base::Hexadecimal => placeholder.is_ascii_digit() || ('A'..='F').contains(&placeholder),
+        };
+        assert!(!placeholder.is_ascii_lowercase() && !is_literal && !matches!(placeholder, ' ' | '<' | '>' | '|'),
+            "Invalid value for setting 'placeholder'. '{placeholder}' already has a meaning in templates.");
+        Some(placeholder)
+    }
+
+    // The value of a setting that must be an integer literal, if the setting was passed.
+    pub fn integer(&self, name: &str) -> Option<u8> {
+        let value = self.values.get(name)?;
+        if let Expr::Lit(ExprLit { lit: Lit::Int(value), .. }) = value {
+            Some(value.base10_parse()
+                .unwrap_or_else(|_| panic!("Setting '{name}' must be between 0 and 255.")))
+        } else {
+            panic!("Setting '{name}' for {}! must be set to an integer literal.", self.macro_name);
+        }
+    }
+
+    // The value of a setting that can be any expression, if the setting was passed.
+    pub fn expr(&self, name: &str) -> Option<&Expr> {
+        self.values.get(name)
+    }
+
+    // The value of a true/false setting. False if the setting wasn't passed.
+    pub fn flag(&self, name: &str) -> bool {
+        let Some(value) = self.values.get(name) else {
+            return false;
+        };
+
+        if let Expr::Lit(ExprLit { lit: Lit::Bool(value), .. }) = value {
+            value.value
+        } else {
+            panic!("Setting '{name}' for {}! must be set to either 'true' or 'false'.", self.macro_name);
+        }
+    }
+
+    /* The field names listed by a setting, written like a function call: `invert(a, mode)`.
+     * Empty if the setting wasn't passed.
+     */
+    pub fn names(&self, name: &str) -> Vec<Name> {
+        assert!(matches!(self.values.get(name), None | Some(Expr::Call(_))),
+            "Setting '{name}' for {}! must list its fields in parentheses, e.g. '{name}(a, b)'.", self.macro_name);
+        self.field_values(name).into_iter()
+            .map(|(field, value)| {
+                assert!(value.is_none(), "Setting '{name}' must only list field names, e.g. '{name}(a, b)'.");
+                field
+            })
+            .collect()
+    }
+
+    // The paths (such as trait names) listed by a setting written like a function call: `name(A, B)`.
+    pub fn paths(&self, name: &str) -> Vec<Path> {
+        let Some(value) = self.values.get(name) else {
+            return Vec::new();
+        };
+
+        let Expr::Call(ExprCall { args, .. }) = value else {
+            panic!("Setting '{name}' for {}! must list its values in parentheses, e.g. '{name}(Decoded)'.",
+                self.macro_name);
+        };
+
+        args.iter()
+            .map(|arg| match arg {
+                Expr::Path(path) => path.path.clone(),
+                arg => panic!("Setting '{name}' for {}! must only list paths, but found '{}'.",
+                    self.macro_name, arg.to_token_stream()),
+            })
+            .collect()
+    }
+
+    /* The fields that a setting takes from each struct, written like a function call with each
+     * struct expression assigned its field names: `from(header = h, self.payload = (p, f))`.
+     */
+    pub fn struct_fields(&self, name: &str) -> Vec<(Expr, Vec<Name>)> {
+        let Some(value) = self.values.get(name) else {
+            return Vec::new();
+        };
+
+        let Expr::Call(ExprCall { args, .. }) = value else {
+            panic!("Setting '{name}' for {}! must list its structs in parentheses, \
+                e.g. '{name}(header = h, payload = (p, f))'.", self.macro_name);
+        };
+
+        args.iter()
+            .map(|arg| {
+                let Expr::Assign(ExprAssign { left: source, right: fields, .. }) = arg else {
+                    panic!("Setting '{name}' must assign fields to each struct, e.g. '{name}(header = (a, b))'.");
+                };
+                let fields = match &**fields {
+                    Expr::Tuple(tuple) => tuple.elems.iter().collect(),
+                    Expr::Paren(paren) => vec![&*paren.expr],
+                    field => vec![field],
+                };
+                let fields = fields.into_iter()
+                    .map(|field| expr_to_ident(field).ok()
+                        .and_then(|field| Name::bracketed(&field).ok())
+                        .unwrap_or_else(|| panic!("Setting '{name}' must only list valid field names for struct '{}'.",
+                            source.to_token_stream())))
+                    .collect();
+                ((**source).clone(), fields)
+            })
+            .collect()
+    }
+
+    /* The fields listed by a per-field setting, paired with their values, if any were specified.
+     * For example, `optional(a = 5, b)` pairs 'a' with Some(5) and 'b' with None.
+     */
+    pub fn field_values(&self, name: &str) -> Vec<(Name, Option<Expr>)> {
+        let Some(value) = self.values.get(name) else {
+            return Vec::new();
+        };
+
+        let Expr::Call(ExprCall { args, .. }) = value else {
+            panic!("Setting '{name}' for {}! must list its fields in parentheses, \
+                e.g. '{name}(a = 0, b = 1)'.", self.macro_name);
+        };
+
+        let mut field_values: Vec<(Name, Option<Expr>)> = Vec::new();
+        for arg in args {
+            let (field, field_value) = match arg {
+                Expr::Assign(ExprAssign { left, right, .. }) => (&**left, Some((**right).clone())),
+                field => (field, None),
+            };
+            let field = expr_to_ident(field).ok()
+                .and_then(|field| Name::bracketed(&field).ok())
+                .unwrap_or_else(|| panic!("Setting '{name}' must only list valid field names."));
+            assert!(field_values.iter().all(|(existing, _)| *existing != field),
+                "Field '{}' must not be listed more than once in setting '{name}'.", field);
+            field_values.push((field, field_value));
+        }
+
+        field_values
+    }
+
+    /* The value of a setting that is a type, such as u16. Fully-qualified primitive paths, such as
+     * core::primitive::u16, are accepted too, resolving to their last segment.
+     */
+    fn type_name(&self, name: &str) -> Option<String> {
+        let value = self.values.get(name)?;
+        let Expr::Path(path) = value else {
+            panic!("Setting '{name}' must be set to a type, e.g. 'u16'.");
+        };
+        let segments: Vec<String> = path.path.segments.iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        match &segments[..] {
+            [t] => Some(t.clone()),
+            [krate, primitive, t] if (krate == "core" || krate == "std") && primitive == "primitive" =>
+                Some(t.clone()),
+            _ => panic!("Setting '{name}' must be set to a primitive type, such as 'u16' or \
+                'core::primitive::u16', but was '{}'.", segments.join("::")),
+        }
+    }
+
+    // The value of a setting that must be a single identifier, if the setting was passed.
+    fn ident(&self, name: &str) -> Option<String> {
+        let value = self.values.get(name)?;
+        Some(expr_to_ident(value)
+            .unwrap_or_else(|_| panic!("Setting '{name}' value must be entirely alphabetical characters")))
+    }
+}
+
+// Which bits of an input a narrower template should be matched against.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Align {
+    // Match against the least significant bits of the input.
+    Low,
+    // Match against the most significant bits of the input.
+    High,
+}
+
+impl Align {
+    // Convert a lower-case str into its corresponding Align value.
+    pub fn parse(text: &str) -> Result<Align, String> {
+        Ok(match text {
+            "low" => Align::Low,
+            "high" => Align::High,
+            align => return Err(format!("'{align}' is an invalid align option. Options: 'low', 'high'.")),
+        })
+    }
+}
+
+// The order that the bits within each byte of an input are transmitted in.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum BitOrder {
+    // The most significant bit of each byte comes first, as in a normal integer.
+    Msb,
+    // The least significant bit of each byte comes first, but bytes are still most significant first.
+    LsbInByte,
+}
+
+impl BitOrder {
+    // Convert a lower-case str into its corresponding BitOrder value.
+    pub fn parse(text: &str) -> Result<BitOrder, String> {
+        Ok(match text {
+            "msb" => BitOrder::Msb,
+            "lsb_in_byte" => BitOrder::LsbInByte,
+            order => return Err(format!("'{order}' is an invalid bit_order option. Options: 'msb', 'lsb_in_byte'.")),
+        })
+    }
+}
+
+// Which segment of a field that is split across a template holds the field's most significant bits.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SegmentOrder {
+    // The first (leftmost) segment in the template holds the most significant bits.
+    MsbFirst,
+    // The first (leftmost) segment in the template holds the least significant bits.
+    LsbFirst,
+}
+
+impl SegmentOrder {
+    // Convert a lower-case str into its corresponding SegmentOrder value.
+    pub fn parse(text: &str) -> Result<SegmentOrder, String> {
+        Ok(match text {
+            "msb_first" => SegmentOrder::MsbFirst,
+            "lsb_first" => SegmentOrder::LsbFirst,
+            order => return Err(format!("'{order}' is an invalid field_order option. Options: 'msb_first', 'lsb_first'.")),
+        })
+    }
+}
+
+// The form of the output of a macro that can produce fields in more than one form.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Output {
+    // A struct with one field per template name.
+    Fields,
+    // A tuple of the field values, in template order.
+    Tuple,
+    // An array of the field values widened to u128, in template order.
+    Array,
+}
+
+impl Output {
+    // Convert a lower-case str into its corresponding Output value.
+    pub fn parse(text: &str) -> Result<Output, String> {
+        Ok(match text {
+            // 'struct' is a keyword, so it can't be used as a setting value.
+            "fields" => Output::Fields,
+            "tuple" => Output::Tuple,
+            "array" => Output::Array,
+            output => return Err(format!(
+                "'{output}' is an invalid output option. Options: 'fields', 'tuple', 'array'.")),
+        })
+    }
+}
+
+// The size that every space-separated group of digits in a template must be.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Group {
+    // Four bits: one hexadecimal digit, or four binary digits.
+    Nibble,
+    // Eight bits: two hexadecimal digits, or eight binary digits.
+    Byte,
+}
+
+impl Group {
+    // Convert a lower-case str into its corresponding Group value.
+    pub fn parse(text: &str) -> Result<Group, String> {
+        Ok(match text {
+            "nibble" => Group::Nibble,
+            "byte" => Group::Byte,
+            group => return Err(format!("'{group}' is an invalid group option. Options: 'nibble', 'byte'.")),
+        })
+    }
+
+    // How many bits each group must contain.
+    pub const fn bit_count(self) -> u8 {
+        match self {
+            Group::Nibble => 4,
+            Group::Byte => 8,
+        }
+    }
+}
+
+// The type of the integer produced by combining.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ResultType {
+    // The unsigned type of the template's width.
+    Unsigned,
+    // The signed type of the template's width, with the top bit of the template as the sign bit.
+    Signed,
+    // The smallest unsigned type that can hold the highest bit that can be set.
+    Fit,
+}
+
+// The bitwise operator used to merge fields and literals together into a combined integer.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Operator {
+    // Set each bit that is set in any field or literal. Fields and literals never overlap.
+    Or,
+    // Flip each bit that is set in a field or literal, so overlapping bits cancel out.
+    Xor,
+}
+
+impl Operator {
+    // Convert a lower-case str into its corresponding Operator value.
+    pub fn parse(text: &str) -> Result<Operator, String> {
+        Ok(match text {
+            "or" => Operator::Or,
+            "xor" => Operator::Xor,
+            operator => return Err(format!("'{operator}' is an invalid combine option. Options: 'or', 'xor'.")),
+        })
+    }
+}
+
+fn expr_to_ident(expr: &Expr) -> Result<String, String> {
+    if let Expr::Path(path) = expr {
+        path.path.get_ident()
+            .ok_or_else(|| format!("Can't convert expr path to a setting component. Expr path: {path:?}"))
+            .map(ToString::to_string)
+    } else {
+        Err(format!("Can't convert expr to a setting component. Expr: {expr:?}"))
+    }
+}
@@ -1,17 +1,25 @@
+use std::cell::RefCell;
 use std::collections::{BTreeSet, BTreeMap, VecDeque};
 
-use proc_macro2::{TokenStream, Ident};
+use proc_macro2::{TokenStream, Ident, Span};
 use quote::{quote, format_ident, ToTokens};
-use syn::{Expr, Lit};
+use syn::{Expr, ExprAssign, ExprLit, Lit, LitStr};
+use syn::spanned::Spanned;
 
 use crate::base::Base;
-use crate::character::Characters;
+use crate::character::{Character, Characters};
 use crate::field::Field;
 use crate::location::Location;
 use crate::name::Name;
 use crate::location::OnOverflow;
+use crate::setting::{Group, Operator};
 use crate::r#type::{Type, Precision};
 
+thread_local! {
+    // The absolute paths of the template files that were read by the current expansion.
+    static TEMPLATE_FILES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
 /* A sequence of characters used to match and extract bit fields from an integer,
  * or alternately to combine bit fields into an integer.
  * For example, "aaabbcdd" will extract variables a, b, c, and d from a byte (u8),
@@ -25,9 +33,14 @@ pub struct Template {
     precision: Precision,
     // The template-legal characters contained in this template, in order.
     characters: Characters,
+    // How many placeholders were added to the left of the characters to reach a standard width.
+    // The logical width of the template is its width minus its padding.
+    padding: u8,
     // The locations of the disjoint segments of each bit field, paired with the field name.
     // The locations for a name are ordered from right-to-left (offsets in ascending order).
     locations_by_name: Vec<(Name, Vec<Location>)>,
+    // Where the template is in the macro invocation. Used to point errors at the template.
+    span: Span,
 }
 
 impl Template {
@@ -35,7 +48,13 @@ impl Template {
     pub fn from_expr(expr: &Expr, base: Base, precision: Precision) -> Self {
         let template_string = Self::template_string(expr);
         reject_higher_base_chars(&template_string, base);
-        let characters = Characters::from_str(&template_string, base);
+        let mut characters = Characters::from_str(&template_string, base);
+        // Templates that aren't a standard width (including all octal templates) are read as the
+        // next larger standard type, with the extra high bits padded out as placeholders.
+        let width = Type::for_template(characters.width())
+            .or_else(|_| Type::for_nonstandard_template(characters.width()))
+            .expect("Template must have a valid width");
+        let padding = characters.pad_to_width(width.bit_count());
 
         let name_offsets: VecDeque<(u8, Option<Name>)> = characters.iter()
             .rev()
@@ -43,8 +62,6 @@ impl Template {
             .map(|(offset, character)| (u8::try_from(offset).unwrap(), character.to_name()))
             .collect();
 
-        let width = Type::for_template(characters.width())
-            .expect("Template must have a valid width");
         let mut locations_by_name: Vec<(Name, Vec<Location>)> = Vec::new();
         for name in characters.to_names() {
             let mut name_offsets = name_offsets.clone();
@@ -63,7 +80,8 @@ impl Template {
             locations_by_name.push((name, locations));
         }
 
-        Template { width, precision, characters, locations_by_name }
+        reject_overlapping_masks(characters.literal_mask(), &locations_by_name);
+        Template { width, precision, characters, padding, locations_by_name, span: expr.span() }
     }
 
     // Extract the bit fields, as specified by the template, from the input expression.
@@ -74,20 +92,76 @@ impl Template {
             .collect()
     }
 
-    // Capture variables from outside the the macro, substituting them into the template.
-    pub fn combine_with_context(&self, on_overflow: OnOverflow) -> TokenStream {
-        let mut field_streams = Vec::new();
-        for (name, locations) in &self.locations_by_name {
-            let mut streams = self.create_field_streams(
-                *name, &name.to_ident(), locations, on_overflow);
-            field_streams.append(&mut streams);
-        }
+    // The number of bits in the specified field, if the template has it.
+    pub fn field_width(&self, name: Name) -> Option<u8> {
+        self.locations_by_name.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, locations)| locations.iter().map(|location| location.width()).sum())
+    }
 
-        self.combine_with_literal(&field_streams)
+    // The positions of the bits of a field within the template, if the template has the field.
+    pub fn field_mask(&self, name: Name) -> Option<u128> {
+        self.locations_by_name.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, locations)| locations.iter().fold(0, |mask, location| mask | location.to_mask()))
+    }
+
+    // The names shared with another template whose fields have different widths in each, with both widths.
+    pub fn width_mismatches(&self, other: &Template) -> Vec<(Name, u8, u8)> {
+        self.locations_by_name.iter()
+            .filter_map(|(name, _)| {
+                let width = self.field_width(*name)?;
+                let other_width = other.field_width(*name)?;
+                (width != other_width).then_some((*name, width, other_width))
+            })
+            .collect()
     }
 
-    // Substitute macro arguments into the template.
-    pub fn combine_with_args(&self, on_overflow: OnOverflow, exprs: &[Expr]) -> TokenStream {
+    // Where the template was written, for pointing compile errors at it.
+    pub const fn span(&self) -> Span {
+        self.span
+    }
+
+    // The names of the fields that are split into more than one segment, in template order.
+    pub fn noncontiguous_names(&self) -> Vec<Name> {
+        self.locations_by_name.iter()
+            .filter(|(_, locations)| locations.len() > 1)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    // Whether the template has a field with the specified name.
+    pub fn has_name(&self, name: Name) -> bool {
+        self.locations_by_name.iter().any(|(n, _)| *n == name)
+    }
+
+    /* Capture variables from outside the the macro as the inputs for the fields of the template,
+     * except for fields that have constant values.
+     * The captured variables are spanned to the template so that a missing variable is reported
+     * as an error in the template, rather than somewhere in the macro expansion. A dedicated error
+     * isn't possible here since a proc macro can't tell which variables are in scope.
+     */
+    pub fn context_inputs(&self, constants: &[(Name, TokenStream)]) -> Vec<(Name, TokenStream)> {
+        self.locations_by_name.iter()
+            .map(|(name, _)| {
+                let input = Self::constant(constants, *name).unwrap_or_else(|| {
+                    let ident = Ident::new(&name.to_string(), self.span);
+                    quote! { #ident }
+                });
+                (*name, input)
+            })
+            .collect()
+    }
+
+    /* Use macro arguments as the inputs for the fields of the template, in template order,
+     * skipping fields that have constant values.
+     */
+    pub fn arg_inputs(
+        &self,
+        exprs: &[Expr],
+        constants: &[(Name, TokenStream)],
+        on_overflow: OnOverflow,
+    ) -> Result<Vec<(Name, TokenStream)>, syn::Error> {
         for expr in exprs {
             if let Expr::Lit(template) = expr.clone() {
                 if let Lit::Str(template) = template.lit {
@@ -97,27 +171,101 @@ impl Template {
             };
         }
 
-        assert_eq!(exprs.len(), self.locations_by_name.len(),
-            "The number of inputs must be equal to the number of names in the template.",
-        );
+        let expected = self.locations_by_name.len() - constants.len();
+        if exprs.len() != expected {
+            // Point at the first unexpected input, or at the template if inputs are missing.
+            let span = exprs.get(expected).map_or(self.span, Spanned::span);
+            return Err(syn::Error::new(span, format!(
+                "The number of inputs ({}) must be equal to the number of names in the template \
+                (excluding names with constant values), which is {expected}.", exprs.len())));
+        }
+
+        let mut exprs = exprs.iter();
+        let inputs = self.locations_by_name.iter()
+            .map(|(name, _)| {
+                let input = Self::constant(constants, *name).unwrap_or_else(|| {
+                    let expr = exprs.next().unwrap();
+                    self.reject_oversized_literal(*name, expr, on_overflow);
+                    quote! { #expr }
+                });
+                (*name, input)
+            })
+            .collect();
+        Ok(inputs)
+    }
+
+    /* Fail if an integer literal input is too big for its slot when overflowing would panic (or
+     * return an error). A literal is always too big or never too big, so this is rejected at
+     * compile time instead. Other overflow settings handle oversized literals like any other input.
+     */
+    pub fn reject_oversized_literal(&self, name: Name, expr: &Expr, on_overflow: OnOverflow) {
+        if !matches!(on_overflow, OnOverflow::Panic | OnOverflow::Error | OnOverflow::DebugChecked) {
+            return;
+        }
+
+        let Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) = expr else {
+            return;
+        };
+
+        let width = self.field_width(name).expect("Every input should have a field");
+        let max = u128::MAX >> (128 - u32::from(width));
+        let value: u128 = int.base10_parse()
+            .unwrap_or_else(|_| panic!("Literal argument for field '{}' must be an unsigned integer.", name));
+        assert!(value <= max,
+            "Literal value {int} is too big for the {width}-bit slot of field '{}' in the template.",
+            name);
+    }
+
+    // The constant value that was specified for a field instead of an input, if any.
+    fn constant(constants: &[(Name, TokenStream)], name: Name) -> Option<TokenStream> {
+        constants.iter()
+            .find(|(constant_name, _)| *constant_name == name)
+            .map(|(_, value)| value.clone())
+    }
 
+    // Substitute field inputs (from arguments or captured from context) into the template.
+    // The bits of the base value (if any) are used wherever the template has placeholders.
+    pub fn combine(
+        &self,
+        on_overflow: OnOverflow,
+        operator: Operator,
+        base: Option<&Expr>,
+        inputs: &[(Name, TokenStream)],
+    ) -> TokenStream {
         let mut field_streams = Vec::new();
-        for ((name, locations), expr) in self.locations_by_name.iter().zip(exprs.iter()) {
-            let mut streams = self.create_field_streams(
-                *name, &expr.clone(), locations, on_overflow);
+        for ((name, locations), (_, input)) in self.locations_by_name.iter().zip(inputs) {
+            let mut streams = self.create_field_streams(*name, input, locations, on_overflow);
             field_streams.append(&mut streams);
         }
 
-        self.combine_with_literal(&field_streams)
+        if let Some(base) = base {
+            let width = self.width.to_token_stream();
+            let placeholder_mask = self.placeholder_mask();
+            field_streams.push(quote! { ((#base as #width) & (#placeholder_mask as #width)) });
+        }
+
+        self.combine_with_literal(&field_streams, operator)
     }
 
-    // Replace bits in target with bits captured from variables outside the macro.
-    pub fn replace(&self, on_overflow: OnOverflow, target: &Expr) -> TokenStream {
+    /* Replace bits in target with bits captured from variables outside the macro.
+     * The target_width is the type of the target, if it is wider than the template. Only the low
+     * bits of the target are replaced, and its upper bits are preserved. Fields that are listed in
+     * field_overflows use their own overflow behavior rather than on_overflow.
+     */
+    pub fn replace(
+        &self,
+        on_overflow: OnOverflow,
+        field_overflows: &BTreeMap<Name, OnOverflow>,
+        target: &Expr,
+        target_width: Option<Type>,
+        clear_unused: bool,
+    ) -> TokenStream {
         let t = self.width.to_token_stream();
         // The mask allows us to clear to relevant bits in the target before applying replacements.
         let mut replacement_mask = 0u128;
         let mut replacements = Vec::new();
         for (name, locations) in &self.locations_by_name {
+            let on_overflow = field_overflows.get(name).copied().unwrap_or(on_overflow);
             let mut segment_offset = 0;
             for i in 0..locations.len() {
                 let location = locations[i];
@@ -161,8 +309,30 @@ impl Template {
             literal_quote = quote! { | (#literal as #t) };
         }
 
-        let replacement_mask = !replacement_mask;
-        quote! { (#target & #replacement_mask as #t) | (#(#replacements)|*) #literal_quote }
+        // When clearing unused bits, only bits covered by a field or a '1' literal can survive.
+        let coverage_mask = self.value_mask();
+        let Some(target_width) = target_width.filter(|&target_width| target_width != self.width) else {
+            let inverted_mask = !replacement_mask;
+            let replaced = quote! { (#target & #inverted_mask as #t) | (#(#replacements)|*) #literal_quote };
+            return if clear_unused {
+                quote! { ((#replaced) & (#coverage_mask as #t)) }
+            } else {
+                replaced
+            };
+        };
+
+        assert!(target_width > self.width,
+            "The target width ({target_width}) must not be narrower than the template width ({}).", self.width);
+        let target_width = target_width.to_token_stream();
+        let replaced = quote! {
+            (#target & !(#replacement_mask as #t as #target_width))
+                | #target_width::from((#(#replacements)|*) #literal_quote)
+        };
+        if clear_unused {
+            quote! { ((#replaced) & (#coverage_mask as #target_width)) }
+        } else {
+            replaced
+        }
     }
 
     // Substitute Fields into template (not macro arguments nor captured from context).
@@ -172,24 +342,41 @@ impl Template {
             .collect();
         let mut field_streams = Vec::new();
         for (name, locations) in &self.locations_by_name {
-            let field = fields[name].clone();
+            let field = fields.get(name).unwrap_or_else(|| panic!(
+                "Field '{name}' is in the output template, but isn't in any of the input templates."));
+            let field = field.clone();
             let n = name.to_ident();
-            assert_eq!(field.width(), locations.iter().map(|l| l.width).sum(),
+            /* This check covers every width combination, including multi-segment fields and
+             * output templates narrower than the input templates: each segment is placed by its own
+             * location, so only the total width of the field has to match.
+             */
+            let output_width = self.field_width(*name).expect("Output template should contain its own field");
+            assert_eq!(field.width(), output_width,
                 "The width of field '{n}' must match between the input templates and the output template.");
             let field = field.widen(self.width);
-            // Input and output fields having unequal lengths should fail at compile time,
+            // Input and output fields having unequal lengths fail at compile time above,
             // so go with OnOverflow::Corrupt since it is the most efficient option.
             let mut streams = self.create_field_streams(
                 *name, &field.to_token_stream(), locations, OnOverflow::Corrupt);
             field_streams.append(&mut streams);
         }
 
-        self.combine_with_literal(&field_streams)
+        self.combine_with_literal(&field_streams, Operator::Or)
     }
 
-    // Convert a template expression into a String. Useful for error messages.
+    /* Convert a template expression into a String. Useful for error messages.
+     * The template can also be written as an array of field widths and string segments, such as
+     * `[r = 5, g = 6, b = 5]` or `["1...", a = 4]`, which is expanded into a template string.
+     */
     pub fn template_string(template: &Expr) -> String {
         let template_text = quote! { #template };
+        if let Expr::Array(array) = template {
+            return array.elems.iter()
+                .map(Self::template_group)
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
         let Expr::Lit(template) = template.clone() else {
             panic!("The template expression must come after the input value(s), \
                 and must be a literal, but found:\n{template_text}");
@@ -201,17 +388,226 @@ impl Template {
         template.value()
     }
 
-    // True if any placeholders (periods) are present. Used in APIs that don't accept placeholders.
+    /* Read the template from a file if the expression is `template_file = "path"`, with the path
+     * relative to the root of the crate that invoked the macro. Line breaks and indentation in the
+     * file are treated as single spaces. Returns None if the expression isn't a template file.
+     * The file is recorded so that the expansion can include it (see take_files()).
+     */
+    pub fn from_file(expr: &Expr) -> Option<Expr> {
+        let Expr::Assign(ExprAssign { left, right, .. }) = expr else {
+            return None;
+        };
+        let Expr::Path(name) = &**left else {
+            return None;
+        };
+        if !name.path.is_ident("template_file") {
+            return None;
+        }
+
+        let Expr::Lit(ExprLit { lit: Lit::Str(path), .. }) = &**right else {
+            panic!("Setting 'template_file' must be set to a string literal path, e.g. 'template_file = \"regs/status.tmpl\"'.");
+        };
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let full_path = std::path::Path::new(&root).join(path.value());
+        let contents = std::fs::read_to_string(&full_path).unwrap_or_else(|err| panic!(
+            "Template file '{}' couldn't be read (looked for it at '{}'): {err}.", path.value(), full_path.display()));
+        // The expansion includes the file by its absolute path, since include_bytes! is relative to
+        // the invoking source file rather than to the crate root.
+        let full_path = std::fs::canonicalize(&full_path).unwrap_or(full_path);
+        TEMPLATE_FILES.with_borrow_mut(|files| files.push(full_path.display().to_string()));
+        let template = contents.split_whitespace().collect::<Vec<_>>().join(" ");
+        Some(Expr::Lit(ExprLit { lit: Lit::Str(LitStr::new(&template, path.span())), attrs: Vec::new() }))
+    }
+
+    /* Take the paths of the template files that have been read since the last call, so that the
+     * expansion that read them can include them. Files read by an expansion that failed are only
+     * left over until the next call, which is harmless since the whole compilation has failed.
+     */
+    pub fn take_files() -> Vec<String> {
+        TEMPLATE_FILES.take()
+    }
+
+    // Replace a custom placeholder char in a template expression with the standard '.'.
+    pub fn with_placeholder(template: &Expr, placeholder: Option<char>) -> Expr {
+        let Some(placeholder) = placeholder else {
+            return template.clone();
+        };
+
+        match template {
+            Expr::Lit(ExprLit { lit: Lit::Str(text), attrs }) => {
+                let text = LitStr::new(&text.value().replace(placeholder, "."), text.span());
+                Expr::Lit(ExprLit { lit: Lit::Str(text), attrs: attrs.clone() })
+            }
+            Expr::Array(array) => {
+                let mut array = array.clone();
+                for elem in &mut array.elems {
+                    *elem = Self::with_placeholder(elem, Some(placeholder));
+                }
+
+                Expr::Array(array)
+            }
+            _ => template.clone(),
+        }
+    }
+
+    // Expand one element of an array template, either `name = width` or a string segment.
+    fn template_group(group: &Expr) -> String {
+        let group_text = quote! { #group };
+        match group {
+            Expr::Assign(ExprAssign { left, right, .. }) => {
+                let name = match &**left {
+                    Expr::Path(path) => path.path.get_ident().map(ToString::to_string),
+                    _ => None,
+                };
+                let name = name.and_then(|name| Name::bracketed(&name).ok()).unwrap_or_else(|| panic!(
+                    "Array template field names must be valid field names, but found: {group_text}"));
+                let Expr::Lit(ExprLit { lit: Lit::Int(width), .. }) = &**right else {
+                    panic!("Array template field widths must be integer literals, but found: {group_text}");
+                };
+                let width: usize = width.base10_parse()
+                    .unwrap_or_else(|_| panic!("Array template field width is invalid: {group_text}"));
+                if name.is_single_char() {
+                    name.to_string().repeat(width)
+                } else {
+                    format!("<{name}>").repeat(width)
+                }
+            }
+            Expr::Lit(ExprLit { lit: Lit::Str(segment), .. }) => segment.value(),
+            _ => panic!("Array template elements must be either 'name = width' or a string literal, \
+                but found: {group_text}"),
+        }
+    }
+
+    // How many bits of input the template will match against.
+    pub const fn width(&self) -> Type {
+        self.width
+    }
+
+    // How many bits were written in the template, not counting the padding up to a standard width.
+    pub const fn logical_width(&self) -> u8 {
+        self.width.bit_count() - self.padding
+    }
+
+    /* True if any placeholders (periods) are present. Used in APIs that don't accept placeholders.
+     * The padding of a non-standard width template isn't counted, since it wasn't written in the template.
+     */
     pub fn has_placeholders(&self) -> bool {
-        self.characters.has_placeholders()
+        self.characters.iter()
+            .skip(usize::from(self.padding))
+            .any(|&character| character == Character::Placeholder)
+    }
+
+    // Generate a check that the literal bits of the input value match the literals of the template.
+    pub fn matches_literal(&self, value: &Expr) -> TokenStream {
+        let t = self.width.to_token_stream();
+        let literal_mask = self.characters.literal_mask();
+        let literal = self.characters.extract_literal().unwrap_or(0);
+        quote! { (#value as #t) & (#literal_mask as #t) == (#literal as #t) }
+    }
+
+    // Return '1's where the template has a literal (either '0' or '1'), '0's everywhere else.
+    pub fn literal_mask(&self) -> u128 {
+        self.characters.literal_mask()
+    }
+
+    /* A statement that checks that the literal bits of the input match the literals of the
+     * template, if the template has any. An integer literal input is checked during macro expansion
+     * instead. If the check is fallible, a mismatch returns an Err rather than panicking.
+     */
+    pub fn check_literals(&self, value: &Expr, fallible: bool) -> TokenStream {
+        let literal_mask = self.characters.literal_mask();
+        if literal_mask == 0 {
+            return quote! {};
+        }
+
+        let literal = self.literal_value();
+        if let Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) = value {
+            let n: u128 = int.base10_parse()
+                .unwrap_or_else(|_| panic!("Input '{int}' must be a non-negative integer that fits in a u128."));
+            assert!(n & literal_mask == literal,
+                "Input 0b{n:b} doesn't match the literals of template '{}'. \
+                Expected 0b{literal:b} under mask 0b{literal_mask:b}, but found 0b{:b}.",
+                self.characters, n & literal_mask);
+            return quote! {};
+        }
+
+        let t = self.width.to_token_stream();
+        let message = format!(
+            "Input 0b{{:b}} doesn't match the literals of template '{}'. \
+            Expected 0b{literal:b} under mask 0b{literal_mask:b}, but found 0b{{:b}}.",
+            self.characters);
+        let mismatch = crate::runtime_failure(fallible, &quote! { #message, input, literal_bits });
+        quote! {
+            {
+                let input = #value as #t;
+                let literal_bits = input & (#literal_mask as #t);
+                if literal_bits != (#literal as #t) {
+                    #mismatch;
+                }
+            }
+        }
+    }
+
+    // Return '1's where the template has a '1' literal, '0's everywhere else.
+    pub fn literal_value(&self) -> u128 {
+        self.characters.extract_literal().unwrap_or(0)
+    }
+
+    // Return '1's where the template has a field or a '1' literal, '0's everywhere else.
+    pub fn value_mask(&self) -> u128 {
+        let field_mask = self.locations_by_name.iter()
+            .flat_map(|(_, locations)| locations)
+            .fold(0, |mask, location| mask | location.to_mask());
+        field_mask | self.characters.extract_literal().unwrap_or(0)
+    }
+
+    /* How many bits of the template are fields, placeholders, and literals, in that order. Every
+     * bit is exactly one of these, so the counts always sum to the width of the template.
+     */
+    pub fn bit_counts(&self) -> (u32, u32, u32) {
+        let field_bits = self.locations_by_name.iter()
+            .flat_map(|(_, locations)| locations)
+            .map(|location| u32::from(location.width()))
+            .sum();
+        let placeholder_bits = self.placeholder_mask().count_ones();
+        let literal_bits = self.literal_mask().count_ones();
+        assert_eq!(field_bits + placeholder_bits + literal_bits, u32::from(self.width.bit_count()),
+            "Every bit of a template should be a field, a placeholder, or a literal.");
+        (field_bits, placeholder_bits, literal_bits)
+    }
+
+    // Return '1's where the template has a placeholder, '0's everywhere else.
+    pub fn placeholder_mask(&self) -> u128 {
+        self.characters.placeholder_mask()
     }
 
     // Convert the template into a uniquely-identifying struct name.
     pub fn to_struct_name(&self) -> Ident {
+        self.to_type_name("Fields")
+    }
+
+    // The name of the builder struct generated for combining into this Template.
+    pub fn to_builder_name(&self) -> Ident {
+        self.to_type_name("Builder")
+    }
+
+    // The smallest standard type that can hold each field of the Template, in template order.
+    pub fn field_types(&self) -> Vec<(Name, Type)> {
+        self.locations_by_name.iter()
+            .map(|(name, locations)| {
+                let width = locations.iter().map(|location| location.width()).sum();
+                let t = Type::for_field(width, Precision::Standard)
+                    .expect("Field should be shorter than 256 characters");
+                (*name, t)
+            })
+            .collect()
+    }
+
+    fn to_type_name(&self, prefix: &str) -> Ident {
         let struct_name_suffix: String = self.characters.to_string()
-            // Underscores work in struct names, periods do not.
-            .replace('.', "_");
-        format_ident!("{}", format!("Fields·{}", struct_name_suffix))
+            // Underscores work in struct names, periods and angle brackets do not.
+            .replace(['.', '<', '>'], "_");
+        format_ident!("{}", format!("{prefix}·{}", struct_name_suffix))
     }
 
     fn create_field_streams(
@@ -254,24 +650,78 @@ impl Template {
         field_streams
     }
 
-    fn combine_with_literal(&self, field_streams: &[TokenStream]) -> TokenStream {
+    fn combine_with_literal(&self, field_streams: &[TokenStream], operator: Operator) -> TokenStream {
+        let mut streams = field_streams.to_vec();
         if let Some(literal) = self.characters.extract_literal() {
             let width = self.width.to_token_stream();
-            quote! { (#(#field_streams)|*) | (#literal as #width) }
-        } else {
-            quote! { #(#field_streams)|* }
+            streams.push(quote! { (#literal as #width) });
+        }
+
+        match operator {
+            Operator::Or => quote! { #(#streams)|* },
+            Operator::Xor => quote! { #(#streams)^* },
         }
     }
 }
 
+/* Fail if any bit is claimed by more than one field, or by both a field and a literal, since the
+ * result of combining would be ambiguous. Each template character currently claims a single bit, so
+ * this guards against internal inconsistencies rather than user error.
+ */
+fn reject_overlapping_masks(literal_mask: u128, locations_by_name: &[(Name, Vec<Location>)]) {
+    let mut claimed = literal_mask;
+    for (name, locations) in locations_by_name {
+        for location in locations {
+            let overlap = claimed & location.to_mask();
+            let positions: Vec<u32> = (0..128).filter(|i| overlap & (1 << i) != 0).collect();
+            assert!(positions.is_empty(),
+                "Field '{}' overlaps with another field or literal of the template at bit positions {positions:?}.",
+                name);
+            claimed |= location.to_mask();
+        }
+    }
+}
+
+/* Fail if any group of digits (separated by spaces or byte separators) in a template string isn't
+ * exactly the size required by the 'group' setting. In hexadecimal templates, a byte is two digits.
+ */
+pub fn reject_misaligned_groups(text: &str, base: Base, group: Group) {
+    // Repeated groups can merge digits across a space, so they are checked after expansion.
+    let expanded = Characters::expand_groups(text);
+    let template = if expanded == text { format!("'{text}'") } else { format!("'{text}' (expanded to '{expanded}')") };
+    for digits in expanded.split([' ', '|']).filter(|digits| !digits.is_empty()) {
+        let bit_count = Characters::from_str(digits, base).width();
+        assert!(bit_count == group.bit_count(),
+            "Group '{digits}' of template {template} has {bit_count} bits, but 'group={}' requires every \
+            group of digits to have {} bits.", format!("{group:?}").to_lowercase(), group.bit_count());
+    }
+}
+
+/* Remove the parts of a template string that aren't digits: multi-char field names (and their
+ * angle brackets) and group repeat counts (and their braces).
+ */
+pub fn strip_non_digits(text: &str) -> String {
+    strip_between(&strip_between(text, '<', '>'), '{', '}')
+}
+
+// Remove each span that starts with the open char and ends with the close char.
+fn strip_between(text: &str, open: char, close: char) -> String {
+    text.split(open)
+        .enumerate()
+        .map(|(i, part)| if i == 0 { part } else { part.split_once(close).map_or("", |(_, rest)| rest) })
+        .collect()
+}
+
 // TODO: Reject base 64 special characters.
 fn reject_higher_base_chars(text: &str, base: Base) {
     let banned_chars: BTreeSet<char> = match base {
         Base::Binary => ('2'..='9').chain('A'..='Z').collect(),
+        Base::Octal => ('8'..='9').chain('A'..='Z').collect(),
         Base::Hexadecimal => ('G'..='Z').collect(),
     };
 
-    let chars: BTreeSet<char> = text.chars().collect();
+    // Multi-char field names and repeat counts aren't digits, so they aren't checked.
+    let chars: BTreeSet<char> = strip_non_digits(text).chars().collect();
     let rejections: Vec<char> = chars.intersection(&banned_chars).copied().collect();
     assert!(rejections.is_empty(),
         "Invalid characters for base {} detected: {rejections:?}. Did you mean to use a higher base?",
@@ -22,6 +22,17 @@ impl Type {
         }
     }
 
+    /* Create a Type for a Template with a non-standard width: the smallest standard type that can
+     * hold it. The Template is padded on the left with placeholders up to the width of the Type.
+     */
+    pub fn for_nonstandard_template(bit_count: u8) -> Result<Self, String> {
+        match bit_count {
+            0 => Err("Templates cannot be empty.".into()),
+            1..=128 => Self::for_template(bit_count.next_power_of_two().max(8)),
+            _ => Err(format!("Template width must be at most 128 bits, but was {bit_count}.")),
+        }
+    }
+
     /* Determine what Type is needed for a Field that has the specified width and Precision.
      * Ux Precision means the Type will exactly match the width.
      * Standard Precision will result in the Type being the smallest built-in integer type that is
@@ -42,18 +53,19 @@ impl Type {
     }
 
     // Attempt to convert a lowercase str into a Type.
-    pub fn parse(mut text: String) -> Result<Self, String> {
+    pub fn parse(text: String) -> Result<Self, String> {
         if &text == "bool" {
             return Ok(Type::Bool);
         }
 
-        let u = text.remove(0);
-        if u != 'u' {
-            return Err("Type must be bool or start with 'u'.".into());
-        }
-
-        let count: u8 = text.parse()
-            .map_err(|err: std::num::ParseIntError| err.to_string())?;
+        // Macros can't see type aliases, so only the primitive types themselves can be recognized.
+        let not_primitive = || format!("'{text}' is not a bool or an unsigned integer type (such as u16). \
+            Type aliases can't be resolved by macros, so use the underlying type instead. To produce \
+            fields of a custom type, use splitbits_named_into! instead.");
+        let Some(count) = text.strip_prefix('u') else {
+            return Err(not_primitive());
+        };
+        let count: u8 = count.parse().map_err(|_| not_primitive())?;
         Ok(Type::Num(BitCount::new(count)?))
     }
 
@@ -70,11 +82,16 @@ impl Type {
         Self::for_field(self.bit_count() + other.bit_count(), Precision::Standard)
     }
 
-    // Convert the Type to how it will appear in the macro expansion (e.g. bool, u7, u32).
+    /* Convert the Type to how it will appear in the macro expansion (e.g. bool, u7, u32).
+     * Non-standard types come from the ux crate, or from the arbitrary-int crate if the
+     * arbitrary-int feature is enabled.
+     */
     pub fn to_token_stream(self) -> TokenStream {
         let ident = format_ident!("{}", self.to_string());
         if self.is_standard() {
             quote! { #ident }
+        } else if cfg!(feature = "arbitrary-int") {
+            quote! { arbitrary_int::#ident }
         } else {
             quote! { ux::#ident }
         }
@@ -98,6 +115,32 @@ impl fmt::Display for Type {
     }
 }
 
+// A floating point type, for Fields that are converted to a ratio of their maximum value.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Float {
+    F32,
+    F64,
+}
+
+impl Float {
+    // Attempt to convert a lowercase str into a Float.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match text {
+            "f32" => Ok(Self::F32),
+            "f64" => Ok(Self::F64),
+            _ => Err(format!("'{text}' is not a valid float type. Options: 'f32', 'f64'.")),
+        }
+    }
+
+    // Convert the Float to how it will appear in the macro expansion.
+    pub fn to_token_stream(self) -> TokenStream {
+        match self {
+            Self::F32 => quote! { f32 },
+            Self::F64 => quote! { f64 },
+        }
+    }
+}
+
 // Whether only bool, u8, u16, u32, u64, and u128 should be used, or if any ux types up to u127 are
 // allowable too.
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(bools_only=true, 0b11011101, "abbc....");
+}
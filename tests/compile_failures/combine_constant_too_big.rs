@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = combinebits!(overflow=panic, constant(a = 30), 0b1, "aaaa 000b");
+}
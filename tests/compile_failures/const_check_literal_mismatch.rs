@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = combinebits!(const_check(a = 3), 2, 0b1, "aaaa 000b");
+}
@@ -0,0 +1,6 @@
+use splitbits::*;
+
+fn main() {
+    let a: u8 = 0o12;
+    combineoct!("aa89");
+}
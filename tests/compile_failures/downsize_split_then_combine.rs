@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits_then_combine!(0b1001_1010_0000_0000u16, "aaa. .... a... ....", "000a aa00");
+}
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(min=u8, min=u16, 0b11011101, "aaabbccc");
+}
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits!(0b1101_1001u8, "");
+}
@@ -0,0 +1,13 @@
+use splitbits::*;
+
+enum Packet {
+    Data { t: u8, a: u8 },
+    Ack { t: u8, b: u8 },
+}
+
+fn main() {
+    let _ = splitbits_enum!(Packet, 0u8, t, {
+        0 => "ttaa aaaa" as Data,
+        1 => "bbbb bbtt" as Ack,
+    });
+}
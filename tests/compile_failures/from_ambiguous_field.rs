@@ -0,0 +1,9 @@
+use splitbits::*;
+
+fn main() {
+    struct Header { h: u8 }
+    struct Payload { h: u8, p: u8 }
+    let header = Header { h: 1 };
+    let payload = Payload { h: 2, p: 3 };
+    let _ = combinebits!(from(header = h, payload = (h, p)), "hhhh pppp");
+}
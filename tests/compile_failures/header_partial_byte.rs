@@ -0,0 +1,6 @@
+use splitbits::splitbits_header;
+
+fn main() {
+    let message: &[u8] = &[0x12, 0x34];
+    let _ = splitbits_header!(message, "aaaa bbbb cccc");
+}
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    assert_templates_compatible!("aaaa bbbb", "bbba aaaa");
+}
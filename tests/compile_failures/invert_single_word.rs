@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits!(invert=ac, 0b1011_0110, "aaab bbcd");
+}
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits!(lookup(d = [1u32, 2, 4]), 0b1011_0010u8, "dd.. ....");
+}
@@ -0,0 +1,7 @@
+use splitbits::*;
+
+type Word = u16;
+
+fn main() {
+    let _ = splitbits!(min=Word, 0b1101_1001, "aaaa bbbb");
+}
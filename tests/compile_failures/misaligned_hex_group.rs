@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splithex!(group=byte, 0x123456u32, "..aa abb");
+}
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits!(group=nibble, 0u8, "(aa bb){2}");
+}
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits!(0x1234u16, "aaaaaaa|abbbbbbbb");
+}
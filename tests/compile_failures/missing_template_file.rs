@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits!(0u8, template_file = "tests/templates/missing.tmpl");
+}
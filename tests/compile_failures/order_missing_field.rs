@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(order(b), 0b11011101, "aaaa bbbb");
+}
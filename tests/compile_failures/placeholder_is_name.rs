@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(placeholder='x', 0b11011101, "aaxx bbbb");
+}
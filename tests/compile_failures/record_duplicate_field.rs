@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits_record!((0u8, "aaaa bbbb"), (0u8, "cccc aaaa"));
+}
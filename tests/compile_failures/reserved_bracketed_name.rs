@@ -0,0 +1,7 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(rest=true, 0b11011101u8, "<rest><rest>aa ....");
+    splitbits!(keep_raw=true, 0b11011101u8, "<raw><raw>aa bbbb");
+    combinebits_builder!("<build><build>aa bbbb");
+}
@@ -0,0 +1,5 @@
+use splitbits::splitbits;
+
+fn main() {
+    let _ = splitbits!(rest=true, bytes(a), 0x1234u16, "aaaa aaaa aaaa ....");
+}
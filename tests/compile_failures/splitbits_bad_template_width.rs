@@ -1,6 +1,6 @@
-use splitbits::splitbits;
+use splitbits::splithex;
 
 fn main() {
-    let input = 5;
-    splitbits!(input, "aaaabbb");
+    let input = 5u128;
+    splithex!(input, "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab");
 }
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(0u128, "(aaaa bbbb){17}");
+}
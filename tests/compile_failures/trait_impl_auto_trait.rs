@@ -0,0 +1,7 @@
+use splitbits::*;
+
+trait Decoded {}
+
+fn main() {
+    let _ = splitbits!(trait_impl(Decoded, Send), 0b1011_0010u8, "kkkk llll");
+}
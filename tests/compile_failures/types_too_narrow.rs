@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits_named_into!(types(a = u8), 0u16, "aaaa aaaa aa.. ....");
+}
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = splitbits!(warn_noncontiguous=true, 0b1101_0010u8, "aabaa bbb");
+}
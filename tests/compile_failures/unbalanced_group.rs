@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(0b11011101u8, "(ab{4}");
+}
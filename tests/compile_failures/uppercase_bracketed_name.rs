@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    splitbits!(0b11011101, "<Mode><Mode>aa bbbb");
+}
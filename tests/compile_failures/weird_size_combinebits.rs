@@ -2,5 +2,5 @@ use splitbits::*;
 
 fn main() {
     let a: u8 = 0b0101_0101;
-    combinebits!("aaa aaaa");
+    combinehex!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
 }
@@ -0,0 +1,5 @@
+use splitbits::*;
+
+fn main() {
+    let _ = combinebits!("    ");
+}
@@ -0,0 +1,20 @@
+extern crate splitbits;
+
+use splitbits::{assert_templates_compatible, splitbits_then_combine};
+
+assert_templates_compatible!("aaaa bbbb", "bbbb aaaa");
+
+#[test]
+fn compatible() {
+    assert_templates_compatible!("aaab bbbb", "bbbb baaa");
+    assert_templates_compatible!("aabb bbaa", "aaaa bbbb");
+    assert_templates_compatible!("aaaa ....", "0000 aaaa");
+    assert_templates_compatible!("aaaa aaaa", "aaaa aaaa 0000 0000");
+}
+
+#[test]
+fn matches_transform() {
+    assert_templates_compatible!("aaab bbbb", "bbbb baaa");
+    let result = splitbits_then_combine!(0b1001_1010, "aaab bbbb", "bbbb baaa");
+    assert_eq!(result, 0b1101_0100u8);
+}
@@ -1,9 +1,10 @@
 extern crate splitbits;
 
-use splitbits::{combinebits, combinehex};
+use splitbits::{combinebits, combinehex, try_combinebits};
 use ux::{u1, u4, u7, u9, u12};
 
 #[test]
+#[cfg_attr(feature = "default-panic-overflow", should_panic(expected = "too big for its location"))]
 fn combine_overflow() {
     let a: u8 = 0b1010_0101;
     let result = combinebits!("0aaa aaaa");
@@ -39,6 +40,22 @@ fn combine_overflow_saturate() {
     assert_eq!(result, 0b0111_1111);
 }
 
+#[test]
+#[cfg_attr(debug_assertions, should_panic(
+    expected = "Variable a is too big for its location in the template. 0b10100101 > 0b1111111"))]
+fn combine_overflow_debug_checked() {
+    let a: u8 = 0b1010_0101;
+    let result = combinebits!(overflow=debug_checked, "0aaa aaaa");
+    assert_eq!(result, 0b0010_0101);
+}
+
+#[test]
+fn combine_overflow_debug_checked_fits() {
+    let a: u8 = 0b0010_0101;
+    let result = combinebits!(overflow=debug_checked, "0aaa aaaa");
+    assert_eq!(result, 0b0010_0101);
+}
+
 #[test]
 fn combine_trivial() {
     let a: u16 = 0b1010_0101_0000_1111;
@@ -120,6 +137,7 @@ fn combine_arguments_literal_arg() {
 }
 
 #[test]
+#[cfg_attr(feature = "default-panic-overflow", should_panic(expected = "too big for its location"))]
 fn combine_arguments_overflow() {
     let arg = 0b1010_0101;
     let result = combinebits!(arg, "0aaa aaaa");
@@ -154,3 +172,466 @@ fn combine_arguments_overflow_saturate() {
     let result = combinebits!(overflow=saturate, arg, "0aaa aaaa");
     assert_eq!(result, 0b0111_1111);
 }
+
+// A single field spanning all 128 bits must not overflow the mask computation.
+#[test]
+fn combine_u128_full_width() {
+    let a: u128 = 0xFEDC_BA98_7654_3210_0123_4567_89AB_CDEF;
+    let result = combinehex!("aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa");
+    assert_eq!(result, a);
+}
+
+#[test]
+fn combine_optional() {
+    let a: Option<u8> = Some(0b1010);
+    let b: Option<u8> = None;
+    let result = combinebits!(optional(a, b), "aaaa bbbb");
+    assert_eq!(result, 0b1010_0000);
+}
+
+#[test]
+fn combine_optional_default() {
+    let a: Option<u8> = None;
+    let b: Option<u8> = Some(0b0110);
+    let c: u8 = 0b1;
+    let result = combinebits!(optional(a = 0b1111, b = 0b1), "aaaa bbbc");
+    assert_eq!(result, 0b1111_1101);
+}
+
+#[test]
+fn combine_arguments_optional() {
+    let header: Option<u8> = Some(0b10_1010);
+    let payload: Option<u8> = None;
+    let result = combinebits!(optional(p = 3, h), overflow=panic, header, payload, "hhhh hhpp");
+    assert_eq!(result, 0b1010_1011);
+}
+
+#[test]
+fn combine_constant() {
+    let b: u8 = 0b1;
+    let result = combinebits!(constant(a = 5, c = 0xF), "aaab cccc");
+    assert_eq!(result, 0b1011_1111);
+}
+
+#[test]
+fn combine_arguments_constant() {
+    let result = combinebits!(constant(b = 0b11), 0b10u8, 0b0u8, "aabb 000c");
+    assert_eq!(result, 0b1011_0000);
+}
+
+#[test]
+fn combine_constant_overflow() {
+    let result = combinebits!(constant(a = 0x1F), overflow=saturate, 0u8, "aaab 0000");
+    assert_eq!(result, 0b1110_0000);
+}
+
+#[test]
+fn names() {
+    let version: u8 = 0b10;
+    let length: u8 = 0b1_0110;
+    let f = true;
+    let result = combinebits!(names(v = version, l = length), "fvvl llll");
+    assert_eq!(result,                                       0b1101_0110);
+}
+
+#[test]
+fn from_structs() {
+    struct Header { h: u8, k: bool }
+    struct Payload { p: u16 }
+    let header = Header { h: 0b101, k: true };
+    let payload = &Payload { p: 0xABC };
+    let result = combinebits!(constant(z = 0u8), from(header = (h, k), payload = p), "hhhk zzzz pppp pppp pppp 0000 0000 0000");
+    assert_eq!(result, 0xB0AB_C000);
+}
+
+#[test]
+fn from_struct_expressions() {
+    struct Header { mode: u8, k: bool }
+    struct Packet { header: Header }
+    struct Word { w: u8 }
+    let packet = Packet { header: Header { mode: 0b101, k: true } };
+    let word0 = Word { w: 0xAB };
+    let result = combinebits!(from(packet.header = (mode, k), word0 = w), "<mode><mode><mode>k wwww wwww");
+    assert_eq!(result, 0xBABu16);
+}
+
+#[test]
+fn map() {
+    let mut values = std::collections::BTreeMap::new();
+    values.insert('a', 0xAu128);
+    values.insert('b', 0xB);
+    values.insert('c', 0xCDE);
+    let result = combinebits!(map=values, "0000 0000 aaaa bbbb cccc cccc cccc 0000");
+    assert_eq!(result, 0xAB_CDE0);
+}
+
+#[test]
+fn map_overflow() {
+    let values = std::collections::HashMap::from([('a', 0x1_0000_00F0u64), ('b', 0x3)]);
+    assert_eq!(combinebits!(overflow=truncate, map=&values, "aaaa aaaa bbbb 0000"), 0xF030);
+    assert_eq!(combinebits!(overflow=saturate, map=&values, "aaaa aaaa bbbb 0000"), 0xFF30);
+}
+
+#[test]
+#[should_panic(expected = "Field 'b' is missing from the map.")]
+fn map_missing_key() {
+    let values = std::collections::BTreeMap::from([('a', 1u8)]);
+    let _ = combinebits!(map=values, "aaaa bbbb");
+}
+
+#[test]
+#[should_panic(expected = "Key 'c' of the map isn't a field of the template.")]
+fn map_extra_key() {
+    let values = std::collections::BTreeMap::from([('a', 1u8), ('b', 2), ('c', 3)]);
+    let _ = combinebits!(map=values, "aaaa bbbb");
+}
+
+#[test]
+#[should_panic(expected = "Field 'a' is too big for the template: 256")]
+fn map_overflow_panic() {
+    let values = std::collections::BTreeMap::from([('a', 256u16)]);
+    let _ = combinebits!(overflow=panic, map=values, "aaaa aaaa");
+}
+
+#[test]
+fn const_check() {
+    let variant: u8 = 0b10;
+    let length: u8 = 0b1_0110;
+    let result = combinebits!(const_check(v = 0b10), variant, length, "0vvl llll");
+    assert_eq!(result,                                               0b0101_0110);
+}
+
+#[test]
+#[should_panic(expected = "Field 'v' must equal 0b10, but was 3")]
+fn const_check_mismatch() {
+    let v: u8 = 0b11;
+    let l: u8 = 0b1_0110;
+    let _ = combinebits!(const_check(v = 0b10), "0vvl llll");
+}
+
+#[test]
+fn clamp() {
+    let a: i8 = -20;
+    let b: i16 = 100;
+    let result = combinebits!(overflow=clamp, "aaaa bbbb");
+    assert_eq!(result,                       0b1000_0111);
+}
+
+#[test]
+fn clamp_in_range() {
+    let a: i8 = -3;
+    let b: i8 = 2;
+    let c: u8 = 1;
+    let result = combinebits!(overflow=clamp, "aaaa bbbb cccc 0000");
+    assert_eq!(result,                       0b1101_0010_0001_0000);
+}
+
+#[test]
+fn clamp_split_field() {
+    let a: i32 = 1000;
+    let result = combinebits!(overflow=clamp, "aa00 aaaa");
+    assert_eq!(result,                       0b0100_1111);
+}
+
+// Unsigned inputs aren't reinterpreted as signed values, so they clamp to the max of their slots.
+#[test]
+fn clamp_unsigned() {
+    let a: u8 = 200;
+    let b: &i8 = &-100;
+    let result = combinebits!(overflow=clamp, "aaa0 bbbb");
+    assert_eq!(result,                       0b1110_1000);
+}
+
+#[test]
+fn clamp_bool() {
+    let a = true;
+    let b: i8 = 1;
+    let result = combinebits!(overflow=clamp, "abbb bbbb");
+    assert_eq!(result,                       0b1000_0001);
+}
+
+#[test]
+fn clamp_extreme_inputs() {
+    let a: u128 = u128::MAX;
+    assert_eq!(combinebits!(overflow=clamp, "aaaa aaaa"), 0xFFu8);
+    let a: i128 = i128::MIN;
+    assert_eq!(combinebits!(overflow=clamp, "aaaa aaaa"), 0x80u8);
+}
+
+#[test]
+fn combine_xor() {
+    let a: u8 = 0b1100;
+    let b: u8 = 0b0101;
+    let result = combinebits!(combine=xor, "aaaa bbbb");
+    assert_eq!(result, 0b1100_0101);
+}
+
+#[test]
+fn combine_xor_overlapping() {
+    let a: u16 = 0b1111_1111;
+    let b: u16 = 0b11;
+    let result = combinebits!(combine=xor, overflow=corrupt, "0000 0000 1100 aabb");
+    assert_eq!(result,                                     0b0000_0011_0011_1111);
+}
+
+#[test]
+fn combine_literal_arguments() {
+    let result = combinebits!(0b1111, 0x1, 3, "aaaa 0bcc");
+    assert_eq!(result, 0b1111_0111);
+}
+
+#[test]
+fn combine_oversized_literal_arguments() {
+    let result = combinebits!(overflow=truncate, 0b1_0101, 0b11, "aaaa 00bb");
+    assert_eq!(result, 0b0101_0011);
+    let result = combinebits!(combine=xor, overflow=corrupt, 0b1111_0000u8, "1010 101a");
+    assert_eq!(result, 0b0101_1010);
+    let result = combinebits!(overflow=saturate, constant(a = 30), 0b1, "aaaa 000b");
+    assert_eq!(result, 0b1111_0001);
+}
+
+#[test]
+fn combine_base() {
+    let a: u8 = 0b11;
+    let b: u8 = 0b0000;
+    let result = combinebits!(base=0b1010_1010_1010_1010, ".... aa.. bbbb 10..");
+    assert_eq!(result, 0b1010_1110_0000_1010);
+}
+
+#[test]
+fn combine_base_arguments() {
+    let base: u16 = 0xFFFF;
+    let result = combinebits!(base=base, 0x12u8, ".... .... aaaa aaaa");
+    assert_eq!(result, 0xFF12);
+}
+
+#[test]
+fn array_template() {
+    let r: u8 = 0b10000;
+    let g: u8 = 0b11_1111;
+    let b: u8 = 0b00001;
+    let result = combinebits!([r = 5, g = 6, b = 5]);
+    assert_eq!(result, 0b1000_0111_1110_0001u16);
+}
+
+#[test]
+fn signed_result() {
+    let s = true;
+    let m: u16 = 0x7FFE;
+    let result = combinebits!(result=i16, "smmm mmmm mmmm mmmm");
+    assert_eq!(result, -2i16);
+}
+
+#[test]
+fn signed_result_positive() {
+    let result = combinebits!(result=i8, 0b101u8, 0b0u8, "0aaa 000b");
+    assert_eq!(result, 0b0101_0000i8);
+}
+
+#[test]
+fn unsigned_result() {
+    let result = combinehex!(result=u16, 0xABu8, "FFaa");
+    assert_eq!(result, 0xFFABu16);
+}
+
+#[test]
+fn signed_result_overflow_panic() {
+    let a: u8 = 0xFF;
+    let result = combinebits!(result=i8, overflow=panic, "aaaa aaaa");
+    assert_eq!(result, -1i8);
+}
+
+#[test]
+fn multi_char_names() {
+    let mode: u8 = 0b10;
+    let a: u8 = 0b01;
+    let flag = true;
+    let result = combinebits!("<mode><mode>aa <flag>000");
+    assert_eq!(result, 0b1001_1000);
+}
+
+#[test]
+fn placeholder() {
+    let a: u8 = 0b101;
+    let result = combinebits!(placeholder='-', base=0xFFu8, "10-- aaa0");
+    assert_eq!(result, 0b1011_1010);
+}
+
+#[test]
+fn reference_args() {
+    let values: [u8; 2] = [0b1010, 0b0110];
+    let result = combinebits!(&values[0], &values[1], "aaaa bbbb");
+    assert_eq!(result,                               0b1010_0110);
+
+    let fields: Vec<&u8> = values.iter().collect();
+    let result = combinebits!(fields[1], fields[0], "aaaa bbbb");
+    assert_eq!(result,                             0b0110_1010);
+}
+
+#[test]
+fn reference_variables() {
+    let values: [u8; 2] = [0b1010, 0b0110];
+    let a = &values[0];
+    let b = &values[1];
+    let result = combinebits!("aaaa bbbb");
+    assert_eq!(result,       0b1010_0110);
+}
+
+#[test]
+fn args_evaluated_once() {
+    let mut calls = 0;
+    let mut next = || { calls += 1; 0b11_0110u8 };
+    let result = combinebits!(next(), "aaa0 aaa0");
+    assert_eq!(result,               0b1100_1100);
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn args_named_after_other_fields() {
+    let a: u8 = 0b0001;
+    let b: u8 = 0b1000;
+    let result = combinebits!(b, a, "aaaa bbbb");
+    assert_eq!(result,             0b1000_0001);
+}
+
+#[test]
+fn fit_result() {
+    let a: u8 = 0b101;
+    let b = true;
+    let result: u8 = combinebits!(result=fit, "0000 0000 0000 0000 0000 0000 baaa 0000");
+    assert_eq!(result,                         0b1101_0000);
+
+    // A '1' literal takes up a bit, but a '0' literal doesn't.
+    let result: u16 = combinebits!(result=fit, "0000 0001 0000 0aaa");
+    assert_eq!(result,                         0b0000_0001_0000_0101);
+
+    // Bits from the base value count too.
+    let result: u16 = combinebits!(result=fit, base=0xFFFF_FFFFu32, "0000 0000 0000 0000 .... .... .... 0aaa");
+    assert_eq!(result,                                               0b1111_1111_1111_0101);
+
+    let result: u64 = combinebits!(result=fit, 0x1234u16, "0000 0000 0000 0000 0000 0000 0000 0000 \
+        0000 0000 0000 0000 0000 0000 0000 0000 aaaa aaaa aaaa aaaa 0000 0000 0000 0000 \
+        0000 0000 0000 0000 0000 0000 0000 0000");
+    assert_eq!(result, 0x1234_0000_0000_0000);
+}
+
+#[test]
+fn warn_noncontiguous_acknowledged() {
+    let a: u8 = 0b1110;
+    let b: u8 = 0b0100;
+    let result = combinebits!(warn_noncontiguous=true, noncontiguous(a), "aabb bbaa");
+    assert_eq!(result,                                                  0b1101_0010);
+}
+
+#[test]
+fn lookup() {
+    let d = 3200u32;
+    let f = 2.0f32;
+    let result = combinebits!(lookup(d = [100u32, 200, 400, 800, 1600, 3200, 6400, 12800], f = [0.5f32, 2.0]), "dddf 0000");
+    assert_eq!(result, 0b1011_0000);
+}
+
+// Reference inputs are dereferenced before they are looked up or checked.
+#[test]
+fn lookup_and_const_check_references() {
+    let (value, check) = (400u32, 2u8);
+    let (d, c) = (&value, &check);
+    let result = combinebits!(lookup(d = [100u32, 200, 400, 800]), const_check(c = 2), "ddcc 0000");
+    assert_eq!(result, 0b1010_0000);
+}
+
+#[test]
+#[should_panic(expected = "The value of field 'd' isn't in its lookup table: 300")]
+fn lookup_missing_value() {
+    let d = 300u32;
+    let _ = combinebits!(lookup(d = [100u32, 200, 400, 800]), "dd00 0000");
+}
+
+#[test]
+#[cfg_attr(feature = "default-panic-overflow", should_panic(expected = "too big for its location"))]
+fn default_overflow() {
+    let a: u8 = 0b1_0110;
+    let result = combinebits!("1aaa aaaa");
+    assert_eq!(result,        0b1001_0110);
+    let a: u8 = 0b1111_0110;
+    let result = combinebits!("1aaa aaaa");
+    assert_eq!(result,        0b1111_0110);
+}
+
+// Positions that aren't covered by a field or a '1' literal must always be zero, even when every
+// input has all of its bits set.
+#[test]
+fn uncovered_bits_are_zero() {
+    let a = u8::MAX;
+    let b = u16::MAX;
+    let result = combinebits!(overflow=truncate, "0aa0 0bb0 0000 b00b");
+    assert_eq!(result,                           0b0110_0110_0000_1001);
+}
+
+#[test]
+fn template_file() {
+    let e: u8 = 0x12;
+    let s: u8 = 0x34;
+    let result = combinebits!(template_file = "tests/templates/status.tmpl");
+    assert_eq!(result, 0x1234);
+}
+
+#[test]
+fn try_combinebits_lookup() {
+    let d: u32 = 30;
+    let result = try_combinebits!(lookup(d = [10u32, 20, 40, 80]), "0000 00dd");
+    assert_eq!(result, Err("The value of field 'd' isn't in its lookup table: 30".to_owned()));
+    let d: u32 = 40;
+    assert_eq!(try_combinebits!(lookup(d = [10u32, 20, 40, 80]), "0000 00dd"), Ok(0b10u8));
+}
+
+#[test]
+fn try_combinebits_const_check() {
+    let a: u8 = 2;
+    let b: u8 = 1;
+    let result = try_combinebits!(const_check(a = 3), "aabb");
+    assert_eq!(result, Err("Field 'a' must equal 3, but was 2.".to_owned()));
+}
+
+#[test]
+fn try_combinebits_map() {
+    let values = std::collections::BTreeMap::from([('a', 1u128)]);
+    let result = try_combinebits!(map = values, "aaaa bbbb");
+    assert_eq!(result, Err("Field 'b' is missing from the map.".to_owned()));
+
+    let values = std::collections::BTreeMap::from([('a', 1u128), ('b', 2), ('c', 3)]);
+    let result = try_combinebits!(map = values, "aaaa bbbb");
+    assert_eq!(result, Err("Key 'c' of the map isn't a field of the template.".to_owned()));
+}
+
+#[test]
+fn try_combinebits_overflow() {
+    let a: u8 = 0b0110_0001;
+    let result = try_combinebits!(overflow=panic, "0aaaaaa0");
+    assert_eq!(
+        result,
+        Err("Variable a is too big for its location in the template. 0b1100001 > 0b111111".to_owned()),
+    );
+
+    let a: u8 = 0b0010_0001;
+    assert_eq!(try_combinebits!(overflow=panic, "0aaaaaa0"), Ok(0b0100_0010));
+    // Truncation can't fail.
+    let a: u8 = 0b0110_0001;
+    assert_eq!(try_combinebits!(overflow=truncate, "0aaaaaa0"), Ok(0b0100_0010));
+}
+
+#[test]
+fn nonstandard_width() {
+    let r: u8 = 0x12;
+    let g: u8 = 0x34;
+    let b: u8 = 0x56;
+    let color: u32 = combinebits!("rrrrrrrr gggggggg bbbbbbbb");
+    assert_eq!(color, 0x0012_3456);
+}
+
+#[test]
+fn truncate_wraps() {
+    // Truncating to a slot is the same as wrapping modulo the slot size.
+    let a: u8 = 0b1001_0111;
+    assert_eq!(combinebits!(overflow=truncate, "00aaaaaa"), a % 0b100_0000);
+}
@@ -0,0 +1,30 @@
+extern crate splitbits;
+
+use splitbits::combinebits_at;
+
+#[test]
+fn combine_at() {
+    let a: u8 = 0b101;
+    let b: u16 = 0b1_1001;
+    let result = combinebits_at!(width = u16, (a, at = 12, bits = 3), (b, at = 0, bits = 5));
+    assert_eq!(result, 0b0101_0000_0001_1001);
+}
+
+#[test]
+fn combine_at_full_width() {
+    let result = combinebits_at!(width = u128, (u128::MAX, at = 0, bits = 128));
+    assert_eq!(result, u128::MAX);
+}
+
+#[test]
+fn combine_at_truncate() {
+    let result = combinebits_at!(width = u8, overflow = truncate, (0xFFu8, at = 4, bits = 2));
+    assert_eq!(result, 0b0011_0000);
+}
+
+#[test]
+#[should_panic]
+fn combine_at_panic() {
+    let a: u8 = 0b100;
+    let _ = combinebits_at!(width = u8, overflow = panic, (a, at = 0, bits = 2));
+}
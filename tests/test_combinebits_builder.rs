@@ -0,0 +1,38 @@
+extern crate splitbits;
+
+use splitbits::combinebits_builder;
+
+#[test]
+fn builder() {
+    let result = combinebits_builder!("aaaa abbc")
+        .a(0b10110)
+        .b(0b01)
+        .c(true)
+        .build();
+    assert_eq!(result, 0b1011_0011);
+}
+
+#[test]
+fn builder_unset_fields_are_zero() {
+    let result = combinebits_builder!("aaaa bbbb cccc cccc").b(0xF).build();
+    assert_eq!(result, 0b0000_1111_0000_0000);
+}
+
+#[test]
+fn builder_reused() {
+    let builder = combinebits_builder!("1aaa bbbb").a(0b010);
+    assert_eq!(builder.b(0b0001).build(), 0b1010_0001);
+    assert_eq!(builder.b(0b1000).build(), 0b1010_1000);
+}
+
+#[test]
+fn builder_split_field() {
+    let result = combinebits_builder!("aabb bbaa").a(0b1101).b(0).build();
+    assert_eq!(result, 0b1100_0001);
+}
+
+#[test]
+#[should_panic]
+fn builder_overflow_panic() {
+    let _ = combinebits_builder!(overflow=panic, "aaaa bbbb").a(0b1_0000).build();
+}
@@ -0,0 +1,30 @@
+extern crate splitbits;
+
+use splitbits::matches_template;
+
+#[test]
+fn matches() {
+    assert!(matches_template!(0b1010_0110u8, "1010 ...."));
+    assert!(matches_template!(0b1010_0110u8, "1.1. .11."));
+    assert!(!matches_template!(0b1010_0110u8, "1011 ...."));
+    assert!(!matches_template!(0b1010_0110u8, ".... ...1"));
+}
+
+#[test]
+fn matches_ignores_names() {
+    assert!(matches_template!(0b1010_0110u8, "1010 aaaa"));
+    assert!(matches_template!(0xFFFF_0000u32, "aaaa aaaa aaaa aaaa 0000 0000 0000 0000"));
+}
+
+#[test]
+fn matches_without_literals() {
+    assert!(matches_template!(0u16, ".... .... .... ...."));
+}
+
+#[test]
+fn matches_wide() {
+    let value: u128 = 0xCAFE << 112;
+    assert!(matches_template!(value,
+        "1100 1010 1111 1110 .... .... .... .... .... .... .... .... .... .... .... .... \
+         .... .... .... .... .... .... .... .... .... .... .... .... .... .... .... ...."));
+}
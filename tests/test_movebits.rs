@@ -0,0 +1,59 @@
+extern crate splitbits;
+
+use proptest::prelude::*;
+use splitbits::movebits;
+
+#[test]
+fn nibble_swap() {
+    let swapped = movebits!(0b1011_0010u8, "hhhh llll", "llll hhhh");
+    assert_eq!(swapped, 0b0010_1011);
+}
+
+#[test]
+fn byte_swap() {
+    let swapped = movebits!(0x1234_5678u32,
+        "aaaa aaaa bbbb bbbb cccc cccc dddd dddd",
+        "dddd dddd cccc cccc bbbb bbbb aaaa aaaa");
+    assert_eq!(swapped, 0x7856_3412);
+}
+
+#[test]
+fn reverse() {
+    let reversed = movebits!(0b1100_1010u8, "abcd efgh", "hgfe dcba");
+    assert_eq!(reversed, 0b0101_0011);
+}
+
+#[test]
+fn relocate_with_literals() {
+    let relocated = movebits!(0b1010_0101u8, ".... .fff", "1fff 0000");
+    assert_eq!(relocated, 0b1101_0000);
+}
+
+#[test]
+fn widen() {
+    let widened = movebits!(0b1011_0010u8, "aaaa bbbb", "bbbb 0000 0000 aaaa");
+    assert_eq!(widened, 0b0010_0000_0000_1011u16);
+}
+
+proptest! {
+    #[test]
+    fn reverse_matches_reverse_bits(value: u8) {
+        let reversed = movebits!(value, "abcd efgh", "hgfe dcba");
+        prop_assert_eq!(reversed, value.reverse_bits());
+    }
+
+    #[test]
+    fn byte_swap_matches_swap_bytes(value: u32) {
+        let swapped = movebits!(value,
+            "aaaa aaaa bbbb bbbb cccc cccc dddd dddd",
+            "dddd dddd cccc cccc bbbb bbbb aaaa aaaa");
+        prop_assert_eq!(swapped, value.swap_bytes());
+    }
+
+    #[test]
+    fn permutation_round_trip(value: u16) {
+        let permuted = movebits!(value, "abcd efgh ijkl mnop", "pfkb oaje ncgl hmid");
+        let restored = movebits!(permuted, "pfkb oaje ncgl hmid", "abcd efgh ijkl mnop");
+        prop_assert_eq!(restored, value);
+    }
+}
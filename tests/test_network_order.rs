@@ -0,0 +1,75 @@
+extern crate splitbits;
+
+use splitbits::{splitbits, splithex, splitbits_header, combinebits};
+
+// The first 20 bytes of an IPv4 header (no options), in network order.
+const IPV4_HEADER: [u8; 20] = [
+    0x45, 0x00, 0x00, 0x54,
+    0x1C, 0x46, 0x40, 0x00,
+    0x40, 0x01, 0xB1, 0xE6,
+    0xC0, 0xA8, 0x00, 0x01,
+    0xC0, 0xA8, 0x00, 0xC7,
+];
+
+#[test]
+fn single_word() {
+    let word = u32::from_be_bytes([0x45, 0x00, 0x00, 0x54]);
+    let fields = splitbits!(word, "vvvv hhhh dddd ddee llll llll llll llll");
+    assert_eq!(fields.v, 4);
+    assert_eq!(fields.h, 5);
+    assert_eq!(fields.d, 0);
+    assert_eq!(fields.e, 0);
+    assert_eq!(fields.l, 84);
+}
+
+#[test]
+fn two_words_from_bytes() {
+    let input = u64::from_be_bytes(IPV4_HEADER[..8].try_into().unwrap());
+    let fields = splitbits!(input,
+        "vvvv hhhh dddd ddee llll llll llll llll iiii iiii iiii iiii fffo oooo oooo oooo");
+    assert_eq!(fields.v, 4);
+    assert_eq!(fields.h, 5);
+    assert_eq!(fields.l, 84);
+    assert_eq!(fields.i, 0x1C46);
+    assert_eq!(fields.f, 0b010);
+    assert_eq!(fields.o, 0);
+}
+
+#[test]
+fn two_words_from_words() {
+    let words: [u32; 2] = [0x4500_0054, 0x1C46_4000];
+    let first = (u64::from(words[0]) << 32) | u64::from(words[1]);
+    let second = combinebits!(words[0], words[1],
+        "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb");
+    assert_eq!(first, second);
+    assert_eq!(first, u64::from_be_bytes(IPV4_HEADER[..8].try_into().unwrap()));
+}
+
+#[test]
+fn field_spanning_words() {
+    // A field that straddles the boundary between the first and second words.
+    let input = u64::from_be_bytes([0x00, 0x00, 0x00, 0xAB, 0xCD, 0x00, 0x00, 0x00]);
+    let fields = splithex!(input, ".... ..ss ss.. ....");
+    assert_eq!(fields.s, 0xABCD);
+}
+
+#[test]
+fn full_header() {
+    // Templates must have a standard width, so the header is extracted in chunks, each taking the remaining bytes.
+    let (header, rest) = splitbits_header!(&IPV4_HEADER[..],
+        "vvvv hhhh dddd ddee llll llll llll llll iiii iiii iiii iiii fffo oooo oooo oooo");
+    let (routing, rest) = splitbits_header!(rest, "tttt tttt pppp pppp cccc cccc cccc cccc");
+    let (addresses, rest) = splitbits_header!(rest,
+        "ssss ssss ssss ssss ssss ssss ssss ssss dddd dddd dddd dddd dddd dddd dddd dddd");
+    assert!(rest.is_empty());
+    assert_eq!(header.v, 4);
+    assert_eq!(header.h, 5);
+    assert_eq!(header.l, 84);
+    assert_eq!(header.i, 0x1C46);
+    assert_eq!(header.f, 0b010);
+    assert_eq!(routing.t, 64);
+    assert_eq!(routing.p, 1);
+    assert_eq!(routing.c, 0xB1E6);
+    assert_eq!(addresses.s, 0xC0A8_0001);
+    assert_eq!(addresses.d, 0xC0A8_00C7);
+}
@@ -45,6 +45,7 @@ fn replace_segments() {
 }
 
 #[test]
+#[cfg_attr(feature = "default-panic-overflow", should_panic(expected = "too big for its location"))]
 fn replace_too_big() {
     let a = 0b110u16;
     let b = 0b00001u8;
@@ -95,6 +96,26 @@ fn replace_too_big_corrupt() {
     assert_eq!(result,                          0b1010_0001_1000_0101u16);
 }
 
+#[test]
+fn replace_too_big_per_field() {
+    let a = 0b110u16;
+    let b = 0b00001u8;
+    let c = 0b0101u128;
+    let d = false;
+    let result = replacebits!(overflow(a = saturate, c = panic), 0b0001_1010_1100_1111u16, ".aab bbbb .d.. cccc");
+    assert_eq!(result,                                            0b0110_0001_1000_0101u16);
+}
+
+#[test]
+#[should_panic(expected = "Variable a is too big for its location in the template. 0b110 > 0b11")]
+fn replace_too_big_per_field_panic() {
+    let a = 0b110u16;
+    let b = 0b00001u8;
+    let c = 0b0101u128;
+    let d = false;
+    let _ = replacebits!(overflow(b = saturate, a = panic), 0b0001_1010_1100_1111u16, ".aab bbbb .d.. cccc");
+}
+
 #[test]
 fn replacehex_ux() {
     let a = u4::new(0xE);
@@ -103,3 +124,41 @@ fn replacehex_ux() {
     let result = replacehex!(0xABCD_EF01_2345_6789, "0a.. cc.b bbbb bb1D");
     assert_eq!(result,       0x0ECD_2A09_0210_AB1Du64);
 }
+
+// Only the low bits covered by the template are replaced. The upper bits of the target are kept.
+#[test]
+fn replace_wider_target() {
+    let a: u8 = 0b0101;
+    let b: u8 = 0b0011;
+    let target: u32 = 0xDEAD_BE00;
+    let result = replacebits!(width=u32, target, "aaaabbbb");
+    assert_eq!(result, 0xDEAD_BE53);
+}
+
+#[test]
+fn replace_wider_target_with_placeholders_and_literals() {
+    let a: u8 = 0b11;
+    let result = replacebits!(width=u64, 0xFFFF_FFFF_0000_00FF, "..aa 01..");
+    assert_eq!(result,                   0xFFFF_FFFF_0000_00F7u64);
+}
+
+#[test]
+fn placeholder() {
+    let a: u8 = 0b101;
+    let result = replacebits!(placeholder='-', 0b1000_0001u8, "-aaa ----");
+    assert_eq!(result, 0b1101_0001);
+}
+
+#[test]
+fn clear_unused() {
+    let a: u8 = 0b101;
+    let result = replacebits!(clear_unused=true, 0b1111_1111u8, ".aaa 1..0");
+    assert_eq!(result,                          0b0101_1000);
+}
+
+#[test]
+fn clear_unused_wider_target() {
+    let a: u8 = 0b11;
+    let result = replacebits!(width=u64, clear_unused=true, 0xFFFF_FFFF_0000_00FF, "..aa 01..");
+    assert_eq!(result,                                      0x0000_0000_0000_0034u64);
+}
@@ -0,0 +1,42 @@
+extern crate splitbits;
+
+use splitbits::replacebits_swap;
+
+#[test]
+fn swap() {
+    let a = 0b101u16;
+    let b = 0b00001u8;
+    let d = false;
+    let (result, (old_a, old_b, old_d)) = replacebits_swap!(0b1001_1010_1100_1111u16, "aaab bbbb .d.. ....");
+    assert_eq!(result,                                                               0b1010_0001_1000_1111u16);
+    assert_eq!(old_a, 0b100u8);
+    assert_eq!(old_b, 0b11010u8);
+    assert!(old_d);
+}
+
+#[test]
+fn swap_single_field() {
+    let a = 0b11u8;
+    let (result, old_a) = replacebits_swap!(0b1001_0110u8, "..aa ....");
+    assert_eq!(result,                                     0b1011_0110u8);
+    assert_eq!(old_a, 0b01u8);
+}
+
+#[test]
+fn swap_target_evaluated_once() {
+    let mut reads = 0;
+    let mut read = || { reads += 1; 0b1001_0110u8 };
+    let a = 0b11u8;
+    let (result, old_a) = replacebits_swap!(read(), "aaa. ....");
+    assert_eq!(result, 0b0111_0110u8);
+    assert_eq!(old_a, 0b100u8);
+    assert_eq!(reads, 1);
+}
+
+#[test]
+fn swap_wider_target() {
+    let a = 0b1111u8;
+    let (result, old_a) = replacebits_swap!(width=u16, 0xABCDu16, "aaaa ....");
+    assert_eq!(result, 0xABFDu16);
+    assert_eq!(old_a, 0xCu8);
+}
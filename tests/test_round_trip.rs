@@ -0,0 +1,70 @@
+extern crate splitbits;
+
+use proptest::prelude::*;
+use splitbits::{splitbits, combinebits, splitbits_then_combine, splithex_then_combine};
+
+// Templates are fixed at compile time, so only the values are randomized.
+proptest! {
+    #[test]
+    fn combine_then_split_u8(a in 0..=0b111u8, b in 0..=0b11u8, c in 0..=0b111u8) {
+        let combined = combinebits!(a, b, c, "aaab bccc");
+        let fields = splitbits!(combined, "aaab bccc");
+        prop_assert_eq!((fields.a, fields.b, fields.c), (a, b, c));
+    }
+
+    #[test]
+    fn combine_then_split_multiple_segments(a in 0..=0xFu8, b in 0..=0xFu8, c in 0..=0x7Fu8, d: bool) {
+        let combined = combinebits!(a, b, c, d, "aabb bbaa cccc cccd");
+        let fields = splitbits!(combined, "aabb bbaa cccc cccd");
+        prop_assert_eq!((fields.a, fields.b, fields.c, fields.d), (a, b, c, d));
+    }
+
+    #[test]
+    fn combine_then_split_literals(a in 0..=0xFu8, b: u8, c in 0..=0xFFFu16) {
+        let combined = combinebits!(a, b, c, "aaaa 1010 bbbb bbbb 0000 cccc cccc cccc");
+        prop_assert_eq!(combined & 0x0F00_F000, 0x0A00_0000);
+        let fields = splitbits!(combined, "aaaa .... bbbb bbbb .... cccc cccc cccc");
+        prop_assert_eq!((fields.a, fields.b, fields.c), (a, b, c));
+    }
+
+    #[test]
+    fn combine_then_split_u64(a: bool, b in 0..=0x7FFF_FFFFu32, c: u32) {
+        let combined = combinebits!(a, b, c,
+            "abbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb cccc cccc cccc cccc cccc cccc cccc cccc");
+        let fields = splitbits!(combined,
+            "abbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb cccc cccc cccc cccc cccc cccc cccc cccc");
+        prop_assert_eq!((fields.a, fields.b, fields.c), (a, b, c));
+    }
+
+    #[test]
+    fn split_then_combine_u128(value: u128) {
+        let fields = splitbits!(value, "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+                                        bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+        let (a, b) = (fields.a, fields.b);
+        let combined = combinebits!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+                                     bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+        prop_assert_eq!(combined, value);
+    }
+
+    #[test]
+    fn splitbits_then_combine_identity(value: u16) {
+        let result = splitbits_then_combine!(value, "aabb bbaa cccc cccd", "aabb bbaa cccc cccd");
+        prop_assert_eq!(result, value);
+    }
+
+    #[test]
+    fn splithex_then_combine_identity(value: u32) {
+        let result = splithex_then_combine!(value, "abbc cccd", "abbc cccd");
+        prop_assert_eq!(result, value);
+    }
+
+    #[test]
+    fn combine_then_split_lookup(index in 0..8usize) {
+        const DIVIDERS: [u32; 8] = [100, 200, 400, 800, 1600, 3200, 6400, 12800];
+        let d = DIVIDERS[index];
+        let register = combinebits!(lookup(d = [100u32, 200, 400, 800, 1600, 3200, 6400, 12800]), "01dd d000");
+        prop_assert_eq!(register, 0b0100_0000 | ((index as u8) << 3));
+        let fields = splitbits!(lookup(d = [100u32, 200, 400, 800, 1600, 3200, 6400, 12800]), register, "..dd d...");
+        prop_assert_eq!(fields.d, d);
+    }
+}
@@ -1,6 +1,6 @@
 extern crate splitbits;
 
-use splitbits::splitbits;
+use splitbits::{splitbits, try_splitbits};
 
 #[test]
 fn u8() {
@@ -41,6 +41,7 @@ fn variable_type_inferred() {
 
 // Single bit fields should result in bools, not u8s.
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn bools() {
     let fields = splitbits!(0b11010101, "abbbcdee");
     assert_eq!(fields.a, true);
@@ -68,6 +69,7 @@ fn underscores() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn noncontinguous() {
     let fields = splitbits!(0b1101_1101, "abadadda");
     assert_eq!(fields.a, 0b1011u8);
@@ -76,6 +78,7 @@ fn noncontinguous() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn some_of_everything() {
     let fields = splitbits!(0b1111_1101, ".ad. cdd.");
     assert_eq!(fields.a, true);
@@ -100,6 +103,7 @@ fn duplicate() {
 // LARGE FIELD TESTS
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn u16() {
     let fields = splitbits!(
         0b1101110111111001,
@@ -113,6 +117,7 @@ fn u16() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn u32() {
     let fields = splitbits!(
         0b1101_1101_1000_0100_0000_0000_1111_1001,
@@ -128,6 +133,25 @@ fn u32() {
 }
 
 #[test]
+fn nonstandard_width() {
+    // A 24-bit template reads a u32, ignoring its top byte.
+    let color: u32 = 0xFF12_3456;
+    let fields = splitbits!(
+        color,
+        "rrrrrrrr gggggggg bbbbbbbb",
+    );
+
+    assert_eq!(fields.r, 0x12u8);
+    assert_eq!(fields.g, 0x34u8);
+    assert_eq!(fields.b, 0x56u8);
+
+    let fields = splitbits!(0b0110_0101u8, "aaabbbb");
+    assert_eq!(fields.a, 0b110u8);
+    assert_eq!(fields.b, 0b0101u8);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
 fn u64() {
     let fields = splitbits!(
         0b1101_1101_1000_0000_0000_0000_1111_0001_1101_1101_1000_0000_0000_0000_1101_0001,
@@ -193,6 +217,17 @@ fn min_u8() {
     assert_eq!(fields.f, 0b001u8);
 }
 
+#[test]
+fn min_qualified_path() {
+    let fields = splitbits!(min=core::primitive::u16, 0b1101_1001, "aaaa bbbc");
+    assert_eq!(fields.a, 0b1101u16);
+    assert_eq!(fields.b, 0b100u16);
+    assert_eq!(fields.c, 1u16);
+
+    let fields = splitbits!(min=std::primitive::u32, 0b1101_1001, "aaaa bbbb");
+    assert_eq!(fields.a, 0b1101u32);
+}
+
 #[test]
 fn min_u16() {
     let fields = splitbits!(
@@ -207,3 +242,838 @@ fn min_u16() {
     assert_eq!(fields.f, 0b001u16);
 }
 
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn min_bool() {
+    let fields = splitbits!(
+        min=bool,
+        0b1101110111111001,
+         "aaaaaaaaadddefff",
+    );
+    assert_eq!(fields.a, 0b110111011u16);
+
+    assert_eq!(fields.d, 0b111u8);
+    assert_eq!(fields.e, true);
+    assert_eq!(fields.f, 0b001u8);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn bools_only() {
+    let fields = splitbits!(bools_only=true, 0b1001_0000, "abcd ....");
+    assert_eq!(fields.a, true);
+    assert_eq!(fields.b, false);
+    assert_eq!(fields.c, false);
+    assert_eq!(fields.d, true);
+}
+
+// Inversion must be limited to the width of the field, not the width of its type.
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn invert() {
+    let fields = splitbits!(invert(a, c), 0b1011_0110, "aaab bbcd");
+    assert_eq!(fields.a, 0b010u8);
+    assert_eq!(fields.b, 0b101u8);
+    assert_eq!(fields.c, false);
+    assert_eq!(fields.d, false);
+}
+
+#[test]
+fn invert_multiple_segments() {
+    let fields = splitbits!(invert(a), 0b1011_0110_0000_1111, "aaaa .... .... aaaa");
+    assert_eq!(fields.a, 0b0100_0000u8);
+}
+
+#[test]
+fn invert_with_min() {
+    let fields = splitbits!(min=u16, invert(a, b), 0b1011_0110, "aaab ....");
+    assert_eq!(fields.a, 0b010u16);
+    assert_eq!(fields.b, 0b0u16);
+}
+
+// A single field spanning all 128 bits must not overflow the mask computation.
+#[test]
+fn u128_full_width() {
+    let value: u128 = 0xFEDC_BA98_7654_3210_0123_4567_89AB_CDEF;
+    let fields = splitbits!(
+        value,
+        "aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa \
+         aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa aaaaaaaa",
+    );
+    assert_eq!(fields.a, value);
+}
+
+#[test]
+fn align_low() {
+    let value: u32 = 0xABCD_1234;
+    let fields = splitbits!(align=low, value, "aaaa bbbb");
+    assert_eq!(fields.a, 0x3u8);
+    assert_eq!(fields.b, 0x4u8);
+}
+
+#[test]
+fn align_high() {
+    let value: u32 = 0xABCD_1234;
+    let fields = splitbits!(align=high, value, "aaaa bbbb");
+    assert_eq!(fields.a, 0xAu8);
+    assert_eq!(fields.b, 0xBu8);
+
+    let fields = splitbits!(align=high, value, "aaaa aaaa aaaa bbbb");
+    assert_eq!(fields.a, 0xABCu16);
+    assert_eq!(fields.b, 0xDu8);
+}
+
+// Aligning high when the template is as wide as the input shouldn't shift anything.
+#[test]
+fn align_high_full_width() {
+    let value: u8 = 0b1101_1101;
+    let fields = splitbits!(align=high, value, "aaab bccc");
+    assert_eq!(fields.a, 0b110u8);
+    assert_eq!(fields.b, 0b11u8);
+    assert_eq!(fields.c, 0b101u8);
+}
+
+#[test]
+fn char() {
+    let fields = splitbits!(char(a), 0x41_62u16, "aaaa aaaa bbbb bbbb");
+    assert_eq!(fields.a, 'A');
+    assert_eq!(fields.b, 0x62u8);
+}
+
+// Six-bit packed text, offset to start at the space character.
+#[test]
+fn char_offset() {
+    let value: u32 = 0x33C0_FC80;
+    let call = splitbits!(char(a = 0x20, b = 0x20, c = 0x20, d = 0x20), value, "..aa aaaa bbbb bbcc cccc dddd dd.. ....");
+    assert_eq!([call.a, call.b, call.c, call.d], ['S', 'P', '/', 'R']);
+}
+
+#[test]
+fn char_wide() {
+    let fields = splitbits!(char(a), 0x0001_F980u32, "...a aaaa aaaa aaaa aaaa aaaa .... ....");
+    assert_eq!(fields.a, '\u{1F9}');
+    let fields = splitbits!(char(a = 0x1F900), 0x80u16, ".... .... aaaa aaaa");
+    assert_eq!(fields.a, '\u{1F980}');
+}
+
+#[test]
+#[should_panic(expected = "Field 'a' must be a valid char, but had code point 0xD800.")]
+fn char_invalid() {
+    let _ = splitbits!(char(a), 0xD800u16, "aaaa aaaa aaaa aaaa");
+}
+
+#[test]
+fn char_range() {
+    let fields = splitbits!(char(a), range(a = 'A'..='T'), 0x51u8, "aaaa aaaa");
+    assert_eq!(fields.a, 'Q');
+}
+
+#[test]
+fn signed() {
+    let fields = splitbits!(signed(a, c), 0b1110_1101, "aaab bbbc");
+    assert_eq!(fields.a, -1i8);
+    assert_eq!(fields.b, 0b0110u8);
+    assert_eq!(fields.c, -1i8);
+
+    let fields = splitbits!(signed(a, b), 0x7_0000_0001u64,
+        ".... .... .... .... .... .... .... aaab bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb");
+    assert_eq!(fields.a, 3i8);
+    assert_eq!(fields.b, -0xFFFF_FFFFi64);
+}
+
+#[test]
+fn signed_multi_char_names() {
+    let fields = splitbits!(signed(delta), 0b1110_1101, "<delta><delta><delta>b bbbb");
+    assert_eq!(fields.delta, -1i8);
+    assert_eq!(fields.b, 0b0_1101u8);
+}
+
+#[test]
+fn signed_full_width() {
+    let fields = splitbits!(signed(a), 0xFFFEu16, "aaaa aaaa aaaa aaaa");
+    assert_eq!(fields.a, -2i16);
+}
+
+#[test]
+fn signed_min() {
+    let fields = splitbits!(signed(a), min=i16, 0b1001_0101, "aaab bbbb");
+    assert_eq!(fields.a, -4i16);
+    assert_eq!(fields.b, 0b10101u16);
+}
+
+#[test]
+#[should_panic(expected = "Field 'a' must be within its range (- 2 ..= 1), but was -4.")]
+fn signed_range() {
+    let _ = splitbits!(signed(a), range(a = -2..=1), 0b1000_0000u8, "aaa. ....");
+}
+
+#[test]
+fn map_into() {
+    #[derive(PartialEq, Debug)]
+    struct Meters(u16);
+
+    let fields = splitbits!(map_into(d = core::time::Duration::from_millis, m = Meters as Meters),
+        0x0301_2C05u32, "dddd dddd dddd dddd mmmm mmmm mmmm ....");
+    assert_eq!(fields.d, core::time::Duration::from_millis(0x0301));
+    assert_eq!(fields.m, Meters(0x2C0));
+}
+
+#[test]
+fn rotate_input() {
+    let fields = splitbits!(rotate_input=4, 0x2345u16, "aaaa bbbb bbbb bbbb");
+    assert_eq!(fields.a, 0x5);
+    assert_eq!(fields.b, 0x234);
+
+    // The rotation wraps within the template, not within the wider input.
+    let input: u32 = 0x0000_1234;
+    let fields = splitbits!(rotate_input=4, input, "aaaa bbbb bbbb bbbb");
+    assert_eq!(fields.a, 0x4);
+    assert_eq!(fields.b, 0x123);
+}
+
+#[test]
+fn rotate_input_recombine() {
+    let value: u16 = 0x1234;
+    let fields = splitbits!(rotate_input=4, rest=true, value, "aaaa .... bbbb ....");
+    assert_eq!(fields.a, 0x4u8);
+    assert_eq!(fields.b, 0x2u8);
+    assert_eq!(fields.rest, 0x0103);
+    assert_eq!(fields.recombine(), value);
+}
+
+#[test]
+fn field_order() {
+    let value: u16 = 0b1011_0000_0110_0001;
+    let fields = splitbits!(value, "aaaa bbbb aaaa cccc");
+    assert_eq!(fields.a, 0b1011_0110);
+    assert_eq!(fields.c, 0b0001);
+
+    let fields = splitbits!(field_order(a = lsb_first, c = msb_first), value, "aaaa bbbb aaaa cccc");
+    assert_eq!(fields.a, 0b0110_1011);
+    assert_eq!(fields.c, 0b0001);
+}
+
+// Segments of different widths keep their own bit order, only their order within the field changes.
+#[test]
+fn field_order_uneven_segments() {
+    let fields = splitbits!(field_order(a = lsb_first), 0b1_0000_011u8, "abbb baaa");
+    assert_eq!(fields.a, 0b0111);
+    let fields = splitbits!(field_order(a = lsb_first), 0b1010_0101u8, "aabb bbaa");
+    assert_eq!(fields.a, 0b0110);
+}
+
+#[test]
+fn template_file() {
+    let status = splitbits!(0b1010_0101_1100_0011u16, template_file = "tests/templates/status.tmpl");
+    assert_eq!(status.e, 0b1010_0101);
+    assert_eq!(status.s, 0b1100_0011);
+}
+
+#[test]
+fn bit_order_lsb_in_byte() {
+    let value: u32 = 0x1234_5678;
+    let fields = splitbits!(bit_order=lsb_in_byte, value, "aaaa aaaa bbbb bbbb cccc cccc cccc cccc");
+    assert_eq!(fields.a, 0x48u8);
+    assert_eq!(fields.b, 0x2Cu8);
+    assert_eq!(fields.c, 0x6A1Eu16);
+}
+
+// The bits are reversed within each byte of the whole input, before the template is aligned.
+#[test]
+fn bit_order_lsb_in_byte_align_high() {
+    let value: u32 = 0x1234_5678;
+    let fields = splitbits!(bit_order=lsb_in_byte, align=high, value, "aaaa bbbb");
+    assert_eq!(fields.a, 0x4u8);
+    assert_eq!(fields.b, 0x8u8);
+}
+
+#[test]
+fn bit_order_lsb_in_byte_recombine() {
+    let value: u16 = 0x1234;
+    let fields = splitbits!(bit_order=lsb_in_byte, rest=true, value, "aaaa .... bbbb ....");
+    assert_eq!(fields.a, 0x4u8);
+    assert_eq!(fields.b, 0x2u8);
+    assert_eq!(fields.rest, 0x080C);
+    assert_eq!(fields.recombine(), value);
+}
+
+#[test]
+fn diff() {
+    let mut previous = None;
+    let mut changes = Vec::new();
+    for value in [0b1101_1101u8, 0b1101_1101, 0b1100_1101, 0b0010_0010] {
+        let fields = splitbits!(diff=true, value, "aaab bccc");
+        if let Some(previous) = previous {
+            changes.push(fields.diff(&previous).collect::<Vec<_>>());
+        }
+
+        previous = Some(fields);
+    }
+
+    assert_eq!(changes, [vec![], vec!["b"], vec!["a", "b", "c"]]);
+}
+
+#[derive(PartialEq, Debug)]
+enum Flag {
+    Zero,
+    One,
+    Three,
+}
+
+#[test]
+fn flags_enum() {
+    let fields = splitbits!(flags_enum(f = [Flag::Zero, Flag::One, _, Flag::Three]), 0b1011_0000u8, "ffff ....");
+    assert_eq!(fields.f, 0b1011);
+    assert_eq!(fields.iter_set_f().collect::<Vec<_>>(), [Flag::Zero, Flag::One, Flag::Three]);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn flags_enum_single_bit() {
+    let fields = splitbits!(flags_enum(a = [Flag::One]), 0b1000_0000u8, "a... ....");
+    assert_eq!(fields.a, true);
+    assert_eq!(fields.iter_set_a().collect::<Vec<_>>(), [Flag::One]);
+}
+
+#[test]
+fn conversion_masked() {
+    let fields = splitbits!(conversion=masked, invert(c), 0b1101_1001_0110_0001u16, "aaaa aaaa abbb bccc");
+    assert_eq!(fields.a, 0b1_1011_0010u16);
+    assert_eq!(fields.b, 0b1100u8);
+    assert_eq!(fields.c, 0b110u8);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn conversion_checked() {
+    let fields = splitbits!(conversion=checked, 0b1101_1001, "aaab bbbc");
+    assert_eq!(fields.a, 0b110u8);
+    assert_eq!(fields.b, 0b1100u8);
+    assert_eq!(fields.c, true);
+}
+
+#[test]
+fn ratio() {
+    let fields = splitbits!(ratio(a, b = f64, c), 0b1000_0000_0000_1001u16, "aaaa aaaa aabb bbbc");
+    assert_eq!(fields.a, 0b10_0000_0000 as f32 / 1023.0);
+    assert_eq!(fields.b, 0b0_0100 as f64 / 31.0);
+    assert_eq!(fields.c, 1.0f32);
+}
+
+#[test]
+fn ratio_inverted() {
+    let fields = splitbits!(ratio(a), invert(a), 0b0000_0000, "aaaa ....");
+    assert_eq!(fields.a, 1.0);
+}
+
+#[test]
+fn range() {
+    let date = splitbits!(range(m = 1..=12, d = 1..=31), 0b0101_1000_1001_0000u16, "yyyy yyym mmmd dddd");
+    assert_eq!(date.y, 44);
+    assert_eq!(date.m, 4);
+    assert_eq!(date.d, 16);
+}
+
+#[test]
+#[should_panic(expected = "Field 'm' must be within its range (1 ..= 12), but was 13.")]
+fn range_exceeded() {
+    let _ = splitbits!(range(m = 1..=12, d = 1..=31), 0b0101_1001_1011_0000u16, "yyyy yyym mmmd dddd");
+}
+
+#[test]
+#[should_panic(expected = "Field 'd' must be within its range (1 ..= 31), but was 0.")]
+fn range_below_start() {
+    let _ = splitbits!(range(m = 1..=12, d = 1..=31), 0b0101_1000_1000_0000u16, "yyyy yyym mmmd dddd");
+}
+
+#[test]
+fn range_ratio() {
+    let fields = splitbits!(ratio(v), range(v = 0.0..0.5), 0b0011_0000, "vvvv ....");
+    assert_eq!(fields.v, 0.2);
+}
+
+#[test]
+fn bytes() {
+    let value: u64 = 0x0123_4567_89AB_CDEF;
+    let fields = splitbits!(bytes(a, c), value, "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa \
+        bbbb bbbb bbbb bbbb cccc cccc cccc cccc");
+    assert_eq!(fields.a, [0x01, 0x23, 0x45, 0x67]);
+    assert_eq!(fields.b, 0x89AB);
+    assert_eq!(fields.c, [0xCD, 0xEF]);
+}
+
+#[test]
+fn bytes_with_min() {
+    let fields = splitbits!(bytes(a), min=u32, 0b1011_0110u8, "aaaa ....");
+    assert_eq!(fields.a, [0, 0, 0, 0b1011]);
+}
+
+#[test]
+fn order() {
+    let (c, a, b) = splitbits!(order(c, a, b), output=tuple, 0b1011_0110, "aaab bbcc");
+    assert_eq!(a, 0b101);
+    assert_eq!(b, 0b101);
+    assert_eq!(c, 0b10);
+
+    let values = splitbits!(order(b, a), output=array, 0b1011_0110, "aaaa bbbb");
+    assert_eq!(values, [0b0110, 0b1011]);
+}
+
+#[test]
+fn rest() {
+    let value: u32 = 0xDEAD_BEEF;
+    let mut fields = splitbits!(rest=true, value, "aaaa .... bbbb .... .... c... dddd dddd");
+    assert_eq!(fields.rest, 0x0E0D_B600);
+    assert_eq!(fields.recombine(), value);
+
+    fields.c = false;
+    fields.d = 0x12;
+    assert_eq!(fields.recombine(), 0xDEAD_B612);
+}
+
+#[test]
+fn rest_with_min() {
+    let fields = splitbits!(rest=true, min=u16, 0b1011_0110u8, "a..b bb..");
+    assert_eq!(fields.a, 1u16);
+    assert_eq!(fields.rest, 0b0010_0010);
+    assert_eq!(fields.recombine(), 0b1011_0110);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn literal_input_is_const() {
+    // Fields of a literal input are computed during macro expansion, so they are usable as consts.
+    const A: u8 = splitbits!(0b1011_0110, "aaaa bbbc").a;
+    const C: bool = splitbits!(0b1011_0110, "aaaa bbbc").c;
+    assert_eq!(A, 0b1011);
+    assert_eq!(C, false);
+}
+
+#[test]
+fn literal_input_matches_runtime() {
+    let value: u32 = 0xDEAD_BEEF;
+    let runtime = splitbits!(invert(c), min=u16, value, "aaaa .... bbbb .... .... c... ddbb dddd");
+    let folded = splitbits!(invert(c), min=u16, 0xDEAD_BEEF, "aaaa .... bbbb .... .... c... ddbb dddd");
+    assert_eq!(folded.a, runtime.a);
+    assert_eq!(folded.b, runtime.b);
+    assert_eq!(folded.c, runtime.c);
+    assert_eq!(folded.d, runtime.d);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn multi_char_names() {
+    let fields = splitbits!(0b1011_0110, "<mode><mode>aa <flag>bbb");
+    assert_eq!(fields.mode, 0b10);
+    assert_eq!(fields.a, 0b11);
+    assert_eq!(fields.flag, false);
+    assert_eq!(fields.b, 0b110);
+}
+
+#[test]
+fn multi_char_names_with_underscores() {
+    let fields = splitbits!(0b1011_0110, "<op_code><op_code>.. <op><op><op><op>");
+    assert_eq!(fields.op_code, 0b10);
+    assert_eq!(fields.op, 0b0110);
+}
+
+#[test]
+fn multi_char_names_with_settings() {
+    let fields = splitbits!(invert(a), range(mode = 0..3), 0b1011_0110, "<mode><mode>aa ....");
+    assert_eq!(fields.mode, 0b10);
+    assert_eq!(fields.a, 0b00);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn multi_char_names_invert() {
+    let fields = splitbits!(invert(mode, a), 0b1011_0110, "<mode><mode>aa <flag>bbb");
+    assert_eq!(fields.mode, 0b01);
+    assert_eq!(fields.a, 0b00);
+    assert_eq!(fields.flag, false);
+}
+
+#[test]
+fn multi_char_names_array_template() {
+    let pixel = splitbits!(0b1000_0111_1110_0001u16, [red = 5, green = 6, blue = 5]);
+    assert_eq!(pixel.red, 0b10000);
+    assert_eq!(pixel.green, 0b11_1111);
+    assert_eq!(pixel.blue, 0b00001);
+}
+
+#[test]
+fn array_template() {
+    let pixel = splitbits!(0b1000_0111_1110_0001u16, [r = 5, g = 6, b = 5]);
+    assert_eq!(pixel.r, 0b10000);
+    assert_eq!(pixel.g, 0b11_1111);
+    assert_eq!(pixel.b, 0b00001);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn array_template_with_segments() {
+    let fields = splitbits!(0b1011_0110u8, ["..", a = 3, "..", b = 1]);
+    assert_eq!(fields.a, 0b110);
+    assert_eq!(fields.b, false);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn default() {
+    let mut fields = splitbits!(default=0xDEAD_BEEF, 0u32, "aaaa .... bbbb .... .... c... dddd dddd");
+    assert_eq!(fields.a, 0);
+    fields = Default::default();
+    assert_eq!(fields.a, 0xD);
+    assert_eq!(fields.b, 0xA);
+    assert_eq!(fields.c, true);
+    assert_eq!(fields.d, 0xEF);
+}
+
+#[test]
+fn default_with_rest_and_raw() {
+    const RESET: u16 = 0b1010_0110_1100_0011;
+    let mut fields = splitbits!(default=RESET, rest=true, keep_raw=true, 0u16, "aaa. .... ..bb ....");
+    fields = Default::default();
+    assert_eq!(fields.a, 0b101);
+    assert_eq!(fields.b, 0b00);
+    assert_eq!(fields.rest, 0b0000_0110_1100_0011);
+    assert_eq!(fields.raw(), RESET);
+    assert_eq!(fields.recombine(), RESET);
+}
+
+#[test]
+fn keep_raw() {
+    let value: u32 = 0xDEAD_BEEF;
+    let fields = splitbits!(keep_raw=true, value, "aaaa .... bbbb .... .... c... dddd dddd");
+    assert_eq!(fields.a, 0xD);
+    assert_eq!(fields.raw(), value);
+}
+
+#[test]
+fn keep_raw_align_high() {
+    let fields = splitbits!(keep_raw=true, align=high, 0xBEEFu16, "aaaa ....");
+    assert_eq!(fields.a, 0xB);
+    assert_eq!(fields.raw(), 0xBEu8);
+}
+
+#[test]
+fn keep_raw_fmt() {
+    let fields = splitbits!(keep_raw=true, 0x0BEEu16, "aaaa .... bbbb ....");
+    assert_eq!(format!("{fields:x}"), "bee");
+    assert_eq!(format!("{fields:016b}"), "0000101111101110");
+}
+
+#[test]
+fn layout() {
+    let fields = splitbits!(layout=true, 0xDEAD_BEEFu32, "aaaa .... bbbb .... .... c... dddd dddd");
+    assert_eq!(fields.layout(), [("a", 4, 28), ("b", 4, 20), ("c", 1, 11), ("d", 8, 0)]);
+}
+
+#[test]
+fn layout_split_field() {
+    // A field split into multiple segments has the offset of its lowest segment.
+    let fields = splitbits!(layout=true, 0b1011_0110u8, "aabb aa..");
+    assert_eq!(fields.layout(), [("a", 4, 2), ("b", 2, 4)]);
+}
+
+#[test]
+fn layout_struct_size() {
+    // A u32, a u8, and a bool: six bytes of fields, padded to the alignment of the u32.
+    let fields = splitbits!(layout=true, 0xDEAD_BEEFu64,
+        "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa bbbb bbbb c... .... .... .... .... ....");
+    assert_eq!(fields.struct_size(), 8);
+    assert_eq!(fields.struct_padding(), 2);
+}
+
+// The compiler chooses the memory layout of the struct, so the declared field order doesn't matter.
+#[test]
+fn layout_struct_size_order() {
+    let fields = splitbits!(layout=true, order(c, a, b), 0xDEAD_BEEFu64,
+        "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa bbbb bbbb c... .... .... .... .... ....");
+    assert_eq!(fields.struct_size(), 8);
+    assert_eq!(fields.struct_padding(), 2);
+}
+
+#[test]
+fn output_array() {
+    let values = splitbits!(output=array, invert(c), 0b1011_0110, "aaaa bbbc");
+    assert_eq!(values, [0b1011, 0b011, 1]);
+
+    let value: u64 = 0xFFFF_FFFF_0000_0001;
+    let values: [u128; 3] = splitbits!(output=array, value, "aaaa aaaa .... .... .... .... bbbb bbbb \
+        .... .... .... .... .... .... .... ...c");
+    assert_eq!(values, [0xFF, 0xFF, 1]);
+}
+
+#[test]
+fn placeholder() {
+    let fields = splitbits!(placeholder='-', 0b1011_0110, "aa-- -bbb");
+    assert_eq!(fields.a, 0b10);
+    assert_eq!(fields.b, 0b110);
+
+    let fields = splitbits!(placeholder='_', 0b1011_0110, ["__", a = 3, "___"]);
+    assert_eq!(fields.a, 0b110);
+}
+
+#[test]
+fn output_fields() {
+    let fields = splitbits!(output=fields, 0b1011_0110, "aaaa bbbb");
+    assert_eq!(fields.a, 0b1011);
+    assert_eq!(fields.b, 0b0110);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn output_tuple() {
+    let (a, b, c) = splitbits!(output=tuple, invert(c), 0b1011_0110, "aaaa bbbc");
+    assert_eq!(a, 0b1011);
+    assert_eq!(b, 0b011);
+    assert_eq!(c, true);
+
+    let single = splitbits!(output=tuple, 0b1011_0110, "aaaa ....");
+    assert_eq!(single, 0b1011);
+
+    let (single,) = splitbits!(output=tuple, always_tuple=true, 0b1011_0110, "aaaa ....");
+    assert_eq!(single, 0b1011);
+}
+
+#[test]
+fn byte_separators() {
+    let fields = splitbits!(0x12_34_56u32, "........|aaaaaaaa|bbbbcccc|dddddddd");
+    assert_eq!(fields.a, 0x12);
+    assert_eq!(fields.b, 0x3);
+    assert_eq!(fields.c, 0x4);
+    assert_eq!(fields.d, 0x56);
+}
+
+#[test]
+fn repeated_groups() {
+    let fields = splitbits!(0b10_11_01_10u8, "(ab){4}");
+    assert_eq!(fields.a, 0b1101);
+    assert_eq!(fields.b, 0b0110);
+
+    // Nested groups, mixed with the rest of the template.
+    let fields = splitbits!(0b1101_1000_0110_0111u16, "(c(ab){2}){2} dd(e){4}");
+    assert_eq!(fields.a, 0b1100);
+    assert_eq!(fields.b, 0b0101);
+    assert_eq!(fields.c, 0b10);
+    assert_eq!(fields.d, 0b10);
+    assert_eq!(fields.e, 0b0111);
+}
+
+#[test]
+fn function_input_evaluated_once() {
+    let mut reads = 0;
+    let mut read = || { reads += 1; 0b1011_0110_1001_0011u16 };
+    // 'a' is split into two segments, so a naive expansion would read it three times.
+    let fields = splitbits!(read(), "aaaa bbbb aaaa cccc");
+    assert_eq!(fields.a, 0b1011_1001);
+    assert_eq!(fields.b, 0b0110);
+    assert_eq!(fields.c, 0b0011);
+    assert_eq!(reads, 1);
+}
+
+#[test]
+fn closure_input_called_once() {
+    let mut reads = 0;
+    let fields = splitbits!(|| { reads += 1; 0b1011_0110u8 }, "aaaa bbbb");
+    assert_eq!(fields.a, 0b1011);
+    assert_eq!(fields.b, 0b0110);
+    assert_eq!(reads, 1);
+}
+
+#[test]
+fn function_input_evaluated_once_for_every_output() {
+    let mut reads = 0;
+    let mut read = || { reads += 1; 0b1011_0110u8 };
+    let (a, b) = splitbits!(output=tuple, read(), "aaaa bbbb");
+    assert_eq!((a, b), (0b1011, 0b0110));
+    let values = splitbits!(output=array, read(), "aaaa bbbb");
+    assert_eq!(values, [0b1011, 0b0110]);
+    let fields = splitbits!(keep_raw=true, rest=true, read(), "aaaa ....");
+    assert_eq!(fields.raw(), 0b1011_0110);
+    assert_eq!(reads, 3);
+}
+
+#[test]
+fn warn_noncontiguous_contiguous_fields() {
+    let fields = splitbits!(warn_noncontiguous=true, 0b1101_0010u8, "aabb bbcc");
+    assert_eq!(fields.a, 0b11);
+    assert_eq!(fields.b, 0b0100);
+    assert_eq!(fields.c, 0b10);
+}
+
+#[test]
+fn warn_noncontiguous_acknowledged() {
+    let fields = splitbits!(warn_noncontiguous=true, noncontiguous(a, c), 0b1101_0010u8, "acbb bbca");
+    assert_eq!(fields.a, 0b10);
+    assert_eq!(fields.c, 0b11);
+    assert_eq!(fields.b, 0b0100);
+}
+
+#[test]
+fn group_byte() {
+    let fields = splitbits!(group=byte, 0x1234u16, "aaaaaaab bbbbbbbb");
+    assert_eq!(fields.a, 0b0001001);
+    assert_eq!(fields.b, 0b000110100);
+}
+
+// Groups of digits are checked after repeated groups are expanded.
+#[test]
+fn group_nibble_repeated() {
+    let fields = splitbits!(group=nibble, 0x12u8, "(aabb ){2}");
+    assert_eq!(fields.a, 0b0000);
+    assert_eq!(fields.b, 0b0110);
+}
+
+trait Decoded {}
+trait Named {
+    fn name(&self) -> &'static str { "fields" }
+}
+
+fn assert_decoded(_: &impl Decoded) {}
+
+#[test]
+fn trait_impl() {
+    let fields = splitbits!(trait_impl(Decoded, Named), 0b1011_0110u8, "aaaa bbbb");
+    assert_decoded(&fields);
+    assert_eq!(fields.name(), "fields");
+    assert_eq!(fields.a, 0b1011);
+}
+
+#[test]
+fn lookup() {
+    let fields = splitbits!(
+        lookup(d = [100u32, 200, 400, 800, 1600, 3200, 6400, 12800], f = [0.5f32, 2.0]),
+        0b1011_0010u8,
+        "dddf ....",
+    );
+    assert_eq!(fields.d, 3200u32);
+    assert_eq!(fields.f, 2.0f32);
+}
+
+#[test]
+fn lookup_unsuffixed() {
+    let fields = splitbits!(lookup(a = [-1, 1]), 0b1000_0000u8, "a... ....");
+    assert_eq!(fields.a, 1i32);
+
+    let fields = splitbits!(lookup(b = [0.25, 0.5, 0.75, 1.0]), 0b1011_0010u8, "aaaa ..bb");
+    assert_eq!(fields.a, 0b1011);
+    assert_eq!(fields.b, 0.75f64);
+}
+
+#[test]
+fn lookup_literal_input_is_const() {
+    const DIVIDER: u16 = splitbits!(lookup(d = [1u16, 2, 4, 8]), 0b1000_0000u8, "dd.. ....").d;
+    assert_eq!(DIVIDER, 4);
+}
+
+#[test]
+fn sign_magnitude() {
+    let fields = splitbits!(sign_magnitude(v, s), 0b1000_0101u8, "svvv vvvv");
+    assert_eq!(fields.v, -5i8);
+    let fields = splitbits!(sign_magnitude(v, s), 0b0111_1111u8, "svvv vvvv");
+    assert_eq!(fields.v, 127i8);
+    let fields = splitbits!(sign_magnitude(v, s), 0b1111_1111u8, "svvv vvvv");
+    assert_eq!(fields.v, -127i8);
+}
+
+#[test]
+fn sign_magnitude_widened() {
+    // An 8-bit magnitude plus a sign bit doesn't fit in an i8.
+    let fields = splitbits!(sign_magnitude(v, s), 0b1111_1111_1000_0000u16, "...s vvvv vvvv ....");
+    assert_eq!(fields.v, -248i16);
+
+    let sample: u32 = 0x8000_7FFF;
+    let fields = splitbits!(sign_magnitude(m, n), sample, "n... .... .... .... mmmm mmmm mmmm mmmm");
+    assert_eq!(fields.m, -0x7FFFi32);
+}
+
+#[test]
+fn sign_magnitude_with_other_fields() {
+    let fields = splitbits!(sign_magnitude(v, s), 0b1111_0011u8, "aasv vvvv");
+    assert_eq!(fields.a, 0b11);
+    assert_eq!(fields.v, -0b1_0011i8);
+}
+
+#[test]
+fn try_splitbits() {
+    let fields = try_splitbits!(0b1111_0000u8, "aaabbbbb");
+    let fields = fields.unwrap();
+    assert_eq!(fields.a, 0b111u8);
+    assert_eq!(fields.b, 0b10000u8);
+}
+
+// The inputs are evaluated in the caller's function, so '?' still applies to it.
+#[test]
+fn try_splitbits_question_mark_input() {
+    fn high(x: Option<u8>) -> Option<u8> {
+        let fields = try_splitbits!(x?, "1aaa bbbb").ok()?;
+        Some(fields.a)
+    }
+
+    assert_eq!(high(None), None);
+    assert_eq!(high(Some(0b1010_0110)), Some(0b010));
+    assert_eq!(high(Some(0b0010_0110)), None);
+}
+
+#[test]
+fn try_splitbits_range() {
+    let result = try_splitbits!(range(a = 0..=3), 0xFFu8, "aaaa bbbb");
+    assert_eq!(
+        result.map(|fields| fields.a),
+        Err("Field 'a' must be within its range (0 ..= 3), but was 15.".to_owned()),
+    );
+    let fields = try_splitbits!(range(a = 0..=3), 0x3Fu8, "aaaa bbbb").unwrap();
+    assert_eq!(fields.a, 3);
+}
+
+#[test]
+fn try_splitbits_char() {
+    let value: u16 = 0xD800;
+    let result = try_splitbits!(char(c), value, "cccc cccc cccc cccc");
+    assert_eq!(
+        result.map(|fields| fields.c),
+        Err("Field 'c' must be a valid char, but had code point 0xD800.".to_owned()),
+    );
+}
+
+#[test]
+fn literals() {
+    let opcode: u16 = 0b1010_0110_0000_1111;
+    let fields = splitbits!(opcode, "1010 aaaa 0000 bb11");
+    assert_eq!(fields.a, 0b0110u8);
+    assert_eq!(fields.b, 0b11u8);
+
+    let fields = splitbits!(0b1010_0110u8, "1010aaaa");
+    assert_eq!(fields.a, 0b0110u8);
+}
+
+#[test]
+fn literals_aligned() {
+    // The literals are checked against the same bits that the fields are extracted from.
+    let fields = splitbits!(align=high, 0xA600u16, "1010 aaaa");
+    assert_eq!(fields.a, 0x6u8);
+
+    let input: u16 = 0x006A;
+    let fields = splitbits!(rotate_input=4, input, "1010 aaaa");
+    assert_eq!(fields.a, 0x6u8);
+}
+
+#[test]
+fn try_splitbits_literals_mismatch() {
+    let opcode: u8 = 0b1110_0110;
+    let result = try_splitbits!(opcode, "1010aaaa");
+    assert_eq!(
+        result.map(|fields| fields.a),
+        Err("Input 0b11100110 doesn't match the literals of template '1010aaaa'. \
+            Expected 0b10100000 under mask 0b11110000, but found 0b11100000.".to_owned()),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected 0b10100000 under mask 0b11110000, but found 0b11100000.")]
+fn literals_mismatch() {
+    let opcode: u8 = 0b1110_0110;
+    let _ = splitbits!(opcode, "1010aaaa");
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "arbitrary-int")]
+
+extern crate splitbits;
+
+use splitbits::{splitbits_ux, splitbits_named_ux};
+use arbitrary_int::{u2, u3, u5, u9, u19};
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn arbitrary_int() {
+    let fields = splitbits_ux!(
+        0b1101_1101_1000_0100_0000_0000_1111_1001,
+         "aaaa bbbb bbbb bbbb bbbb bbbi aajj kaaa",
+    );
+
+    assert_eq!(fields.a, u9::new(0b1_1011_1001));
+    assert_eq!(fields.b, u19::new(0b110_1100_0010_0000_0000));
+    assert_eq!(fields.i, false);
+    assert_eq!(fields.j, u2::new(0b11u8));
+    assert_eq!(fields.k, true);
+}
+
+#[test]
+fn arbitrary_int_named() {
+    let (a, b) = splitbits_named_ux!(0b1111_0000u8, "aaab bbbb");
+    assert_eq!(a, u3::new(0b111));
+    assert_eq!(b, u5::new(0b1_0000));
+}
@@ -0,0 +1,30 @@
+extern crate splitbits;
+
+use splitbits::splitbits_capture;
+
+#[test]
+fn capture() {
+    let mut a: u8;
+    let mut b: bool;
+    let mut c: u8;
+    let input: u8 = 0b1011_0110;
+    splitbits_capture!(input, "aaab .ccc");
+    assert_eq!(a, 0b101);
+    assert!(b);
+    assert_eq!(c, 0b110);
+
+    // Existing values are overwritten.
+    splitbits_capture!(0b0100_1001u8, "aaab .ccc");
+    assert_eq!(a, 0b010);
+    assert!(!b);
+    assert_eq!(c, 0b001);
+}
+
+#[test]
+fn capture_min() {
+    let a: u32;
+    let b: u32;
+    splitbits_capture!(min=u32, 0b1111_0000u8, "aaab bbbb");
+    assert_eq!(a, 0b111);
+    assert_eq!(b, 0b10000);
+}
@@ -0,0 +1,37 @@
+extern crate splitbits;
+
+use splitbits::splitbits_column;
+
+#[test]
+fn column() {
+    let words: [u16; 4] = [0x1234, 0x5678, 0x9ABC, 0xDEF0];
+    let highs = splitbits_column!(words, "hhhh hhhh .... ....", h);
+    assert_eq!(highs, [0x12u8, 0x56, 0x9A, 0xDE]);
+}
+
+#[test]
+fn column_bool() {
+    let flags = splitbits_column!([0b1000_0000u8, 0b0000_0000, 0b1111_1111], "f... ....", f);
+    assert_eq!(flags, [true, false, true]);
+}
+
+#[test]
+fn column_split_field() {
+    let words: [u8; 2] = [0b1001_0110, 0b0110_1001];
+    let values = splitbits_column!(words, "aa.. ..aa", a);
+    assert_eq!(values, [0b1010, 0b0101]);
+}
+
+#[test]
+fn column_with_settings() {
+    let words: [u8; 2] = [0b1001_0110, 0b0110_1001];
+    let values = splitbits_column!(min=u32, invert(a), words, "aaaa ....", a);
+    assert_eq!(values, [0b0110u32, 0b1001]);
+}
+
+#[test]
+fn column_multi_char_name() {
+    let words: [u8; 2] = [0b1001_0110, 0b0110_1001];
+    let modes = splitbits_column!(words, "<mode><mode>.. ....", mode);
+    assert_eq!(modes, [0b10, 0b01]);
+}
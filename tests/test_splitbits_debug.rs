@@ -0,0 +1,133 @@
+extern crate splitbits;
+
+use std::process::Command;
+
+use splitbits::splitbits_debug;
+
+// Set for a test that has been rerun in a child process to print its breakdown.
+const PRINT_BREAKDOWN: &str = "SPLITBITS_PRINT_BREAKDOWN";
+
+/* Rerun the specified test in a child process and return the breakdown that it printed.
+ * A test can't capture its own stderr, so the child (which has PRINT_BREAKDOWN set) prints instead.
+ */
+fn printed_breakdown(test_name: &str) -> String {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([test_name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(PRINT_BREAKDOWN, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "Child test '{test_name}' failed: {output:?}");
+    String::from_utf8(output.stderr).unwrap()
+}
+
+// The debug variant must extract exactly the same values as splitbits!.
+#[test]
+fn debug() {
+    let fields = splitbits_debug!(0b1101_1101, "aaab bccc");
+    assert_eq!(fields.a, 0b110u8);
+    assert_eq!(fields.b, 0b11u8);
+    assert_eq!(fields.c, 0b101u8);
+}
+
+#[test]
+fn debug_printed() {
+    if std::env::var_os(PRINT_BREAKDOWN).is_some() {
+        let value: u16 = 0b1011_0110_0000_1111;
+        splitbits_debug!(min=u8, value, "aaaa .... .... aaab");
+        return;
+    }
+
+    let breakdown = printed_breakdown("debug_printed");
+    if cfg!(debug_assertions) {
+        assert_eq!(breakdown, "\
+            splitbits_debug!(min=u8, value, \"aaaa .... .... aaab\"):\n  \
+              a [15:12, 3:1] = 0b1011111 (0x5F)\n  \
+              b [0] = 0b1 (0x1)\n");
+    } else {
+        assert_eq!(breakdown, "");
+    }
+}
+
+#[test]
+fn debug_multiple_segments() {
+    let value: u16 = 0b1011_0110_0000_1111;
+    let fields = splitbits_debug!(min=u8, value, "aaaa .... .... aaab");
+    assert_eq!(fields.a, 0b101_1111u8);
+    assert_eq!(fields.b, 1u8);
+}
+
+// MSB-0 numbering only affects the printed breakdown, not the extracted values.
+#[test]
+fn debug_msb0() {
+    let fields = splitbits_debug!(bit_numbering=msb0, 0b1101_1101, "aaab bccc");
+    assert_eq!(fields.a, 0b110u8);
+    assert_eq!(fields.b, 0b11u8);
+    assert_eq!(fields.c, 0b101u8);
+}
+
+#[test]
+fn debug_msb0_printed() {
+    if std::env::var_os(PRINT_BREAKDOWN).is_some() {
+        splitbits_debug!(bit_numbering=msb0, 0b1011_0110u8, "aaaa bbcd");
+        return;
+    }
+
+    let breakdown = printed_breakdown("debug_msb0_printed");
+    if cfg!(debug_assertions) {
+        assert_eq!(breakdown, "\
+            splitbits_debug!(bit_numbering=msb0, 0b1011_0110u8, \"aaaa bbcd\"):\n  \
+              a [0:3] = 0b1011 (0xB)\n  \
+              b [4:5] = 0b1 (0x1)\n  \
+              c [6] = true\n  \
+              d [7] = false\n");
+    } else {
+        assert_eq!(breakdown, "");
+    }
+}
+
+// The layout offsets follow the bit numbering, counting to the leftmost bit of each field for MSB-0.
+#[test]
+fn debug_msb0_layout() {
+    let fields = splitbits_debug!(bit_numbering=msb0, layout=true, 0b1101_1101, "aaab bccc");
+    assert_eq!(fields.layout(), [("a", 3, 0), ("b", 2, 3), ("c", 3, 5)]);
+
+    let fields = splitbits_debug!(bit_numbering=msb0, layout=true, 0xB6u8, "aabb aa..");
+    assert_eq!(fields.layout(), [("a", 4, 0), ("b", 2, 2)]);
+
+    let fields = splitbits_debug!(layout=true, 0b1101_1101, "aaab bccc");
+    assert_eq!(fields.layout(), [("a", 3, 5), ("b", 2, 3), ("c", 3, 0)]);
+}
+
+// Fields that aren't integers are printed with their Debug formatting instead.
+#[test]
+fn debug_non_integer_fields() {
+    let fields = splitbits_debug!(char(c), bytes(b), 0x41_1234u32, "cccc cccc bbbb bbbb bbbb bbbb");
+    assert_eq!(fields.c, 'A');
+    assert_eq!(fields.b, [0x12, 0x34]);
+
+    let fields = splitbits_debug!(
+        lookup(l = [10u16, 20, 40, 80]), map_into(d = std::time::Duration::from_millis),
+        0b1000_0101u8,
+        "ll.. dddd",
+    );
+    assert_eq!(fields.l, 40);
+    assert_eq!(fields.d, std::time::Duration::from_millis(5));
+}
+
+#[test]
+fn debug_non_integer_fields_printed() {
+    if std::env::var_os(PRINT_BREAKDOWN).is_some() {
+        splitbits_debug!(char(c), bytes(b), 0x41_1234u32, "cccc cccc bbbb bbbb bbbb bbbb");
+        return;
+    }
+
+    let breakdown = printed_breakdown("debug_non_integer_fields_printed");
+    if cfg!(debug_assertions) {
+        assert_eq!(breakdown, "\
+            splitbits_debug!(char(c), bytes(b), 0x41_1234u32, \"cccc cccc bbbb bbbb bbbb bbbb\"):\n  \
+              c [23:16] = 'A'\n  \
+              b [15:0] = [18, 52]\n");
+    } else {
+        assert_eq!(breakdown, "");
+    }
+}
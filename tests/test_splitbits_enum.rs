@@ -0,0 +1,94 @@
+extern crate splitbits;
+
+use splitbits::splitbits_enum;
+
+#[derive(PartialEq, Debug)]
+enum Message {
+    Data { t: u8, l: u8, p: u16 },
+    Ack { t: u8, s: u16 },
+    Reset { t: u8 },
+}
+
+fn decode(value: u32) -> Option<Message> {
+    splitbits_enum!(Message, value, t, {
+        0x1 => "tttt llll llll .... pppp pppp pppp pppp" as Data,
+        0x2 => "tttt .... .... .... ssss ssss ssss ssss" as Ack,
+        0x3 | 0xF => "tttt .... .... .... .... .... .... ...." as Reset,
+    })
+}
+
+#[test]
+fn variants() {
+    assert_eq!(decode(0x1420_BEEF), Some(Message::Data { t: 0x1, l: 0x42, p: 0xBEEF }));
+    assert_eq!(decode(0x2FFF_1234), Some(Message::Ack { t: 0x2, s: 0x1234 }));
+    assert_eq!(decode(0xF000_0000), Some(Message::Reset { t: 0xF }));
+}
+
+#[test]
+fn unmatched_discriminant() {
+    assert_eq!(decode(0x0420_BEEF), None);
+}
+
+#[test]
+fn wildcard_arm() {
+    #[derive(PartialEq, Debug)]
+    enum Flag {
+        Set { f: bool, v: u8 },
+        Clear { f: bool },
+    }
+
+    let decode = |value: u8| splitbits_enum!(Flag, value, f, {
+        true => "fvvv vvvv" as Set,
+        _ => "f... ...." as Clear,
+    });
+    assert_eq!(decode(0b1000_0101), Some(Flag::Set { f: true, v: 0b101 }));
+    assert_eq!(decode(0b0000_0101), Some(Flag::Clear { f: false }));
+}
+
+// The input is only evaluated once, even though every arm extracts from it.
+#[test]
+fn input_evaluated_once() {
+    let mut reads = 0;
+    let mut read = || {
+        reads += 1;
+        0x2000_0007u32
+    };
+    let message = splitbits_enum!(Message, read(), t, {
+        0x1 => "tttt llll llll .... pppp pppp pppp pppp" as Data,
+        0x2 => "tttt .... .... .... ssss ssss ssss ssss" as Ack,
+    });
+    assert_eq!(message, Some(Message::Ack { t: 0x2, s: 0x7 }));
+    assert_eq!(reads, 1);
+}
+
+// Only the literals of the arm that matched are checked.
+#[test]
+fn literals_of_matched_arm() {
+    #[derive(PartialEq, Debug)]
+    enum Frame {
+        Short { k: u8, v: u8 },
+        Long { k: u8, w: u8 },
+    }
+
+    let decode = |value: u16| splitbits_enum!(Frame, value, k, {
+        0x1 => "kkkk 0000 vvvv vvvv" as Short,
+        0x2 => "kkkk 1111 wwww wwww" as Long,
+    });
+    assert_eq!(decode(0x1042), Some(Frame::Short { k: 0x1, v: 0x42 }));
+    assert_eq!(decode(0x2F42), Some(Frame::Long { k: 0x2, w: 0x42 }));
+}
+
+#[test]
+#[should_panic(expected = "doesn't match the literals of template 'kkkk0000vvvvvvvv'")]
+fn literal_mismatch() {
+    #[derive(PartialEq, Debug)]
+    enum Frame {
+        Short { k: u8, v: u8 },
+    }
+
+    let value = 0x1F42u16;
+    let frame = splitbits_enum!(Frame, value, k, {
+        0x1 => "kkkk 0000 vvvv vvvv" as Short,
+    });
+    assert_eq!(frame, Some(Frame::Short { k: 0x1, v: 0x42 }));
+}
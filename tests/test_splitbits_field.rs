@@ -0,0 +1,31 @@
+extern crate splitbits;
+
+use splitbits::splitbits_field;
+
+#[test]
+fn field() {
+    let value: u16 = 0b1011_0110_0000_0000;
+    let field = splitbits_field!(value, "a", at = 9, width = 4);
+    assert_eq!(field, 0b1011u8);
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn field_bool() {
+    let field = splitbits_field!(0b1000_0000u8, "a", at = 7, width = 1);
+    assert_eq!(field, true);
+}
+
+#[test]
+fn field_wide_input() {
+    let value: u128 = 0xABCD << 100;
+    let field = splitbits_field!(value, "a", at = 100, width = 16);
+    assert_eq!(field, 0xABCDu16);
+}
+
+#[test]
+fn field_narrow_input() {
+    let value: u8 = 0b1111_0000;
+    let field = splitbits_field!(value, "a", at = 2, width = 12);
+    assert_eq!(field, 0b11_1100u16);
+}
@@ -0,0 +1,47 @@
+extern crate splitbits;
+
+use splitbits::splitbits_header;
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn header() {
+    let message = [0b1010_0011u8, 0x12, 0x34];
+    let (header, payload) = splitbits_header!(&message[..], "aaab bbbc");
+    assert_eq!(header.a, 0b101);
+    assert_eq!(header.b, 0b0001);
+    assert_eq!(header.c, true);
+    assert_eq!(payload, [0x12, 0x34]);
+}
+
+#[test]
+fn header_big_endian() {
+    let message: Vec<u8> = vec![0xDE, 0xAD, 0xBE, 0xEF];
+    let (header, payload) = splitbits_header!(&message, "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa");
+    assert_eq!(header.a, 0xDEAD_BEEF);
+    assert!(payload.is_empty());
+}
+
+// Headers can be parsed one after another, since the remainder borrows from the input.
+#[test]
+fn header_chained() {
+    let message: &[u8] = &[0x12, 0x34, 0x56, 0x78];
+    let (first, rest) = splitbits_header!(message, "aaaa bbbb");
+    let (second, payload) = splitbits_header!(rest, "cccc cccc cccc cccc");
+    assert_eq!((first.a, first.b, second.c), (1, 2, 0x3456));
+    assert_eq!(payload, [0x78]);
+}
+
+#[test]
+fn header_nonstandard_width() {
+    let message: &[u8] = &[0x12, 0x34, 0x56, 0x78, 0x9A];
+    let (color, rest) = splitbits_header!(message, "rrrrrrrr gggggggg bbbbbbbb");
+    assert_eq!((color.r, color.g, color.b), (0x12, 0x34, 0x56));
+    assert_eq!(rest, [0x78, 0x9A]);
+}
+
+#[test]
+#[should_panic]
+fn header_too_short() {
+    let message: &[u8] = &[0x12];
+    let _ = splitbits_header!(message, "aaaa aaaa bbbb bbbb");
+}
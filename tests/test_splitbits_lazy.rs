@@ -0,0 +1,55 @@
+extern crate splitbits;
+
+use splitbits::splitbits_lazy;
+
+splitbits_lazy!(#[derive(Clone, Copy, PartialEq, Debug)] Control, "e.mm aaaa aaaa aaaa aaaa aaaa aaaa aaaa");
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn accessors() {
+    let control = Control(0b1010_0000_0000_0000_0000_0001_0010_0011);
+    assert_eq!(control.e(), true);
+    assert_eq!(control.m(), 0b10u8);
+    assert_eq!(control.a(), 0x0123u32);
+}
+
+#[test]
+fn conversions() {
+    let control = Control::from(0x8000_0001);
+    assert_eq!(control, Control(0x8000_0001));
+    assert_eq!(u32::from(control), 0x8000_0001);
+}
+
+// The accessors read the current value, since nothing is extracted ahead of time.
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn accessors_after_mutation() {
+    let mut control = Control(0);
+    assert_eq!(control.e(), false);
+    control.0 |= 0x8000_0000;
+    assert_eq!(control.e(), true);
+}
+
+mod registers {
+    splitbits::splitbits_lazy!(pub Flags, "aabb bbaa");
+}
+
+#[test]
+fn visibility_and_split_fields() {
+    let flags = registers::Flags(0b1001_0110);
+    assert_eq!(flags.a(), 0b1010);
+    assert_eq!(flags.b(), 0b0101);
+}
+
+splitbits_lazy!(Tagged, "1010 vvvv");
+
+#[test]
+#[should_panic(expected = "doesn't match the literals of template '1010vvvv'")]
+fn conversion_literal_mismatch() {
+    let _ = Tagged::from(0b0110_0101);
+}
+
+#[test]
+fn conversion_literal_match() {
+    assert_eq!(Tagged::from(0b1010_0101).v(), 0b0101);
+}
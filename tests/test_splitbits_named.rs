@@ -13,6 +13,7 @@ fn onefield() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn named() {
     let (greatest, of, all, time) = splitbits_named!(
         0b1101110111110001,
@@ -25,6 +26,7 @@ fn named() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn named_preserve_ordering() {
     let (greatest, of, all, time) = splitbits_named!(0b1000_1011, "bdda.cc.");
     assert_eq!(greatest, true);
@@ -34,6 +36,7 @@ fn named_preserve_ordering() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn named_existing_variables() {
     let greatest; let of; let all; let time;
     (greatest, of, all, time) = splitbits_named!(
@@ -45,3 +48,56 @@ fn named_existing_variables() {
     assert_eq!(all, false);
     assert_eq!(time, 0b001u8);
 }
+
+#[test]
+fn onefield_always_tuple() {
+    let (field,) = splitbits_named!(
+        always_tuple=true,
+        0b1101_1101_1000_0100_0000_0000_1111_1001,
+         ".... bbbb bbbb bbbb bbbb bbb. .... ....",
+    );
+
+    assert_eq!(field, 0b110_1100_0010_0000_0000u32);
+}
+
+// always_tuple shouldn't change anything for templates that already produce a tuple.
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn named_always_tuple() {
+    let (greatest, of, all, time) = splitbits_named!(always_tuple=true, 0b1000_1011, "bdda.cc.");
+    assert_eq!(greatest, true);
+    assert_eq!(of, 0b00u8);
+    assert_eq!(all, false);
+    assert_eq!(time, 0b01u8);
+}
+
+#[test]
+fn onefield_always_tuple_false() {
+    let field = splitbits_named!(always_tuple=false, 0b1101_1101, "..aa aa..");
+    assert_eq!(field, 0b0111u8);
+}
+
+#[test]
+fn named_align_high() {
+    let value: u16 = 0b1101_1101_0000_0000;
+    let (greatest, of) = splitbits_named!(align=high, value, "aaab bbb.");
+    assert_eq!(greatest, 0b110u8);
+    assert_eq!(of, 0b1110u8);
+}
+
+#[test]
+fn function_input_evaluated_once() {
+    let mut reads = 0;
+    let mut read = || { reads += 1; 0b1011_0110u8 };
+    let (kind, length) = splitbits_named!(read(), "kkkk llll");
+    assert_eq!(kind, 0b1011);
+    assert_eq!(length, 0b0110);
+    assert_eq!(reads, 1);
+}
+
+#[test]
+fn sign_magnitude() {
+    let (a, v) = splitbits_named!(sign_magnitude(v, s), 0b1111_0011u8, "aasv vvvv");
+    assert_eq!(a, 0b11);
+    assert_eq!(v, -0b1_0011i8);
+}
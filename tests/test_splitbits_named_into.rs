@@ -23,3 +23,43 @@ fn named_existing_types() {
     assert_eq!(all, 0u32);
     assert_eq!(time, 0b001u16);
 }
+
+#[test]
+fn onefield() {
+    let field: u32 = splitbits_named_into!(0b1101_1101, "..aa aa..");
+    assert_eq!(field, 0b0111u32);
+}
+
+#[test]
+fn onefield_always_tuple() {
+    let (field,): (u32,) = splitbits_named_into!(always_tuple=true, 0b1101_1101, "..aa aa..");
+    assert_eq!(field, 0b0111u32);
+}
+
+#[test]
+fn explicit_types() {
+    let (wide, flag, signed) = splitbits_named_into!(
+        types(a = u64, f = u8, s = i16),
+        0b1101_1101_1101_1101u16,
+         "aaaa aaaf ssss ssss",
+    );
+    assert_eq!(wide, 0b110_1110u64);
+    assert_eq!(flag, 1u8);
+    assert_eq!(signed, 0b1101_1101i16);
+}
+
+#[derive(PartialEq, Debug)]
+struct Count(u32);
+
+impl From<u8> for Count {
+    fn from(value: u8) -> Self {
+        Self(value.into())
+    }
+}
+
+#[test]
+fn explicit_custom_type() {
+    let (count, rest) = splitbits_named_into!(types(c = Count, r = u8), 0b1101_1101, "cccc rrrr");
+    assert_eq!(count, Count(0b1101));
+    assert_eq!(rest, 0b1101u8);
+}
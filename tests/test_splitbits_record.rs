@@ -0,0 +1,52 @@
+extern crate splitbits;
+
+use splitbits::splitbits_record;
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn record() {
+    let control: u32 = 0x8000_0040;
+    let address: u32 = 0xDEAD_BEEF;
+    let descriptor = splitbits_record!(
+        (control, "o... .... .... .... .... .... llll llll"),
+        (address, "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa"),
+    );
+    assert_eq!(descriptor.o, true);
+    assert_eq!(descriptor.l, 0x40u8);
+    assert_eq!(descriptor.a, 0xDEAD_BEEFu32);
+}
+
+// Words can have different widths from each other.
+#[test]
+fn record_mixed_widths() {
+    let record = splitbits_record!(
+        (0b1010_0101u8, "aaaa bbbb"),
+        (0xABCDu16, "cccc cccc dddd dddd"),
+    );
+    assert_eq!(record.a, 0b1010u8);
+    assert_eq!(record.b, 0b0101u8);
+    assert_eq!(record.c, 0xABu8);
+    assert_eq!(record.d, 0xCDu8);
+}
+
+// Each word is only evaluated once, even though multiple fields are extracted from it.
+#[test]
+fn record_words_evaluated_once() {
+    let words = [0x12u8, 0x34];
+    let mut reads = 0;
+    let mut read = |index: usize| {
+        reads += 1;
+        words[index]
+    };
+    let record = splitbits_record!((read(0), "aaaa bbbb"), (read(1), "cccc dddd"));
+    assert_eq!((record.a, record.b, record.c, record.d), (0x1, 0x2, 0x3, 0x4));
+    assert_eq!(reads, 2);
+}
+
+// The literals of each template are checked against that template's word.
+#[test]
+#[should_panic(expected = "doesn't match the literals of template '1010bbbb'")]
+fn record_literal_mismatch() {
+    let word = 0b0110_0101u8;
+    let _ = splitbits_record!((0xABu8, "aaaa aaaa"), (word, "1010 bbbb"));
+}
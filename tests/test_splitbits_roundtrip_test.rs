@@ -0,0 +1,33 @@
+extern crate splitbits;
+
+use splitbits::splitbits_roundtrip_test;
+
+#[test]
+fn contiguous_fields() {
+    splitbits_roundtrip_test!("aaaa bbbb");
+    splitbits_roundtrip_test!("aaab bccc dddd dddd eeee eeee eeee eeee");
+}
+
+#[test]
+fn split_fields() {
+    splitbits_roundtrip_test!("aabb bbaa");
+    splitbits_roundtrip_test!("abab abab abab abab");
+}
+
+#[test]
+fn bools() {
+    splitbits_roundtrip_test!("abcd efgh");
+}
+
+// Only the field bits are compared, so placeholders and literals don't fail the round trip.
+#[test]
+fn placeholders_and_literals() {
+    splitbits_roundtrip_test!("..aa 10bb");
+    splitbits_roundtrip_test!("<mode><mode>.. .... 1111 0000 .... ...b bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb cccc cccc");
+}
+
+#[test]
+fn wide() {
+    splitbits_roundtrip_test!("aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa \
+        bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbb bbbc");
+}
@@ -97,3 +97,38 @@ fn splithex_then_combine_with_literals() {
          "ddAB cCbb bDEF aa01 2345 6789 eeee eeee");
     assert_eq!(result, 0x67AB_5C01_2DEF_BC01_2345_6789_0000_8843);
 }
+
+#[test]
+fn splithex_then_combine_output_binary() {
+    let result = splithex_then_combine!(
+        output_base=binary,
+        0xAB, "a.",
+        0xCD, ".b",
+              "aaaa bbbb");
+    assert_eq!(result, 0xAD);
+}
+
+#[test]
+fn splitbits_then_combine_output_hexadecimal() {
+    let result = splitbits_then_combine!(
+        output_base=hexadecimal,
+        0b1010_0000u8, "aaaa aaaa",
+        0b0000_1100u8, "bbbb bbbb",
+                       "aa00 00bb");
+    assert_eq!(result, 0xA000_000C);
+}
+
+#[test]
+fn split_then_combine_downsize_template() {
+    let result = splitbits_then_combine!(0b1001_0110_1111_0000u16, "aaaa aaaa .... ....", "aaaa aaaa");
+    assert_eq!(result, 0b1001_0110u8);
+
+    let result = splitbits_then_combine!(0b1001_0110_1111_0000u16, "..aa .... aa.. ....", "00aa aa00");
+    assert_eq!(result, 0b0001_1100u8);
+
+    let result = splitbits_then_combine!(
+        0xF0F0_5678u32, ".... .... .... .... aaaa aaaa aaaa aaaa",
+                        "aaaa aaaa aaaa aaaa",
+    );
+    assert_eq!(result, 0x5678u16);
+}
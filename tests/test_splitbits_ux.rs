@@ -1,9 +1,15 @@
+#![cfg(feature = "ux")]
+
 extern crate splitbits;
 
-use splitbits::{splitbits_ux, splithex_ux, splithex_named_ux, splithex_named_into_ux};
-use ux::{u1, u2, u3, u9, u19};
+use splitbits::{
+    splitbits_ux, splitbits_named_ux, splitbits_named_into_ux, splithex_ux, splithex_named_ux,
+    splithex_named_into_ux,
+};
+use ux::{u1, u2, u3, u9, u10, u19};
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn ux() {
     let fields = splitbits_ux!(
         0b1101_1101_1000_0100_0000_0000_1111_1001,
@@ -64,3 +70,32 @@ fn min_u2() {
     assert_eq!(fields.e, u2::new(0b1u8));
     assert_eq!(fields.f, u3::new(0b001u8));
 }
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn named_ux_preserve_ordering() {
+    let (greatest, of, all, time) = splitbits_named_ux!(0b1000_1011, "bdda.cc.");
+    assert_eq!(greatest, true);
+    assert_eq!(of, u2::new(0b00));
+    assert_eq!(all, false);
+    assert_eq!(time, u2::new(0b01));
+}
+
+#[test]
+fn named_into_ux_preserve_ordering() {
+    let (greatest, of, all, time): (u2, u3, u1, u10) =
+        splitbits_named_into_ux!(min=u1, 0b1000_1011, "bdda.cc.");
+    assert_eq!(greatest, u2::new(1));
+    assert_eq!(of, u3::new(0b00));
+    assert_eq!(all, u1::new(0));
+    assert_eq!(time, u10::new(0b01));
+}
+
+#[test]
+fn named_ux_always_tuple() {
+    let (field,) = splitbits_named_ux!(always_tuple=true, 0b1101_1101, "..aa a...");
+    assert_eq!(field, u3::new(0b011));
+
+    let (field,): (u10,) = splitbits_named_into_ux!(always_tuple=true, 0b1101_1101, "..aa a...");
+    assert_eq!(field, u10::new(0b011));
+}
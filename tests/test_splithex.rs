@@ -60,3 +60,31 @@ fn onehex() {
     assert_eq!(value, 0x0db8u16);
 }
 
+
+#[test]
+fn multi_char_names() {
+    let fields = splithex!(0xBEEF, "<high><high> <low>l");
+    assert_eq!(fields.high, 0xBE);
+    assert_eq!(fields.low, 0xE);
+    assert_eq!(fields.l, 0xF);
+}
+
+#[test]
+fn byte_separators() {
+    let fields = splithex!(0x12_34_56u32, "..|aa|bc|dd");
+    assert_eq!(fields.a, 0x12);
+    assert_eq!(fields.b, 0x3);
+    assert_eq!(fields.c, 0x4);
+    assert_eq!(fields.d, 0x56);
+}
+
+#[test]
+fn group_byte() {
+    let fields = splithex!(group=byte, 0x1234u16, "ab|bc");
+    assert_eq!(fields.a, 0x1);
+    assert_eq!(fields.b, 0x23);
+    assert_eq!(fields.c, 0x4);
+
+    let fields = splithex!(group=nibble, 0x1234u16, "a b b c");
+    assert_eq!(fields.b, 0x23);
+}
@@ -0,0 +1,56 @@
+extern crate splitbits;
+
+use splitbits::{splitoct, splitoct_named, combineoct, splitoct_then_combine, replaceoct};
+
+#[test]
+fn oct() {
+    let permissions = splitoct!(0o4755u16, "sugo");
+    assert_eq!(permissions.s, 0o4u8);
+    assert_eq!(permissions.u, 0o7u8);
+    assert_eq!(permissions.g, 0o5u8);
+    assert_eq!(permissions.o, 0o5u8);
+}
+
+// Two digits are six bits, which are padded to a u8. The padding bits are ignored.
+#[test]
+fn oct_padding_ignored() {
+    let fields = splitoct!(0b1100_0101u8, "ab");
+    assert_eq!(fields.a, 0o0);
+    assert_eq!(fields.b, 0o5);
+
+    // Eleven digits are 33 bits, which are padded to a u64.
+    let fields = splitoct!(0o12345670123u64, "aaaa aaaa bbb");
+    assert_eq!(fields.a, 0o12345670u32);
+    assert_eq!(fields.b, 0o123u16);
+}
+
+#[test]
+fn oct_named() {
+    let (user, group, other) = splitoct_named!(0o0640u16, ".ugo");
+    assert_eq!(user, 0o6);
+    assert_eq!(group, 0o4);
+    assert_eq!(other, 0o0);
+}
+
+#[test]
+fn combine_oct() {
+    let u: u8 = 0o6;
+    let g: u8 = 0o4;
+    let o: u8 = 0o4;
+    assert_eq!(combineoct!("0ugo"), 0o0644u16);
+    assert_eq!(combineoct!("2ugo"), 0o2644u16);
+}
+
+#[test]
+fn split_then_combine_oct() {
+    let output = splitoct_then_combine!(0o1234u16, "abcd", "dcba");
+    assert_eq!(output, 0o4321);
+}
+
+#[test]
+fn replace_oct() {
+    let u: u8 = 0o7;
+    // The bits of the target beyond the template's padding are left in place too.
+    let output = replaceoct!(0b1010_0000_0000_0000u16, ".u..");
+    assert_eq!(output,       0b1010_0001_1100_0000u16);
+}
@@ -0,0 +1,58 @@
+extern crate splitbits;
+
+use splitbits::{template_consts, splitbits};
+
+template_consts!(pub MAGIC, "1100 1010 .... .... aaaa aaaa 0000 0001");
+template_consts!(NO_LITERALS, "aaaa bbbb");
+
+mod registers {
+    splitbits::template_consts!(pub(crate) STATUS, "01.. aaaa");
+}
+
+#[test]
+fn consts() {
+    assert_eq!(MAGIC_LITERAL_MASK, 0xFF00_00FFu32);
+    assert_eq!(MAGIC_LITERAL_VALUE, 0xCA00_0001u32);
+}
+
+#[test]
+fn no_literals() {
+    assert_eq!(NO_LITERALS_LITERAL_MASK, 0u8);
+    assert_eq!(NO_LITERALS_LITERAL_VALUE, 0u8);
+}
+
+#[test]
+fn bit_counts() {
+    assert_eq!(MAGIC_FIELD_BITS, 8);
+    assert_eq!(MAGIC_PLACEHOLDER_BITS, 8);
+    assert_eq!(MAGIC_LITERAL_BITS, 16);
+    assert_eq!(MAGIC_FIELD_BITS + MAGIC_PLACEHOLDER_BITS + MAGIC_LITERAL_BITS, u32::BITS);
+
+    assert_eq!(NO_LITERALS_FIELD_BITS, 8);
+    assert_eq!(NO_LITERALS_PLACEHOLDER_BITS, 0);
+    assert_eq!(NO_LITERALS_LITERAL_BITS, 0);
+}
+
+#[test]
+fn visibility() {
+    assert_eq!(registers::STATUS_LITERAL_MASK, 0b1100_0000u8);
+    assert_eq!(registers::STATUS_LITERAL_VALUE, 0b0100_0000u8);
+}
+
+// The consts can strip the magic bits before the rest of the value is decoded.
+#[test]
+fn strip_magic() {
+    let frame: u32 = 0xCA00_4201;
+    assert_eq!(frame & MAGIC_LITERAL_MASK, MAGIC_LITERAL_VALUE);
+    let fields = splitbits!(frame & !MAGIC_LITERAL_MASK, ".... .... .... .... aaaa aaaa .... ....");
+    assert_eq!(fields.a, 0x42);
+}
+
+template_consts!(WIDE, "1111 .... .... .... .... .... .... .... .... .... .... .... .... .... .... .... \
+    .... .... .... .... .... .... .... .... .... .... .... .... .... .... .... ...1");
+
+#[test]
+fn wide() {
+    assert_eq!(WIDE_LITERAL_MASK, (0xF << 124) | 1u128);
+    assert_eq!(WIDE_LITERAL_VALUE, (0xF << 124) | 1u128);
+}